@@ -0,0 +1,65 @@
+/**
+ * trace.rs
+ *  command line front end for trace guided code discovery. Runs a ROM
+ *  headless in the interpreter, writes the trace to -o if given, and
+ *  reports how much of the code that ran static recovery finds, with
+ *  and without the trace fed back in.
+ *
+ *  Usage: trace <rom.ch8> [--frames <n>] [--seed <n>] [--input <keys.txt> | --random-input] [-o <trace.txt>]
+ */
+use std::env;
+use std::fs;
+
+use chip8_retargeter_llvm::rom::Rom;
+use chip8_retargeter_llvm::trace::coverage;
+use difftest::{trace, InputScript};
+
+const USAGE: &str =
+    "Usage: trace <rom.ch8> [--frames <n>] [--seed <n>] [--input <keys.txt> | --random-input] [-o <trace.txt>]";
+
+pub fn main() -> Result<(), String> {
+    let args: Vec<String> = env::args().collect();
+    let rom_path = args.get(1).ok_or(USAGE)?;
+    let mut frames = 600;
+    let mut seed = 0;
+    let mut input = None;
+    let mut random = false;
+    let mut output = None;
+    let mut flags = args[2..].iter();
+    while let Some(flag) = flags.next() {
+        if flag == "--random-input" {
+            random = true;
+            continue;
+        }
+        let value = flags.next().ok_or(USAGE)?;
+        let number = || value.parse::<u64>().map_err(|_| format!("Invalid value for {}: {}", flag, value));
+        match flag.as_str() {
+            "--frames" => frames = number()?,
+            "--seed" => seed = number()?,
+            "--input" => input = Some(InputScript::from_file(value)?),
+            "-o" => output = Some(value),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let input = match input {
+        Some(_) if random => return Err(USAGE.to_string()),
+        Some(input) => input,
+        None if random => InputScript::random(seed, frames),
+        None => InputScript::empty(),
+    };
+
+    let rom = Rom::from_file(rom_path)?;
+    let traced = trace(&rom, frames, seed, &input);
+    if let Some(output) = output {
+        fs::write(output, traced.trace.to_string()).map_err(|e| format!("Cannot write {}: {}", output, e))?;
+    }
+    match &traced.stopped {
+        Some((pc, error)) => println!(
+            "Traced {} frames ({} instructions) until a fault at 0x{:03X}: {}",
+            traced.frames, traced.instructions, pc, error
+        ),
+        None => println!("Traced {} frames ({} instructions)", traced.frames, traced.instructions),
+    }
+    print!("{}", coverage(&rom, &traced.trace));
+    Ok(())
+}
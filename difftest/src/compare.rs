@@ -0,0 +1,89 @@
+/**
+ * compare.rs
+ *  this file lines up the interpreter's machine against the recompiled
+ *  program's and lists everything that differs between them.
+ */
+use std::fmt;
+
+use emulator::processor::{CpuState, Video};
+use emulator::{HIRES_HEIGHT, HIRES_WIDTH};
+
+/**
+ * Everything compared at a frame boundary
+ */
+pub struct Snapshot {
+    pub cpu: CpuState,
+    pub video: Video,
+}
+
+/**
+ * One thing that differs and its value on each side
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    pub what: String,
+    pub interpreter: String,
+    pub recompiled: String,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: interpreter {}, recompiled {}", self.what, self.interpreter, self.recompiled)
+    }
+}
+
+/**
+ * differ records a difference if two values are not equal
+ */
+fn differ<T: PartialEq + fmt::UpperHex>(differences: &mut Vec<Difference>, what: String, interpreter: T, recompiled: T) {
+    if interpreter != recompiled {
+        differences.push(Difference {
+            what,
+            interpreter: format!("0x{:02X}", interpreter),
+            recompiled: format!("0x{:02X}", recompiled),
+        });
+    }
+}
+
+/**
+ * compare lists the differences between two machines. Memory and the
+ *    screen are reported by their first differing byte or pixel and how
+ *    many differ in all.
+ */
+pub fn compare(interpreter: &Snapshot, recompiled: &Snapshot) -> Vec<Difference> {
+    let (a, b) = (&interpreter.cpu, &recompiled.cpu);
+    let mut differences = Vec::new();
+
+    differ(&mut differences, "PC".to_string(), a.pc, b.pc);
+    for x in 0..16 {
+        differ(&mut differences, format!("V{:X}", x), a.gp_registers[x], b.gp_registers[x]);
+    }
+    differ(&mut differences, "I".to_string(), a.i, b.i);
+    differ(&mut differences, "DT".to_string(), a.dt, b.dt);
+    differ(&mut differences, "ST".to_string(), a.st, b.st);
+    differ(&mut differences, "SP".to_string(), a.sp, b.sp);
+    for level in 0..16 {
+        differ(&mut differences, format!("stack[{}]", level), a.stack[level], b.stack[level]);
+    }
+
+    let memory: Vec<usize> = (0..a.memory.len()).filter(|&addr| a.memory[addr] != b.memory[addr]).collect();
+    if let Some(&first) = memory.first() {
+        differences.push(Difference {
+            what: format!("memory[0x{:03X}] ({} bytes differ)", first, memory.len()),
+            interpreter: format!("0x{:02X}", a.memory[first]),
+            recompiled: format!("0x{:02X}", b.memory[first]),
+        });
+    }
+
+    let mut pixels = (0..HIRES_HEIGHT)
+        .flat_map(|y| (0..HIRES_WIDTH).map(move |x| (x, y)))
+        .filter(|&(x, y)| interpreter.video[y][x] != recompiled.video[y][x]);
+    if let Some((x, y)) = pixels.next() {
+        differences.push(Difference {
+            what: format!("pixel ({}, {}) ({} pixels differ)", x, y, pixels.count() + 1),
+            interpreter: interpreter.video[y][x].to_string(),
+            recompiled: recompiled.video[y][x].to_string(),
+        });
+    }
+    differences
+}
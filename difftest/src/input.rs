@@ -0,0 +1,104 @@
+/**
+ * input.rs
+ *  this file reads the keypad script both sides of a differential run
+ *  are fed. Each line gives a frame and the keys held down from that
+ *  frame on, as hex digits, until a later line changes them:
+ *
+ *  ```text
+ *      ; frame  keys
+ *      0
+ *      30       5
+ *      45       5 6
+ *      60
+ *  ```
+ */
+use std::fs;
+
+// Fewest and one more than the most frames a random key is held for
+const MIN_HOLD: u64 = 4;
+const MAX_HOLD: u64 = 30;
+
+pub struct InputScript {
+    // Frame each change happens on and the keys held from then on, sorted by frame
+    changes: Vec<(u64, [bool; 16])>,
+}
+
+impl InputScript {
+    /**
+     * empty is a script that never presses anything
+     */
+    pub fn empty() -> InputScript {
+        InputScript { changes: Vec::new() }
+    }
+
+    /**
+     * random is a script for frames frames that holds down one random key,
+     *    or none, for a random number of frames at a time. The same seed
+     *    always gives the same script.
+     */
+    pub fn random(seed: u64, frames: u64) -> InputScript {
+        // xorshift64, which must not start from zero
+        let mut state = seed ^ 0x9E37_79B9_7F4A_7C15;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let mut changes = Vec::new();
+        let mut frame = 0;
+        while frame < frames {
+            let mut keys = [false; 16];
+            // Leave the keypad alone a fifth of the time
+            let key = next() % 20;
+            if key < 16 {
+                keys[key as usize] = true;
+            }
+            changes.push((frame, keys));
+            frame += MIN_HOLD + next() % (MAX_HOLD - MIN_HOLD);
+        }
+        InputScript { changes }
+    }
+
+    pub fn from_file(filename: &str) -> Result<InputScript, String> {
+        let text = fs::read_to_string(filename).map_err(|e| format!("Cannot open {}: {}", filename, e))?;
+        InputScript::parse(&text).map_err(|e| format!("{}:{}", filename, e))
+    }
+
+    /**
+     * parse reads a script, reporting the line of the first mistake
+     */
+    pub fn parse(text: &str) -> Result<InputScript, String> {
+        let mut changes = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let code = line.split(';').next().unwrap();
+            let mut words = code.split_whitespace();
+            let frame = match words.next() {
+                Some(word) => word.parse().map_err(|_| format!("{}: Invalid frame {}", index + 1, word))?,
+                None => continue,
+            };
+            let mut keys = [false; 16];
+            for word in words {
+                match u8::from_str_radix(word, 16) {
+                    Ok(key) if key < 16 => keys[key as usize] = true,
+                    _ => return Err(format!("{}: Invalid key {}", index + 1, word)),
+                }
+            }
+            changes.push((frame, keys));
+        }
+        // Later lines win when two name the same frame
+        changes.sort_by_key(|&(frame, _)| frame);
+        Ok(InputScript { changes })
+    }
+
+    /**
+     * keys returns the keys held down during a frame
+     */
+    pub fn keys(&self, frame: u64) -> [bool; 16] {
+        self.changes
+            .iter()
+            .take_while(|&&(start, _)| start <= frame)
+            .last()
+            .map_or([false; 16], |&(_, keys)| keys)
+    }
+}
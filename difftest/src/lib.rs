@@ -0,0 +1,283 @@
+/**
+ * lib.rs
+ *  the differential testing harness. A ROM is lifted and JIT compiled
+ *  against the runtime, then run headless in lockstep with the emulator's
+ *  interpreter. Both sides get the same keys and the same random bytes,
+ *  and their registers, stack, memory and screen are compared at every
+ *  frame boundary until the first point where they disagree.
+ *
+ *  Recompiled code only writes its registers back at safepoints, so a
+ *  frame ends at the first safepoint once it has run enough instructions.
+ *  The WebAssembly backend is checked the same way, run under wasmi.
+ *
+ *  The same headless interpreter also records traces of programs running,
+ *  for the trace guided code discovery of the retargeter's cfg module.
+ */
+mod compare;
+mod input;
+mod trace;
+mod wasm;
+
+pub use compare::{compare, Difference, Snapshot};
+pub use input::InputScript;
+pub use trace::{trace, Traced};
+pub use wasm::run_wasm;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use inkwell::context::Context;
+use inkwell::targets::{InitializationConfig, Target};
+use inkwell::OptimizationLevel;
+
+use chip8_retargeter_llvm::classify::code_cfg;
+use chip8_retargeter_llvm::codegen::to_llvm;
+use chip8_retargeter_llvm::lift::lift;
+use chip8_retargeter_llvm::rom::Rom;
+use chip8_rt::{Chip8State, Status};
+use emulator::processor::CPU;
+
+pub struct Options {
+    // Frames to run before calling it a match
+    pub frames: u64,
+    // Fewest instructions in a frame
+    pub frame_length: u64,
+    // Seed for the random bytes RND draws on both sides
+    pub seed: u64,
+    pub input: InputScript,
+}
+
+/**
+ * Where the two sides first disagreed
+ */
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub frame: u64,
+    // Instructions run by then, counted from the start
+    pub instruction: u64,
+    pub differences: Vec<Difference>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    // Every frame matched
+    Matched,
+    Diverged(Divergence),
+    // The recompiled program stopped early and matched up to that point
+    Stopped(String),
+}
+
+pub struct Report {
+    // Frames that matched
+    pub frames: u64,
+    pub instructions: u64,
+    pub outcome: Outcome,
+}
+
+/**
+ * The interpreter side of the run, stepped along from the recompiled
+ * side's observer
+ */
+struct Lockstep {
+    interpreter: CPU,
+    options: Options,
+    frames: u64,
+    instructions: u64,
+    // Instructions run by the recompiled side this frame
+    pending: u64,
+    divergence: Option<Divergence>,
+}
+
+/**
+ * runtime_symbols lists every runtime function generated code may call,
+ *    for the JIT to link against
+ */
+fn runtime_symbols() -> Vec<(&'static str, usize)> {
+    vec![
+        ("chip8_rt_init", chip8_rt::chip8_rt_init as *const () as usize),
+        ("chip8_rt_run", chip8_rt::chip8_rt_run as *const () as usize),
+        ("chip8_rt_exit", chip8_rt::chip8_rt_exit as *const () as usize),
+        ("chip8_rt_tick", chip8_rt::chip8_rt_tick as *const () as usize),
+        ("chip8_rt_cls", chip8_rt::chip8_rt_cls as *const () as usize),
+        ("chip8_rt_draw", chip8_rt::chip8_rt_draw as *const () as usize),
+        ("chip8_rt_draw_sprite", chip8_rt::chip8_rt_draw_sprite as *const () as usize),
+        ("chip8_rt_key_pressed", chip8_rt::chip8_rt_key_pressed as *const () as usize),
+        ("chip8_rt_wait_key", chip8_rt::chip8_rt_wait_key as *const () as usize),
+        ("chip8_rt_random", chip8_rt::chip8_rt_random as *const () as usize),
+        ("chip8_rt_bad_address", chip8_rt::chip8_rt_bad_address as *const () as usize),
+        ("chip8_rt_bad_stack", chip8_rt::chip8_rt_bad_stack as *const () as usize),
+        ("chip8_rt_code_written", chip8_rt::chip8_rt_code_written as *const () as usize),
+        ("chip8_rt_unresolved", chip8_rt::chip8_rt_unresolved as *const () as usize),
+    ]
+}
+
+/**
+ * recompiled_snapshot copies out the recompiled side's machine
+ */
+fn recompiled_snapshot(state: &Chip8State) -> Snapshot {
+    Snapshot { cpu: state.to_cpu_state(), video: state.host.cpu.mmio.video_memory }
+}
+
+impl Lockstep {
+    fn new(rom: &Rom, options: Options) -> Lockstep {
+        let mut interpreter = CPU::from_bytes(&rom.bytes);
+        interpreter.seed_random(options.seed);
+        interpreter.mmio.input_memory = options.input.keys(0);
+        Lockstep { interpreter, options, frames: 0, instructions: 0, pending: 0, divergence: None }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot { cpu: self.interpreter.save_state(), video: self.interpreter.mmio.video_memory }
+    }
+
+    /**
+     * catch_up runs the interpreter over the instructions the recompiled
+     *    side has run since the last frame boundary, the same way the
+     *    emulator's main loop does. A fault in the interpreter comes back
+     *    as an error.
+     */
+    fn catch_up(&mut self) -> Result<(), String> {
+        let count = self.pending;
+        self.pending = 0;
+        for _ in 0..count {
+            self.interpreter.execute_next_opcode().map_err(|error| error.to_string())?;
+            self.interpreter.update_timers();
+        }
+        Ok(())
+    }
+
+    /**
+     * count adds the instructions the recompiled side has run since it
+     *    was last seen to the current frame, given how many it has run in all
+     */
+    fn count(&mut self, instructions: u64) {
+        self.pending += instructions - self.instructions;
+        self.instructions = instructions;
+    }
+
+    /**
+     * check catches the interpreter up and compares it to the recompiled
+     *    side, recording a divergence if they differ
+     */
+    fn check(&mut self, recompiled: &Snapshot) -> bool {
+        let differences = match self.catch_up() {
+            Ok(()) => compare(&self.snapshot(), recompiled),
+            Err(message) => vec![Difference {
+                what: "execution".to_string(),
+                interpreter: format!("stopped ({})", message),
+                recompiled: "running".to_string(),
+            }],
+        };
+        if differences.is_empty() {
+            return true;
+        }
+        self.divergence = Some(Divergence { frame: self.frames, instruction: self.instructions, differences });
+        false
+    }
+
+    /**
+     * end_frame checks both sides agree at the end of a frame. Returns the
+     *    next frame's keys, already handed to the interpreter, or None if
+     *    the run is over.
+     */
+    fn end_frame(&mut self, recompiled: &Snapshot) -> Option<[bool; 16]> {
+        if !self.check(recompiled) {
+            return None;
+        }
+        self.frames += 1;
+        if self.frames == self.options.frames {
+            return None;
+        }
+        let keys = self.options.input.keys(self.frames);
+        self.interpreter.mmio.input_memory = keys;
+        Some(keys)
+    }
+
+    /**
+     * stopped compares what ran of the last frame before the recompiled
+     *    side stopped for reason
+     */
+    fn stopped(&mut self, recompiled: &Snapshot, reason: String) -> Outcome {
+        if self.check(recompiled) {
+            Outcome::Stopped(reason)
+        } else {
+            Outcome::Diverged(self.divergence.take().unwrap())
+        }
+    }
+
+    /**
+     * report sums up a run that went to the end of its frames or diverged
+     */
+    fn report(&mut self) -> Report {
+        let outcome = match self.divergence.take() {
+            Some(divergence) => Outcome::Diverged(divergence),
+            None => Outcome::Matched,
+        };
+        Report { frames: self.frames, instructions: self.instructions, outcome }
+    }
+
+    /**
+     * instruction_done is the recompiled side's observer. At the end of
+     *    each frame it checks both sides agree and hands both the next
+     *    frame's keys.
+     */
+    fn instruction_done(&mut self, state: &mut Chip8State) {
+        self.count(state.host.instructions);
+        if self.pending < self.options.frame_length {
+            return;
+        }
+        match self.end_frame(&recompiled_snapshot(state)) {
+            Some(keys) => state.host.cpu.mmio.input_memory = keys,
+            None => state.host.status = Status::Quit,
+        }
+    }
+}
+
+/**
+ * run lifts a ROM, runs it recompiled and interpreted side by side and
+ *    reports how far they agreed
+ */
+pub fn run(rom: &Rom, name: &str, options: Options) -> Result<Report, String> {
+    Target::initialize_native(&InitializationConfig::default())?;
+    let context = Context::create();
+    let module = to_llvm(&context, &lift(rom, name)?)?;
+    let engine = module.create_jit_execution_engine(OptimizationLevel::None).map_err(|e| e.to_string())?;
+
+    // Generated code only reaches the runtime through its declarations
+    let symbols = runtime_symbols();
+    for function in module.get_functions().filter(|f| f.count_basic_blocks() == 0) {
+        let name = function.get_name().to_str().unwrap_or("");
+        match symbols.iter().find(|&&(symbol, _)| symbol == name) {
+            Some(&(_, addr)) => engine.add_global_mapping(&function, addr),
+            None => return Err(format!("No runtime function {}", name)),
+        }
+    }
+    let run_addr = engine.get_function_address("chip8_run").map_err(|e| e.to_string())?;
+    let chip8_run: unsafe extern "C" fn(*mut Chip8State) = unsafe { std::mem::transmute(run_addr) };
+
+    // The same table the lifter hands chip8_rt_run from main
+    let blocks: Vec<u16> = code_cfg(rom).blocks().flat_map(|block| vec![block.start, block.end()]).collect();
+
+    let lockstep = Rc::new(RefCell::new(Lockstep::new(rom, options)));
+    let observer = Rc::clone(&lockstep);
+    unsafe {
+        let state = chip8_rt::chip8_rt_init(rom.bytes.as_ptr(), rom.bytes.len() as u32);
+        let host = &mut (*state).host;
+        host.paced = false;
+        host.cpu.seed_random(lockstep.borrow().options.seed);
+        host.cpu.mmio.input_memory = lockstep.borrow().options.input.keys(0);
+        host.observer = Some(Box::new(move |state| observer.borrow_mut().instruction_done(state)));
+
+        chip8_rt::chip8_rt_run(state, chip8_run, blocks.as_ptr(), (blocks.len() / 2) as u32);
+
+        let state = Box::from_raw(state);
+        let mut lockstep = lockstep.borrow_mut();
+        if let (None, Status::Fault(message)) = (&lockstep.divergence, &state.host.status) {
+            lockstep.count(state.host.instructions);
+            let reason = format!("Fault at 0x{:03X}: {}", state.pc, message);
+            let outcome = lockstep.stopped(&recompiled_snapshot(&state), reason);
+            return Ok(Report { frames: lockstep.frames, instructions: lockstep.instructions, outcome });
+        }
+        Ok(lockstep.report())
+    }
+}
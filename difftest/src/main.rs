@@ -0,0 +1,90 @@
+/**
+ * main.rs
+ *  command line front end for the differential testing harness.
+ *
+ *  Usage: difftest <rom.ch8> [--frames <n>] [--frame-length <n>] [--seed <n>] [--input <keys.txt>]
+ *                  [--backend llvm|wasm] [--quirks <preset>]
+ *
+ *  The recompilers only implement the modern quirks, so --quirks takes any
+ *  of the emulator's presets but refuses all the others.
+ */
+use std::env;
+use std::process;
+
+use chip8_retargeter_llvm::rom::Rom;
+use difftest::{run, run_wasm, InputScript, Options, Outcome};
+use emulator::quirks::Quirks;
+
+const USAGE: &str = "Usage: difftest <rom.ch8> [--frames <n>] [--frame-length <n>] [--seed <n>] [--input <keys.txt>]
+                [--backend llvm|wasm] [--quirks <preset>]";
+
+/**
+ * check_quirks returns an error unless name is a quirks preset the
+ *    recompiled code behaves like
+ */
+fn check_quirks(name: &str) -> Result<(), String> {
+    match Quirks::from_name(name) {
+        Some(Quirks::MODERN) => Ok(()),
+        Some(_) => Err(format!("The recompilers only support the modern quirks, not {}", name)),
+        None => {
+            let presets: Vec<&str> = Quirks::PRESETS.iter().map(|&(preset, _)| preset).collect();
+            Err(format!("Unknown quirks preset {}, expected one of: {}", name, presets.join(", ")))
+        }
+    }
+}
+
+/**
+ * parse_options reads the flags after the ROM path, and returns them
+ *    along with whether to check the WebAssembly backend
+ */
+fn parse_options(args: &[String]) -> Result<(Options, bool), String> {
+    let mut options = Options { frames: 600, frame_length: 8, seed: 0, input: InputScript::empty() };
+    let mut wasm = false;
+    let mut flags = args.iter();
+    while let Some(flag) = flags.next() {
+        let value = flags.next().ok_or(USAGE)?;
+        let number = || value.parse::<u64>().map_err(|_| format!("Invalid value for {}: {}", flag, value));
+        match flag.as_str() {
+            "--frames" => options.frames = number()?,
+            "--frame-length" => options.frame_length = number()?.max(1),
+            "--seed" => options.seed = number()?,
+            "--input" => options.input = InputScript::from_file(value)?,
+            "--backend" => match value.as_str() {
+                "llvm" => wasm = false,
+                "wasm" => wasm = true,
+                _ => return Err(USAGE.to_string()),
+            },
+            "--quirks" => check_quirks(value)?,
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    Ok((options, wasm))
+}
+
+pub fn main() -> Result<(), String> {
+    let args: Vec<String> = env::args().collect();
+    let rom_path = args.get(1).ok_or(USAGE)?;
+    let (options, wasm) = parse_options(&args[2..])?;
+    let rom = Rom::from_file(rom_path)?;
+
+    let report = if wasm { run_wasm(&rom, options)? } else { run(&rom, rom_path, options)? };
+    match report.outcome {
+        Outcome::Matched => {
+            println!("Matched for {} frames ({} instructions)", report.frames, report.instructions);
+        }
+        Outcome::Stopped(reason) => {
+            println!("Matched for {} frames until the recompiled program stopped: {}", report.frames, reason);
+        }
+        Outcome::Diverged(divergence) => {
+            println!(
+                "Diverged in frame {}, after {} instructions:",
+                divergence.frame, divergence.instruction
+            );
+            for difference in &divergence.differences {
+                println!("    {}", difference);
+            }
+            process::exit(1);
+        }
+    }
+    Ok(())
+}
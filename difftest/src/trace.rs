@@ -0,0 +1,58 @@
+/**
+ * trace.rs
+ *  this file runs a program headless in the emulator's interpreter and
+ *  records a trace of it: the address of every instruction that runs and
+ *  every target a BNNN computes. Frames are counted the way the emulator
+ *  counts down its timers, and each frame gets its keys from an input
+ *  script. A program that faults still leaves the trace of everything up
+ *  to that point.
+ */
+use chip8_retargeter_llvm::rom::Rom;
+use chip8_retargeter_llvm::trace::Trace;
+use emulator::error::CpuError;
+use emulator::processor::{timers_due, CPU};
+
+use crate::input::InputScript;
+
+pub struct Traced {
+    pub trace: Trace,
+    // Frames that ran to the end
+    pub frames: u64,
+    pub instructions: u64,
+    // Where and why the program faulted before the last frame, if it did
+    pub stopped: Option<(u16, CpuError)>,
+}
+
+/**
+ * trace runs a program for frames frames, seeding RND with seed and
+ *    pressing the keys input gives each frame
+ */
+pub fn trace(rom: &Rom, frames: u64, seed: u64, input: &InputScript) -> Traced {
+    let mut cpu = CPU::from_bytes(&rom.bytes);
+    cpu.seed_random(seed);
+    cpu.mmio.input_memory = input.keys(0);
+    let mut trace = Trace::new();
+    let mut frame = 0;
+    let mut stopped = None;
+
+    while frame < frames {
+        let pc = cpu.get_pc();
+        let result = cpu.next_opcode().and_then(|opcode| cpu.step().map(|_| opcode));
+        let opcode = match result {
+            Ok(opcode) => opcode,
+            Err(error) => {
+                stopped = Some((pc, error));
+                break;
+            }
+        };
+        trace.record(pc);
+        if opcode & 0xF000 == 0xB000 {
+            trace.record_jump(pc, cpu.get_pc());
+        }
+        if timers_due(cpu.instructions()) {
+            frame += 1;
+            cpu.mmio.input_memory = input.keys(frame);
+        }
+    }
+    Traced { trace, frames: frame, instructions: cpu.instructions(), stopped }
+}
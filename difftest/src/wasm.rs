@@ -0,0 +1,141 @@
+/**
+ * wasm.rs
+ *  runs the WebAssembly backend's module under wasmi in lockstep with the
+ *  interpreter. The host hooks it imports work the way the runtime's do,
+ *  on the same parts of a CPU, and the host's tick ends a frame once it
+ *  has run enough instructions.
+ */
+use wasmi::{Caller, Engine, Linker, Memory, Module, Store};
+
+use chip8_retargeter_llvm::rom::{Rom, MEMORY_SIZE};
+use chip8_retargeter_llvm::wasm::{
+    translate, STATE_DT, STATE_I, STATE_PC, STATE_SP, STATE_ST, STATE_STACK, STATE_V,
+};
+use emulator::processor::{CpuState, CPU};
+
+use crate::{Lockstep, Options, Report, Snapshot};
+
+/**
+ * What the module's imports work on
+ */
+struct Host {
+    // Screen, keypad and random numbers, the parts of a CPU the runtime uses
+    cpu: CPU,
+    // Instructions run in all
+    instructions: u64,
+    // Instructions run when the current frame started
+    frame_start: u64,
+    frame_length: u64,
+    // Why the program stopped, if it has
+    stopped: Option<String>,
+}
+
+/**
+ * memory_of returns the module's memory from inside an import
+ */
+fn memory_of(caller: &Caller<'_, Host>) -> Memory {
+    caller.get_export("memory").and_then(|export| export.into_memory()).expect("module exports its memory")
+}
+
+/**
+ * snapshot copies the machine out of the module's memory
+ */
+fn snapshot(data: &[u8], cpu: &CPU) -> Snapshot {
+    let word = |addr: u32| u16::from_le_bytes([data[addr as usize], data[addr as usize + 1]]);
+    let mut gp_registers = [0; 16];
+    gp_registers.copy_from_slice(&data[STATE_V as usize..STATE_V as usize + 16]);
+    let mut stack = [0; 16];
+    for (level, slot) in stack.iter_mut().enumerate() {
+        *slot = word(STATE_STACK + level as u32 * 2);
+    }
+    let mut memory = [0; 4096];
+    memory.copy_from_slice(&data[..4096]);
+    let cpu_state = CpuState {
+        gp_registers,
+        i: word(STATE_I),
+        dt: data[STATE_DT as usize],
+        st: data[STATE_ST as usize],
+        pc: word(STATE_PC),
+        sp: word(STATE_SP) as usize,
+        stack,
+        memory,
+    };
+    Snapshot { cpu: cpu_state, video: cpu.mmio.video_memory }
+}
+
+/**
+ * link defines every hook the module imports
+ */
+fn link(linker: &mut Linker<Host>) -> Result<(), wasmi::Error> {
+    linker.func_wrap("chip8", "cls", |mut caller: Caller<'_, Host>| caller.data_mut().cpu.mmio.clear())?;
+    linker.func_wrap("chip8", "draw", |mut caller: Caller<'_, Host>, x: i32, y: i32, addr: i32, n: i32| {
+        let memory = memory_of(&caller);
+        let (data, host) = memory.data_and_store_mut(&mut caller);
+        let sprite = &data[addr as usize..(addr + n) as usize];
+        host.cpu.mmio.draw_sprite(x as u8, y as u8, sprite) as i32
+    })?;
+    linker.func_wrap("chip8", "key_pressed", |caller: Caller<'_, Host>, key: i32| {
+        caller.data().cpu.mmio.input_memory.get(key as usize).copied().unwrap_or(false) as i32
+    })?;
+    linker.func_wrap("chip8", "random", |mut caller: Caller<'_, Host>| caller.data_mut().cpu.random_byte() as i32)?;
+    linker.func_wrap("chip8", "tick", |mut caller: Caller<'_, Host>, _sound: i32| {
+        let host = caller.data_mut();
+        host.instructions += 1;
+        (host.instructions - host.frame_start < host.frame_length) as i32
+    })?;
+    linker.func_wrap("chip8", "bad_address", |mut caller: Caller<'_, Host>, start: i32| {
+        // The first address used that is not in memory, as the interpreter reports it
+        let first = start.max(MEMORY_SIZE as i32);
+        caller.data_mut().stopped = Some(format!("Invalid memory location: 0x{:X}", first));
+    })?;
+    linker.func_wrap("chip8", "bad_stack", |mut caller: Caller<'_, Host>| {
+        let memory = memory_of(&caller);
+        let (data, host) = memory.data_and_store_mut(&mut caller);
+        let empty = data[STATE_SP as usize] == 0 && data[STATE_SP as usize + 1] == 0;
+        let message = if empty { "Stack underflow" } else { "Stack overflow" };
+        host.stopped = Some(message.to_string());
+    })?;
+    linker.func_wrap("chip8", "unresolved", |mut caller: Caller<'_, Host>, _pc: i32| {
+        caller.data_mut().stopped = Some("Nothing was translated here".to_string());
+    })?;
+    Ok(())
+}
+
+/**
+ * run_wasm translates a ROM to WebAssembly, runs it and the interpreter
+ *    side by side and reports how far they agreed
+ */
+pub fn run_wasm(rom: &Rom, options: Options) -> Result<Report, String> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, &translate(rom)[..]).map_err(|e| e.to_string())?;
+    let mut cpu = CPU::from_bytes(&rom.bytes);
+    cpu.seed_random(options.seed);
+    cpu.mmio.input_memory = options.input.keys(0);
+    let host = Host { cpu, instructions: 0, frame_start: 0, frame_length: options.frame_length, stopped: None };
+    let mut store = Store::new(&engine, host);
+    let mut linker = Linker::new(&engine);
+    link(&mut linker).map_err(|e| e.to_string())?;
+    let instance = linker.instantiate_and_start(&mut store, &module).map_err(|e| e.to_string())?;
+    let memory = instance.get_memory(&store, "memory").ok_or("Module has no memory")?;
+    let init = instance.get_typed_func::<(), ()>(&store, "init").map_err(|e| e.to_string())?;
+    let run_frame = instance.get_typed_func::<(), i32>(&store, "run_frame").map_err(|e| e.to_string())?;
+    init.call(&mut store, ()).map_err(|e| e.to_string())?;
+
+    let mut lockstep = Lockstep::new(rom, options);
+    loop {
+        store.data_mut().frame_start = store.data().instructions;
+        let running = run_frame.call(&mut store, ()).map_err(|e| e.to_string())? != 0;
+        lockstep.count(store.data().instructions);
+        let recompiled = snapshot(memory.data(&store), &store.data().cpu);
+        if !running {
+            let message = store.data_mut().stopped.take().unwrap_or_default();
+            let reason = format!("Fault at 0x{:03X}: {}", recompiled.cpu.pc, message);
+            let outcome = lockstep.stopped(&recompiled, reason);
+            return Ok(Report { frames: lockstep.frames, instructions: lockstep.instructions, outcome });
+        }
+        match lockstep.end_frame(&recompiled) {
+            Some(keys) => store.data_mut().cpu.mmio.input_memory = keys,
+            None => return Ok(lockstep.report()),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+/**
+ * quirks.rs
+ *  checks the retargeter's copy of the quirk preset names against the
+ *  emulator's presets, which the retargeter cannot depend on.
+ */
+use chip8_retargeter_llvm::quirks;
+use emulator::quirks::Quirks;
+
+#[test]
+fn preset_names_match_the_emulator() {
+    let names: Vec<&str> = Quirks::PRESETS.iter().map(|&(name, _)| name).collect();
+    assert_eq!(names, quirks::PRESETS);
+    assert_eq!(Quirks::from_name(quirks::RECOMPILED), Some(Quirks::MODERN));
+}
@@ -0,0 +1,77 @@
+/**
+ * roms.rs
+ *  runs ROMs through both backends in lockstep with the interpreter. Each
+ *  crafted ROM here once made the recompiled code and the interpreter
+ *  disagree, and the bundled assets cover everyday programs.
+ */
+use chip8_retargeter_llvm::rom::Rom;
+use difftest::{run, run_wasm, InputScript, Options, Outcome, Report};
+
+fn options(frames: u64) -> Options {
+    Options { frames, frame_length: 8, seed: 0, input: InputScript::empty() }
+}
+
+/**
+ * assert_agrees runs a ROM for some frames through the LLVM and
+ *    WebAssembly backends, failing if either diverges from the interpreter
+ */
+fn assert_agrees(name: &str, rom: &Rom, frames: u64) {
+    let reports: [(&str, Result<Report, String>); 2] =
+        [("llvm", run(rom, name, options(frames))), ("wasm", run_wasm(rom, options(frames)))];
+    for (backend, report) in reports {
+        let report = report.unwrap_or_else(|e| panic!("{} on {}: {}", name, backend, e));
+        if let Outcome::Diverged(divergence) = report.outcome {
+            let differences: Vec<String> = divergence.differences.iter().map(|d| d.to_string()).collect();
+            panic!("{} diverged on {} in frame {}: {}", name, backend, divergence.frame, differences.join(", "));
+        }
+    }
+}
+
+fn crafted(name: &str, bytes: &[u8]) {
+    assert_agrees(name, &Rom::from_bytes(bytes.to_vec()).unwrap(), 20);
+}
+
+#[test]
+fn drw_reads_vf_before_writing_the_collision() {
+    // LD VF, 5; LD I, 0x000; DRW VF, V1, 1; JP 0x206
+    crafted("drw_vf", &[0x6F, 0x05, 0xA0, 0x00, 0xDF, 0x11, 0x12, 0x06]);
+}
+
+#[test]
+fn drw_off_the_end_of_memory_faults_at_the_drw() {
+    // LD V0, 1; LD I, 0xFFF; LD VF, 5; DRW V0, V0, 2; JP 0x208
+    crafted("drw_fault", &[0x60, 0x01, 0xAF, 0xFF, 0x6F, 0x05, 0xD0, 0x02, 0x12, 0x08]);
+}
+
+#[test]
+fn empty_sprite_past_the_end_of_memory_faults_at_the_drw() {
+    // LD I, 0xFFF; LD V0, 2; ADD I, V0; DRW V0, V0, 0; JP 0x208
+    crafted("drw_empty_fault", &[0xAF, 0xFF, 0x60, 0x02, 0xF0, 0x1E, 0xD0, 0x00, 0x12, 0x08]);
+}
+
+#[test]
+fn memory_faults_report_the_interpreters_address() {
+    // LD I, 0xFFF; LD V0, 0x10; ADD I, V0; LD V0, [I]
+    let rom = Rom::from_bytes(vec![0xAF, 0xFF, 0x60, 0x10, 0xF0, 0x1E, 0xF0, 0x65]).unwrap();
+    let reports = [("llvm", run(&rom, "bad_address", options(5))), ("wasm", run_wasm(&rom, options(5)))];
+    for (backend, report) in reports {
+        match report.unwrap().outcome {
+            Outcome::Stopped(reason) => assert!(reason.ends_with("Invalid memory location: 0x100F"), "{}", reason),
+            _ => panic!("{} did not stop at the fault", backend),
+        }
+    }
+}
+
+#[test]
+fn schip_opcodes_run_as_chip8() {
+    // HIGH; SCD 4; SCR; SCL; LD HF, V3
+    crafted("schip", &[0x00, 0xFF, 0x00, 0xC4, 0x00, 0xFB, 0x00, 0xFC, 0xF3, 0x30]);
+}
+
+#[test]
+fn bundled_assets() {
+    for asset in ["pong", "maze8", "chp8_IBM_logo", "test_opcode"] {
+        let path = format!("{}/../emulator/assets/{}.ch8", env!("CARGO_MANIFEST_DIR"), asset);
+        assert_agrees(asset, &Rom::from_file(&path).unwrap(), 120);
+    }
+}
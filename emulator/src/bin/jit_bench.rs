@@ -0,0 +1,67 @@
+/**
+ * jit_bench.rs
+ *  runs a ROM headless for a number of instructions, once in the
+ *  interpreter and once with the block JIT, and reports how fast each was
+ *  and whether they ended up in the same state.
+ *
+ *  Usage: jit_bench <rom.ch8> [instructions]
+ */
+use std::env;
+use std::time::{Duration, Instant};
+
+use emulator::processor::CPU;
+
+const SEED: u64 = 0;
+
+/**
+ * run steps a CPU until it has run at least count instructions, or the
+ *    program faults. Returns how long that took and how many instructions
+ *    really ran.
+ */
+fn run(cpu: &mut CPU, count: u64) -> (Duration, u64) {
+    let start = Instant::now();
+    let mut executed = 0;
+    while executed < count {
+        match cpu.step() {
+            Ok(ran) => executed += ran as u64,
+            Err(_) => break,
+        }
+    }
+    (start.elapsed(), executed)
+}
+
+pub fn main() -> Result<(), String> {
+    let args: Vec<String> = env::args().collect();
+    let rom = args.get(1).ok_or("Usage: jit_bench <rom.ch8> [instructions]")?;
+    let count = match args.get(2) {
+        Some(count) => count.parse().map_err(|_| format!("Invalid instruction count {}", count))?,
+        None => 1_000_000,
+    };
+
+    let mut jit = CPU::new(rom);
+    jit.seed_random(SEED);
+    jit.enable_jit()?;
+    let (compiled, executed) = run(&mut jit, count);
+
+    // Compiled blocks can run past count, so the interpreter runs as many
+    let mut interpreter = CPU::new(rom);
+    interpreter.seed_random(SEED);
+    let (interpreted, _) = run(&mut interpreter, executed);
+
+    println!("{} instructions", executed);
+    println!("interpreter: {:?}", interpreted);
+    println!("jit:         {:?}", compiled);
+
+    let (a, b) = (interpreter.save_state(), jit.save_state());
+    let same = a.pc == b.pc
+        && a.gp_registers == b.gp_registers
+        && a.i == b.i
+        && a.dt == b.dt
+        && a.st == b.st
+        && a.sp == b.sp
+        && a.stack == b.stack
+        && a.memory[..] == b.memory[..]
+        && interpreter.mmio.video_memory == jit.mmio.video_memory;
+    println!("final state: {}", if same { "same" } else { "different" });
+    Ok(())
+}
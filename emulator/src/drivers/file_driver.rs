@@ -6,6 +6,8 @@
 use std::fs::File;
 use std::io::prelude::*;
 
+use crate::error::CpuError;
+
 const FONT_SET: [u8; 80] = [
   0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
   0x20, 0x60, 0x20, 0x20, 0x70, // 1
@@ -25,11 +27,38 @@ const FONT_SET: [u8; 80] = [
   0xF0, 0x80, 0xF0, 0x80, 0x80, // F
 ];
 
+// Where SUPER-CHIP's big font goes, just after the small one
+pub const BIG_FONT_ADDRESS: u16 = 0x50;
+
+// SUPER-CHIP's 8x10 hex digits, drawn with FX30
+const BIG_FONT_SET: [u8; 160] = [
+  0xFF, 0xFF, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, // 0
+  0x18, 0x78, 0x78, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0xFF, // 1
+  0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // 2
+  0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 3
+  0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0x03, 0x03, // 4
+  0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 5
+  0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 6
+  0xFF, 0xFF, 0x03, 0x03, 0x06, 0x0C, 0x18, 0x18, 0x18, 0x18, // 7
+  0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 8
+  0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 9
+  0x7E, 0xFF, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3, // A
+  0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, // B
+  0x3C, 0xFF, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0xFF, 0x3C, // C
+  0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC, // D
+  0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // E
+  0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0, // F
+];
+
 pub struct FileDriver {
   // Read only memory (except Chip8 spec allows writing too!)
   pub rom: [u8; 4096],
   // Size of the program.
   pub size: usize,
+  // Every address written since the JIT last looked, so it can throw
+  // away compiled code that was written over. None while there is no JIT.
+  #[cfg(feature = "jit")]
+  pub written: Option<Vec<u16>>,
 }
 
 impl FileDriver {
@@ -39,12 +68,17 @@ impl FileDriver {
     let mut f = File::open(filename).expect("Error: Cannot open");
     let mut file_buffer = [0u8; 4096 - 512];
 
-    let bytes_read = if let Ok(bytes_read) = f.read(&mut file_buffer) {
-      bytes_read
-    } else {
-      0
-    };
+    let bytes_read = f.read(&mut file_buffer).unwrap_or_default();
+
+    FileDriver::from_bytes(&file_buffer[..bytes_read])
+  }
 
+  /**
+   * from_bytes builds memory from a program that is already in memory,
+   *    anything past 4096 - 512 bytes is dropped.
+   */
+  pub fn from_bytes(program: &[u8]) -> FileDriver {
+    let size = program.len().min(4096 - 512);
     let mut rom = [0u8; 4096];
     let mut index = 0x0;
     // Write fontset to memory
@@ -52,51 +86,60 @@ impl FileDriver {
       rom[index] = FONT_SET[index];
       index += 1;
     }
-    index = 0x200;
-    // write file into memory
-    while index < 4096 {
-      rom[index] = file_buffer[index - 0x200];
-      index += 1;
-    }
-    
+    // write program into memory
+    rom[0x200..0x200 + size].copy_from_slice(&program[..size]);
+
     FileDriver {
       rom,
-      size: bytes_read
+      size,
+      #[cfg(feature = "jit")]
+      written: None
     }
   }
 
+  /**
+   * load_big_font writes SUPER-CHIP's big font into memory at
+   *    BIG_FONT_ADDRESS. Plain CHIP-8 programs never see it.
+   */
+  pub fn load_big_font(&mut self) {
+    let start = BIG_FONT_ADDRESS as usize;
+    self.rom[start..start + BIG_FONT_SET.len()].copy_from_slice(&BIG_FONT_SET);
+  }
+
   /**
    * get_opcode takes in a location and reads in the next TWO bytes
-   *    then returns the two bytes as a U16.
+   *    then returns the two bytes as a U16, or an error if they are
+   *    not both in memory.
    */
-  pub fn get_opcode(&self, location: u16) -> u16 {
-    if location > 4090 {
-      return 0;
-    }
+  pub fn get_opcode(&self, location: u16) -> Result<u16, CpuError> {
     let loc: usize = location as usize;
-    (self.rom[loc] as u16) << 8 | (self.rom[loc + 1]) as u16
+    if loc + 1 >= self.rom.len() {
+      return Err(CpuError::PcOutOfBounds(location));
+    }
+    Ok((self.rom[loc] as u16) << 8 | (self.rom[loc + 1]) as u16)
   }
 
   /**
    * write_byte takes a location and writes that byte to that location 
    *    in memory
    */
-  pub fn write_byte(&mut self, location: u16, byte: u8) {
-    if location > 4095 {
-      panic!("Invalid memory location: {}", location);
-    }
+  pub fn write_byte(&mut self, location: u16, byte: u8) -> Result<(), CpuError> {
     let loc: usize = location as usize;
+    if loc >= self.rom.len() {
+      return Err(CpuError::MemoryOutOfBounds(location));
+    }
     self.rom[loc] = byte;
+    #[cfg(feature = "jit")]
+    if let Some(written) = &mut self.written {
+      written.push(location);
+    }
+    Ok(())
   }
 
   /**
    * read_byte takes a location and returns a byte at that location
    */
-  pub fn read_byte(&self, location: u16) -> u8 {
-    if location > 4095 {
-      panic!("Invalid memory location: {}", location);
-    }
-    let loc: usize = location as usize;
-    self.rom[loc]
+  pub fn read_byte(&self, location: u16) -> Result<u8, CpuError> {
+    self.rom.get(location as usize).copied().ok_or(CpuError::MemoryOutOfBounds(location))
   }
-}
\ No newline at end of file
+}
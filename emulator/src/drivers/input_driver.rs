@@ -18,10 +18,14 @@ impl InputDriver {
         }
     }
 
-    pub fn get_input(&mut self) -> Result<[bool; 16], ()> {
+    /**
+     * get_input returns which keys are held down, or None once the window
+     *    has been closed
+     */
+    pub fn get_input(&mut self) -> Option<[bool; 16]> {
         for event in self.event_pump.poll_iter() {
             if let Event::Quit { .. } = event {
-                return Err(());
+                return None;
             };
         }
 
@@ -56,12 +60,11 @@ impl InputDriver {
                 _ => None,
             };
 
-            match index {
-                Some(i) => input[i] = true,
-                None => ()
+            if let Some(i) = index {
+                input[i] = true;
             }
         }
 
-        Ok(input)
+        Some(input)
     }
 }
\ No newline at end of file
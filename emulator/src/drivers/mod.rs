@@ -3,9 +3,13 @@
  * Simply imports and re-exports
  */
 mod file_driver;
+#[cfg(feature = "sdl")]
 mod video_driver;
+#[cfg(feature = "sdl")]
 mod input_driver;
 
-pub use self::file_driver::FileDriver;
+pub use self::file_driver::{FileDriver, BIG_FONT_ADDRESS};
+#[cfg(feature = "sdl")]
 pub use self::video_driver::VideoDriver;
-pub use self::input_driver::InputDriver;
\ No newline at end of file
+#[cfg(feature = "sdl")]
+pub use self::input_driver::InputDriver;
@@ -3,17 +3,14 @@
  * this file abstracts the graphics implementation out into just 
  * memory mapped io from the cpu. 
  */
-
 use sdl2;
 use sdl2::pixels;
 use sdl2::rect::Rect;
 use sdl2::render::Canvas;
 use sdl2::video::Window;
+use crate::processor::MMIO;
 use crate::SDL_HEIGHT;
 use crate::SDL_WIDTH;
-use crate::VIDEO_WIDTH;
-use crate::VIDEO_HEIGHT;
-use crate::SCALAR;
 
 pub struct VideoDriver {
   canvas: Canvas<Window>,
@@ -35,26 +32,28 @@ impl VideoDriver {
     canvas.clear();
     canvas.present();
 
-    VideoDriver { canvas: canvas }
+    VideoDriver { canvas }
   }
 
   /**
-   * draw takes in a double array of pixels indicating if a pixel should be 
-   *    colored or not colored.
+   * draw takes in the memory mapped screen, whose pixels indicate if they
+   *    should be colored or not colored, and scales it up to fill the
+   *    window at whichever resolution it is showing.
    */
-  pub fn draw(&mut self, pixels: &[[u8; VIDEO_WIDTH]; VIDEO_HEIGHT]) {
-    for (y, row) in pixels.iter().enumerate() {
-        for (x, &colored) in row.iter().enumerate() {
+  pub fn draw(&mut self, mmio: &MMIO) {
+    let scalar = SDL_WIDTH / mmio.width() as u32;
+    for (y, row) in mmio.video_memory[..mmio.height()].iter().enumerate() {
+        for (x, &colored) in row[..mmio.width()].iter().enumerate() {
             // Scale up to correct pixel top left
-            let x = (x as u32) * SCALAR;
-            let y = (y as u32) * SCALAR;
+            let x = (x as u32) * scalar;
+            let y = (y as u32) * scalar;
             match colored {
               0 => self.canvas.set_draw_color(pixels::Color::RGB(0, 0, 0)),
               _ => self.canvas.set_draw_color(pixels::Color::RGB(115, 115, 115))
             }
             
             // Fill scaled up pixel
-            self.canvas.fill_rect(Rect::new(x as i32, y as i32, SCALAR, SCALAR)).ok();
+            self.canvas.fill_rect(Rect::new(x as i32, y as i32, scalar, scalar)).ok();
         }
     }
     self.canvas.present();
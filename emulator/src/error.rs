@@ -0,0 +1,37 @@
+/**
+ * error.rs
+ *  the ways a chip8 program can make the CPU stop. Stepping returns one
+ *  of these instead of panicking or carrying on, and leaves pc at the
+ *  instruction that caused it, so a frontend can say what went wrong
+ *  and where.
+ */
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuError {
+    // An opcode that is not a chip8 instruction
+    UnknownOpcode(u16),
+    // CALL with every stack slot in use
+    StackOverflow,
+    // RET with nothing on the stack
+    StackUnderflow,
+    // A load, store or sprite read past the end of memory, at this address
+    MemoryOutOfBounds(u16),
+    // pc is too close to the end of memory to fetch an opcode
+    PcOutOfBounds(u16),
+}
+
+impl fmt::Display for CpuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CpuError::UnknownOpcode(opcode) => write!(f, "Unknown opcode: 0x{:04X}", opcode),
+            CpuError::StackOverflow => write!(f, "Stack overflow"),
+            CpuError::StackUnderflow => write!(f, "Stack underflow"),
+            CpuError::MemoryOutOfBounds(addr) => write!(f, "Invalid memory location: 0x{:X}", addr),
+            CpuError::PcOutOfBounds(pc) => write!(f, "No instruction fits at 0x{:X}", pc),
+        }
+    }
+}
+
+impl Error for CpuError {}
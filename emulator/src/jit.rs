@@ -0,0 +1,341 @@
+/**
+ * jit.rs
+ *  an optional block level JIT for the CPU, built with the "jit" feature.
+ *  Blocks the CPU keeps coming back to are lifted with the retargeter and
+ *  compiled to native code by LLVM, then run in place of the interpreter.
+ *  Compiled blocks are cached by start address and thrown away when the
+ *  program writes over them through FileDriver::write_byte.
+ *
+ *  Anything a block cannot do natively is left to the interpreter, which
+ *  stays the reference: stores to memory, waiting for a key, unknown
+ *  opcodes and anything that would make the interpreter fault.
+ */
+use std::collections::HashMap;
+use std::mem;
+
+use inkwell::context::Context;
+use inkwell::execution_engine::ExecutionEngine;
+use inkwell::targets::{InitializationConfig, Target};
+use inkwell::OptimizationLevel;
+
+use chip8_retargeter_llvm::cfg::{ends_block, BasicBlock};
+use chip8_retargeter_llvm::codegen::to_llvm;
+use chip8_retargeter_llvm::instruction::Instruction;
+use chip8_retargeter_llvm::lift::lift_block;
+
+use crate::error::CpuError;
+use crate::processor::{timers_due, CPU};
+
+// Times a block has to be reached before it is compiled
+const HOT_THRESHOLD: u32 = 16;
+// Most instructions in one compiled block
+const MAX_BLOCK_LENGTH: usize = 64;
+// Most instructions one compiled block may run before going back to the
+// main loop, for blocks that loop back on themselves
+const INSTRUCTION_BUDGET: u32 = 256;
+
+/**
+ * The machine as compiled blocks see it. The fields up to and including
+ * memory must match %chip8.state in the retargeter's lift.rs.
+ */
+#[repr(C)]
+struct JitState {
+    v: [u8; 16],
+    i: u16,
+    pc: u16,
+    sp: u16,
+    dt: u8,
+    st: u8,
+    stack: [u16; 16],
+    // Kept in step with the CPU's memory through its write log
+    memory: [u8; 4096],
+    // Not visible to generated code
+    cpu: *mut CPU,
+    // Instructions run by the current block
+    executed: u32,
+    // Where the interpreter has to take over from, if the block gave up
+    resume: Option<u16>,
+}
+
+struct CompiledBlock {
+    // Address just past the last instruction in the block
+    end: u16,
+    function: unsafe extern "C" fn(*mut JitState),
+    // Owns the machine code function points into
+    _engine: ExecutionEngine<'static>,
+}
+
+thread_local! {
+    // Every compiled block borrows the context it was built in, so it is
+    // never freed. It is made once per thread and shared by every JIT.
+    static CONTEXT: &'static Context = Box::leak(Box::new(Context::create()));
+}
+
+pub struct Jit {
+    // This thread's CONTEXT
+    context: &'static Context,
+    state: Box<JitState>,
+    // Compiled blocks by start address, None for blocks that cannot be compiled
+    blocks: HashMap<u16, Option<CompiledBlock>>,
+    // Times each block start was reached before being compiled
+    hits: HashMap<u16, u32>,
+    // Set when the next instruction has to be interpreted
+    interpret_next: bool,
+}
+
+/**
+ * find_block decodes the longest run of instructions from start that a
+ *    compiled block can handle, stopping after control flow
+ */
+fn find_block(memory: &[u8; 4096], start: u16) -> Option<BasicBlock> {
+    let mut instructions = Vec::new();
+    let mut pc = start;
+    // FileDriver::get_opcode faults on an opcode without both bytes in memory,
+    // so the block stops before it and the interpreter reports the fault
+    while instructions.len() < MAX_BLOCK_LENGTH && (pc as usize) + 1 < memory.len() {
+        let opcode = (memory[pc as usize] as u16) << 8 | memory[pc as usize + 1] as u16;
+        let instruction = Instruction::decode(opcode);
+        match instruction {
+            Instruction::LdB(_) | Instruction::Store(_) | Instruction::LdVxK(_) | Instruction::Unknown(_) => break,
+            // SUPER-CHIP's Fx30, Fx75 and Fx85 are unknown to CHIP-8
+            _ if instruction.is_schip() && instruction.encode() >= 0xF000 => break,
+            _ => {}
+        }
+        instructions.push((pc, instruction));
+        pc += 2;
+        if ends_block(&instruction) {
+            break;
+        }
+    }
+    if instructions.is_empty() {
+        return None;
+    }
+    Some(BasicBlock { start, instructions })
+}
+
+impl Jit {
+    pub fn new(cpu: &CPU) -> Result<Jit, String> {
+        Target::initialize_native(&InitializationConfig::default())?;
+        let state = Box::new(JitState {
+            v: [0; 16],
+            i: 0,
+            pc: 0,
+            sp: 0,
+            dt: 0,
+            st: 0,
+            stack: [0; 16],
+            memory: cpu.memory.rom,
+            cpu: std::ptr::null_mut(),
+            executed: 0,
+            resume: None,
+        });
+        Ok(Jit {
+            context: CONTEXT.with(|context| *context),
+            state,
+            blocks: HashMap::new(),
+            hits: HashMap::new(),
+            interpret_next: false,
+        })
+    }
+
+    /**
+     * reset drops every compiled block and takes a fresh copy of memory,
+     *    for when the CPU's memory is replaced wholesale
+     */
+    pub fn reset(&mut self, memory: &[u8; 4096]) {
+        self.state.memory = *memory;
+        self.blocks.clear();
+        self.hits.clear();
+    }
+
+    /**
+     * step runs the compiled block at pc, or a single instruction in the
+     *    interpreter if there is none, and returns how many instructions
+     *    ran. Timers are updated once per instruction either way. Faults
+     *    always come from the interpreter.
+     */
+    pub fn step(&mut self, cpu: &mut CPU) -> Result<u32, CpuError> {
+        let pc = cpu.pc;
+        if !mem::replace(&mut self.interpret_next, false) {
+            if let Some(function) = self.lookup(pc) {
+                return Ok(self.run(cpu, function));
+            }
+        }
+        cpu.execute_next_opcode()?;
+        cpu.update_timers();
+        self.apply_writes(cpu);
+        Ok(1)
+    }
+
+    /**
+     * apply_writes copies what the interpreter wrote through
+     *    FileDriver::write_byte and invalidates any block it wrote over
+     */
+    fn apply_writes(&mut self, cpu: &mut CPU) {
+        let written = match &mut cpu.memory.written {
+            Some(written) => written,
+            None => return,
+        };
+        for addr in written.drain(..) {
+            self.state.memory[addr as usize] = cpu.memory.rom[addr as usize];
+            self.blocks.retain(|&start, block| match block {
+                Some(block) => addr < start || addr >= block.end,
+                // Uncompilable blocks might compile now
+                None => addr < start || addr as usize >= start as usize + MAX_BLOCK_LENGTH * 2,
+            });
+        }
+    }
+
+    /**
+     * lookup returns the compiled block at pc, compiling it if it has been
+     *    reached often enough
+     */
+    fn lookup(&mut self, pc: u16) -> Option<unsafe extern "C" fn(*mut JitState)> {
+        if let Some(block) = self.blocks.get(&pc) {
+            return block.as_ref().map(|block| block.function);
+        }
+        let hits = self.hits.entry(pc).or_insert(0);
+        *hits += 1;
+        if *hits < HOT_THRESHOLD {
+            return None;
+        }
+        self.hits.remove(&pc);
+        let block = self.compile(pc);
+        let function = block.as_ref().map(|block| block.function);
+        self.blocks.insert(pc, block);
+        function
+    }
+
+    /**
+     * compile lifts and compiles the block at start, or returns None if
+     *    there is nothing there a block can run
+     */
+    fn compile(&self, start: u16) -> Option<CompiledBlock> {
+        let block = find_block(&self.state.memory, start)?;
+        let module = lift_block(&block, &format!("block_{:03X}", start)).ok()?;
+        let module = to_llvm(self.context, &module).ok()?;
+        let engine = module.create_jit_execution_engine(OptimizationLevel::Default).ok()?;
+
+        let callbacks: [(&str, usize); 7] = [
+            ("chip8_rt_tick", jit_tick as *const () as usize),
+            ("chip8_rt_cls", jit_cls as *const () as usize),
+            ("chip8_rt_draw", jit_draw as *const () as usize),
+            ("chip8_rt_key_pressed", jit_key_pressed as *const () as usize),
+            ("chip8_rt_random", jit_random as *const () as usize),
+            ("chip8_rt_bad_address", jit_bad_address as *const () as usize),
+            ("chip8_rt_bad_stack", jit_give_up as *const () as usize),
+        ];
+        for (name, addr) in callbacks.iter() {
+            if let Some(function) = module.get_function(name) {
+                engine.add_global_mapping(&function, *addr);
+            }
+        }
+        let addr = engine.get_function_address("chip8_block").ok()?;
+        Some(CompiledBlock {
+            end: block.end(),
+            function: unsafe { mem::transmute::<usize, unsafe extern "C" fn(*mut JitState)>(addr) },
+            _engine: engine,
+        })
+    }
+
+    /**
+     * run copies the registers into the block's state, runs it and copies
+     *    them back out
+     */
+    fn run(&mut self, cpu: &mut CPU, function: unsafe extern "C" fn(*mut JitState)) -> u32 {
+        let state = &mut *self.state;
+        state.v = cpu.gp_registers;
+        state.i = cpu.i;
+        state.pc = cpu.pc;
+        state.sp = cpu.sp as u16;
+        state.dt = cpu.dt;
+        state.st = cpu.st;
+        state.stack = cpu.stack;
+        state.cpu = cpu;
+        state.executed = 0;
+        cpu.d_flag = false;
+
+        unsafe { function(state) };
+
+        cpu.gp_registers = state.v;
+        cpu.i = state.i;
+        cpu.pc = state.pc;
+        cpu.sp = state.sp as usize;
+        cpu.dt = state.dt;
+        cpu.st = state.st;
+        cpu.stack = state.stack;
+        if let Some(pc) = state.resume.take() {
+            cpu.pc = pc;
+            self.interpret_next = true;
+        }
+        state.executed
+    }
+}
+
+/**
+ * jit_tick updates the timers after each instruction, the same as
+ *    CPU::update_timers, and stops the block at the next safepoint once it
+ *    has used its budget or given up
+ */
+unsafe extern "C" fn jit_tick(state: *mut JitState, _synced: bool) -> bool {
+    let state = &mut *state;
+    let cpu = &mut *state.cpu;
+    cpu.instructions += 1;
+    if timers_due(cpu.instructions) {
+        if state.dt > 0 {
+            state.dt -= 1;
+        }
+        if state.st > 0 {
+            println!("Beep");
+            state.st -= 1;
+        }
+    }
+    state.executed += 1;
+    state.executed < INSTRUCTION_BUDGET && state.resume.is_none()
+}
+
+unsafe extern "C" fn jit_cls(state: *mut JitState) {
+    let cpu = &mut *(*state).cpu;
+    cpu.mmio.clear();
+    cpu.d_flag = true;
+}
+
+/**
+ * jit_draw draws the sprite at I. A sprite running off the end of memory
+ *    is a fault, so the interpreter is left to run it.
+ */
+unsafe extern "C" fn jit_draw(state: *mut JitState, x: u8, y: u8, n: u8) -> u8 {
+    let state = &mut *state;
+    let start = state.i as usize;
+    let end = start + n as usize;
+    if end > state.memory.len() {
+        jit_give_up(state);
+        return 0;
+    }
+    let cpu = &mut *state.cpu;
+    cpu.d_flag = true;
+    cpu.mmio.draw_sprite(x, y, &state.memory[start..end])
+}
+
+unsafe extern "C" fn jit_key_pressed(state: *mut JitState, key: u8) -> bool {
+    // There is no such key, so it is not pressed
+    (*(*state).cpu).mmio.input_memory.get(key as usize).copied().unwrap_or(false)
+}
+
+unsafe extern "C" fn jit_random(state: *mut JitState) -> u8 {
+    (*(*state).cpu).random_byte()
+}
+
+/**
+ * jit_give_up is called on a stack fault or anything else the interpreter
+ *    would fault on, with pc on the instruction at fault. The interpreter
+ *    runs it instead, and returns the error.
+ */
+unsafe extern "C" fn jit_give_up(state: *mut JitState) {
+    let state = &mut *state;
+    state.resume = Some(state.pc);
+}
+
+unsafe extern "C" fn jit_bad_address(state: *mut JitState, _addr: u16) {
+    jit_give_up(state);
+}
@@ -0,0 +1,24 @@
+/**
+ * lib.rs
+ *  exposes the CPU and drivers so other crates (the recompiler runtime)
+ *  can reuse them. The SDL drivers are only built with the "sdl" feature,
+ *  and the block JIT only with the "jit" feature.
+ */
+pub mod drivers;
+pub mod error;
+#[cfg(feature = "jit")]
+pub mod jit;
+pub mod processor;
+pub mod quirks;
+
+/******************
+ * CONFIG
+ ******************/
+ pub const SCALAR: u32 = 16;
+ pub const VIDEO_WIDTH: usize = 64;
+ pub const VIDEO_HEIGHT: usize = 32;
+ // SUPER-CHIP's high resolution, switched to at runtime
+ pub const HIRES_WIDTH: usize = 128;
+ pub const HIRES_HEIGHT: usize = 64;
+ pub const SDL_WIDTH: u32 = (VIDEO_WIDTH as u32) * SCALAR;
+ pub const SDL_HEIGHT: u32 = (VIDEO_HEIGHT as u32) * SCALAR;
@@ -8,25 +8,14 @@
  *      4) Updating timer registers
  *      5) Controlling execution rate of your emulator, (Hz)
  */
-mod drivers;
-mod processor;
-
 use std::time::{Instant, Duration};
 use std::thread::sleep;
 use std::env;
 
 extern crate sdl2;
-use processor::CPU;
-use drivers::{InputDriver, VideoDriver};
-
-/******************
- * CONFIG
- ******************/
- pub const SCALAR: u32 = 16;
- pub const VIDEO_WIDTH: usize = 64;
- pub const VIDEO_HEIGHT: usize = 32;
- pub const SDL_WIDTH: u32 = (VIDEO_WIDTH as u32) * SCALAR;
- pub const SDL_HEIGHT: u32 = (VIDEO_HEIGHT as u32) * SCALAR;
+use emulator::processor::CPU;
+use emulator::quirks::Quirks;
+use emulator::drivers::{InputDriver, VideoDriver};
 
  /* main this function should handle the main emulator loop.
  * This loop includes the following:
@@ -42,24 +31,45 @@ pub fn main() -> Result<(), String> {
     let mut video_driver = VideoDriver::new(&context);
     let mut input_driver = InputDriver::new(&context);
     let mut cpu: CPU = CPU::new(&args[1]);
+    // --schip runs SUPER-CHIP programs, with SUPER-CHIP's quirks unless --quirks says otherwise
+    if args.iter().any(|arg| arg == "--schip") {
+        cpu.enable_schip();
+        cpu.set_quirks(Quirks::SCHIP);
+    }
+    // --quirks picks the preset of the interpreter the program was written for
+    if let Some(position) = args.iter().position(|arg| arg == "--quirks") {
+        let name = args.get(position + 1).ok_or("--quirks needs a preset name")?;
+        let presets: Vec<&str> = Quirks::PRESETS.iter().map(|&(preset, _)| preset).collect();
+        let quirks = Quirks::from_name(name)
+            .ok_or_else(|| format!("Unknown quirks preset {}, expected one of: {}", name, presets.join(", ")))?;
+        cpu.set_quirks(quirks);
+    }
+    #[cfg(feature = "jit")]
+    if args.iter().any(|arg| arg == "--jit") {
+        cpu.enable_jit()?;
+    }
     let execution_rate = Duration::from_millis(2);
     loop {
         let duration = Instant::now();
         let input = input_driver.get_input();
         match input {
-            Ok(mem) => cpu.mmio.input_memory = mem,
-            Err(_) => return Ok(())
+            Some(mem) => cpu.mmio.input_memory = mem,
+            None => return Ok(())
         }
 
-        cpu.execute_next_opcode();
+        // Also updates the timers, which count down at 60Hz of instructions run
+        // A program fault stops the emulator, saying where it happened
+        let executed = cpu.step().map_err(|error| format!("Fault at 0x{:03X}: {}", cpu.get_pc(), error))?;
+        if cpu.exited() {
+            return Ok(());
+        }
         if cpu.get_draw_flag() {
-            video_driver.draw(&cpu.mmio.video_memory);
+            video_driver.draw(&cpu.mmio);
         }
 
-        cpu.update_timers();
-
-        if duration.elapsed() < execution_rate {
-            sleep(execution_rate - duration.elapsed())
+        let budget = execution_rate * executed;
+        if duration.elapsed() < budget {
+            sleep(budget - duration.elapsed())
         }
     }
 }
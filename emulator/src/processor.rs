@@ -4,38 +4,239 @@
  *  this file is responisble for implmenting all the opcodes, registers,
  *  and setting the memory mapped I/O.
  */
-use crate::drivers::FileDriver;
+use crate::drivers::{FileDriver, BIG_FONT_ADDRESS};
+use crate::error::CpuError;
+#[cfg(feature = "jit")]
+use crate::jit::Jit;
+use crate::quirks::{IncrementI, Quirks};
 use crate::VIDEO_HEIGHT;
 use crate::VIDEO_WIDTH;
-use rand::random;
+use crate::HIRES_HEIGHT;
+use crate::HIRES_WIDTH;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// The main loop runs one instruction every 2ms
+pub const INSTRUCTIONS_PER_SECOND: u64 = 500;
+// DT and ST count down at 60Hz
+pub const TIMER_HZ: u64 = 60;
+
+/**
+ * timers_due returns true if DT and ST count down after the given
+ *    instruction, counting every instruction run since the program started
+ *    from 1. That is TIMER_HZ of every INSTRUCTIONS_PER_SECOND instructions,
+ *    as evenly spread as whole instructions allow, so anything counting
+ *    instructions the same way counts the timers down on the same ones.
+ */
+pub fn timers_due(instruction: u64) -> bool {
+    instruction * TIMER_HZ / INSTRUCTIONS_PER_SECOND != (instruction - 1) * TIMER_HZ / INSTRUCTIONS_PER_SECOND
+}
+
+// Where a program's video memory is, with room for SUPER-CHIP's high
+// resolution. At the low resolution only the top left 64x32 is used.
+pub type Video = [[u8; HIRES_WIDTH]; HIRES_HEIGHT];
 
 pub struct MMIO {
-    pub video_memory: [[u8; VIDEO_WIDTH]; VIDEO_HEIGHT],
-    pub input_memory: [bool; 16]
+    pub video_memory: Video,
+    pub input_memory: [bool; 16],
+    // Showing SUPER-CHIP's 128x64 rather than 64x32
+    pub hires: bool
+}
+
+impl MMIO {
+    pub fn new() -> MMIO {
+        MMIO {
+            video_memory: [[0; HIRES_WIDTH]; HIRES_HEIGHT],
+            input_memory: [false; 16],
+            hires: false
+        }
+    }
+
+    /**
+     * width returns how many pixels across the screen is at the current
+     *    resolution
+     */
+    pub fn width(&self) -> usize {
+        if self.hires { HIRES_WIDTH } else { VIDEO_WIDTH }
+    }
+
+    /**
+     * height returns how many pixels down the screen is at the current
+     *    resolution
+     */
+    pub fn height(&self) -> usize {
+        if self.hires { HIRES_HEIGHT } else { VIDEO_HEIGHT }
+    }
+
+    /**
+     * clear turns every pixel off
+     */
+    pub fn clear(&mut self) {
+        for i in 0..HIRES_HEIGHT {
+            for j in 0..HIRES_WIDTH {
+                self.video_memory[i][j] = 0;
+            }
+        }
+    }
+
+    /**
+     * set_hires switches between 64x32 and 128x64, clearing the screen
+     */
+    pub fn set_hires(&mut self, hires: bool) {
+        self.hires = hires;
+        self.clear();
+    }
+
+    /**
+     * scroll_down moves the screen down n rows, blanking the rows at the top
+     */
+    pub fn scroll_down(&mut self, n: usize) {
+        let height = self.height();
+        self.video_memory.copy_within(0..height - n, n);
+        for row in &mut self.video_memory[..n] {
+            row.fill(0);
+        }
+    }
+
+    /**
+     * scroll_right moves the screen right n columns, blanking the columns
+     *    on the left
+     */
+    pub fn scroll_right(&mut self, n: usize) {
+        let width = self.width();
+        for row in &mut self.video_memory {
+            row.copy_within(0..width - n, n);
+            row[..n].fill(0);
+        }
+    }
+
+    /**
+     * scroll_left moves the screen left n columns, blanking the columns
+     *    on the right
+     */
+    pub fn scroll_left(&mut self, n: usize) {
+        let width = self.width();
+        for row in &mut self.video_memory {
+            row.copy_within(n..width, 0);
+            row[width - n..width].fill(0);
+        }
+    }
+
+    /**
+     * draw_sprite XORs a sprite, one byte per row, onto the screen at (x, y)
+     *    wrapping around the edges. Returns 1 if any pixel was erased.
+     */
+    pub fn draw_sprite(&mut self, x: u8, y: u8, sprite: &[u8]) -> u8 {
+        self.blit(x, y, sprite, 1, false)
+    }
+
+    /**
+     * draw_clipped_sprite is draw_sprite for sprites cut off at the edges.
+     *    Only where the sprite starts wraps around.
+     */
+    pub fn draw_clipped_sprite(&mut self, x: u8, y: u8, sprite: &[u8]) -> u8 {
+        self.blit(x, y, sprite, 1, true)
+    }
+
+    /**
+     * blit XORs a sprite of row_bytes bytes per row onto the screen at
+     *    (x, y), either clipping or wrapping at the edges. Returns 1 if any
+     *    pixel was erased.
+     */
+    fn blit(&mut self, x: u8, y: u8, sprite: &[u8], row_bytes: usize, clip: bool) -> u8 {
+        let (width, height) = (self.width(), self.height());
+        let (x, y) = (x as usize % width, y as usize % height);
+        let mut collision = 0;
+        for (current, row) in sprite.chunks(row_bytes).enumerate() {
+            if clip && y + current >= height {
+                break;
+            }
+            let y = (y + current) % height;
+            for bit in 0..row_bytes * 8 {
+                if clip && x + bit >= width {
+                    break;
+                }
+                let x = (x + bit) % width;
+                // get bit and shift to place
+                let colored = row[bit / 8] >> (7 - bit % 8) & 1;
+                collision |= colored & self.video_memory[y][x];
+                // set actual color
+                self.video_memory[y][x] ^= colored;
+            }
+        }
+        collision
+    }
+}
+
+impl Default for MMIO {
+    fn default() -> Self {
+        MMIO::new()
+    }
+}
+
+/**
+ * A copy of everything the CPU needs to carry on running. Used to hand
+ * execution between the interpreter and recompiled code.
+ */
+#[derive(Clone)]
+pub struct CpuState {
+    pub gp_registers: [u8; 16],
+    pub i: u16,
+    pub dt: u8,
+    pub st: u8,
+    pub pc: u16,
+    pub sp: usize,
+    pub stack: [u16; 16],
+    pub memory: [u8; 4096]
 }
 
 pub struct CPU {
     // Memory mapped Input Output
     pub mmio: MMIO,
     // General purpose registers
-    gp_registers: [u8; 16],
+    pub(crate) gp_registers: [u8; 16],
     // Special registers
-    i: u16,
-    dt: u8,
-    st: u8,
-    pc: u16,
-    sp: usize,
-    d_flag: bool,
-    stack: [u16; 16],
-    memory: FileDriver
+    pub(crate) i: u16,
+    pub(crate) dt: u8,
+    pub(crate) st: u8,
+    pub(crate) pc: u16,
+    pub(crate) sp: usize,
+    pub(crate) d_flag: bool,
+    pub(crate) stack: [u16; 16],
+    pub(crate) memory: FileDriver,
+    // How the opcodes interpreters disagree on behave
+    pub(crate) quirks: Quirks,
+    // Set when the timers count down and cleared by DRW, for display wait
+    frame_started: bool,
+    // Runs SUPER-CHIP 1.1's instructions as well
+    pub(crate) schip: bool,
+    // SUPER-CHIP's RPL user flags, saved and restored by FX75 and FX85
+    rpl: [u8; 8],
+    // Set once a SUPER-CHIP program runs 00FD
+    exited: bool,
+    // Source of RND bytes, seeded so runs can be repeated
+    rng: StdRng,
+    // Instructions run so far, which decides when the timers count down
+    pub(crate) instructions: u64,
+    // Compiles hot blocks to native code once enabled
+    #[cfg(feature = "jit")]
+    jit: Option<Box<Jit>>
 }
 impl CPU {
     pub fn new(file_name: &str) -> CPU {
+        CPU::with_memory(FileDriver::new(file_name))
+    }
+
+    /**
+     * from_bytes makes a CPU for a program that is already in memory
+     */
+    pub fn from_bytes(program: &[u8]) -> CPU {
+        CPU::with_memory(FileDriver::from_bytes(program))
+    }
+
+    fn with_memory(memory: FileDriver) -> CPU {
         CPU {
-            mmio: MMIO {
-                video_memory: [[0; VIDEO_WIDTH]; VIDEO_HEIGHT],
-                input_memory: [false; 16]
-            },
+            mmio: MMIO::new(),
             gp_registers: [0; 16],
             i: 0,
             dt: 0,
@@ -44,12 +245,130 @@ impl CPU {
             sp: 0,
             d_flag: false,
             stack: [0; 16],
-            memory: FileDriver::new(file_name)
+            memory,
+            quirks: Quirks::MODERN,
+            frame_started: false,
+            schip: false,
+            rpl: [0; 8],
+            exited: false,
+            rng: StdRng::from_entropy(),
+            instructions: 0,
+            #[cfg(feature = "jit")]
+            jit: None
+        }
+    }
+
+    /**
+     * save_state copies out the registers, stack and memory
+     */
+    pub fn save_state(&self) -> CpuState {
+        CpuState {
+            gp_registers: self.gp_registers,
+            i: self.i,
+            dt: self.dt,
+            st: self.st,
+            pc: self.pc,
+            sp: self.sp,
+            stack: self.stack,
+            memory: self.memory.rom
         }
     }
 
-    pub fn execute_next_opcode(&mut self) {
-        let opcode = self.memory.get_opcode(self.pc);
+    /**
+     * load_state replaces the registers, stack and memory, leaving MMIO alone
+     */
+    pub fn load_state(&mut self, state: &CpuState) {
+        self.gp_registers = state.gp_registers;
+        self.i = state.i;
+        self.dt = state.dt;
+        self.st = state.st;
+        self.pc = state.pc;
+        self.sp = state.sp;
+        self.stack = state.stack;
+        self.memory.rom = state.memory;
+        #[cfg(feature = "jit")]
+        if let Some(jit) = &mut self.jit {
+            jit.reset(&self.memory.rom);
+        }
+    }
+
+    /**
+     * set_quirks changes how the opcodes interpreters disagree on behave.
+     *    The JIT only knows the modern quirks, so any others turn it off.
+     */
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+        #[cfg(feature = "jit")]
+        if quirks != Quirks::MODERN {
+            self.disable_jit();
+        }
+    }
+
+    pub fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    /**
+     * enable_schip runs SUPER-CHIP 1.1's instructions as well as CHIP-8's
+     *    and loads its big font. The JIT only knows CHIP-8, so it is turned
+     *    off. Without it the extra opcodes are ignored or unknown, as before.
+     */
+    pub fn enable_schip(&mut self) {
+        self.schip = true;
+        self.memory.load_big_font();
+        #[cfg(feature = "jit")]
+        self.disable_jit();
+    }
+
+    /**
+     * enable_jit makes step run hot blocks as native code
+     */
+    #[cfg(feature = "jit")]
+    pub fn enable_jit(&mut self) -> Result<(), String> {
+        if self.quirks != Quirks::MODERN {
+            return Err("The JIT only runs programs with the modern quirks".to_string());
+        }
+        if self.schip {
+            return Err("The JIT cannot run SUPER-CHIP programs".to_string());
+        }
+        self.jit = Some(Box::new(Jit::new(self)?));
+        self.memory.written = Some(Vec::new());
+        Ok(())
+    }
+
+    /**
+     * disable_jit drops the JIT and stops logging writes for it
+     */
+    #[cfg(feature = "jit")]
+    fn disable_jit(&mut self) {
+        self.jit = None;
+        self.memory.written = None;
+    }
+
+    /**
+     * step runs the next instruction and updates the timers, the same as
+     *    one pass of the emulator's main loop. With the JIT enabled it
+     *    may run a whole compiled block instead. Returns how many
+     *    instructions ran, or why the next one could not.
+     */
+    pub fn step(&mut self) -> Result<u32, CpuError> {
+        #[cfg(feature = "jit")]
+        if let Some(mut jit) = self.jit.take() {
+            let executed = jit.step(self);
+            self.jit = Some(jit);
+            return executed;
+        }
+        self.execute_next_opcode()?;
+        self.update_timers();
+        Ok(1)
+    }
+
+    /**
+     * execute_next_opcode runs the instruction at pc. If it cannot run,
+     *    nothing changes and the error says why.
+     */
+    pub fn execute_next_opcode(&mut self) -> Result<(), CpuError> {
+        let opcode = self.memory.get_opcode(self.pc)?;
         self.d_flag = false;
 
         // Parts of the opcode that are used by various instructions
@@ -62,40 +381,75 @@ impl CPU {
         match opcode {
             0x00E0 => {
                 // CLS: Clear screen
-                for i in 0..VIDEO_HEIGHT {
-                    for j in 0..VIDEO_WIDTH {
-                        self.mmio.video_memory[i][j] = 0;        
-                    }
-                }
+                self.mmio.clear();
                 self.d_flag = true;
                 self.pc += 2;
-                return
+                Ok(())
             },
             0x00EE => {
                 // RET: Return from subroutine
+                if self.sp == 0 {
+                    return Err(CpuError::StackUnderflow);
+                }
                 self.pc = self.stack[self.sp];
                 self.sp -= 1;
                 self.pc += 2;
-                return
+                Ok(())
             }
+            0x00C0..=0x00CF if self.schip => {
+                // SCD nibble: Scroll the screen down nibble rows
+                self.mmio.scroll_down(nibble as usize);
+                self.d_flag = true;
+                self.pc += 2;
+                Ok(())
+            },
+            0x00FB if self.schip => {
+                // SCR: Scroll the screen right 4 pixels
+                self.mmio.scroll_right(4);
+                self.d_flag = true;
+                self.pc += 2;
+                Ok(())
+            },
+            0x00FC if self.schip => {
+                // SCL: Scroll the screen left 4 pixels
+                self.mmio.scroll_left(4);
+                self.d_flag = true;
+                self.pc += 2;
+                Ok(())
+            },
+            0x00FD if self.schip => {
+                // EXIT: Stop the program, running this again for as long as it is stepped
+                self.exited = true;
+                Ok(())
+            },
+            0x00FE | 0x00FF if self.schip => {
+                // LOW / HIGH: Switch to 64x32 or 128x64, clearing the screen
+                self.mmio.set_hires(opcode == 0x00FF);
+                self.d_flag = true;
+                self.pc += 2;
+                Ok(())
+            },
             _ => {
                 match opcode & 0xF000 {
                     0x0000 => {
                         // SYS: This instruction is ignored in modern interpreters
                         self.pc += 2;
-                        return
+                        Ok(())
                     },
                     0x1000 => {
                         // JP addr: Jump to addr
                         self.pc = addr;
-                        return
+                        Ok(())
                     },
                     0x2000 => {
-                        // CALL addr: Call subroutine
+                        // CALL addr: Call subroutine. Stack slot 0 is never used.
+                        if self.sp + 1 >= self.stack.len() {
+                            return Err(CpuError::StackOverflow);
+                        }
                         self.sp += 1;
                         self.stack[self.sp] = self.pc;
                         self.pc = addr;
-                        return
+                        Ok(())
                     },
                     0x3000 => {
                         //SE Vx, byte: Skip next instruction if register[x_val] == byte
@@ -103,7 +457,7 @@ impl CPU {
                             self.pc += 2;
                         }
                         self.pc += 2;
-                        return
+                        Ok(())
                     },
                     0x4000 => {
                         //SNE Vx, byte: Skip next instruction if register[x_val] != byte
@@ -111,7 +465,7 @@ impl CPU {
                             self.pc += 2;
                         }
                         self.pc += 2;
-                        return
+                        Ok(())
                     },
                     0x5000 => {
                         //SE Vx, Vy: Skip next instruction if register[x_val] == register[y_val]
@@ -119,20 +473,20 @@ impl CPU {
                             self.pc += 2;
                         }
                         self.pc += 2;
-                        return
+                        Ok(())
                     },
                     0x6000 => {
                         // LD Vx, byte: Load byte into register[x_val]
                         self.gp_registers[x_val] = byte;
                         self.pc += 2;
-                        return
+                        Ok(())
                     },
                     0x7000 => {
                         // Add Vx, byte: Add byte to register[x_val]
                         let added_val = self.gp_registers[x_val] as u16 + byte as u16;
                         self.gp_registers[x_val] = added_val as u8;
                         self.pc += 2;
-                        return
+                        Ok(())
                     },
                     0x8000 => {
                         match opcode & 0x000F {
@@ -140,25 +494,28 @@ impl CPU {
                                 // LD Vx, Vy: Store val of register[y_val] in register[x_val]
                                 self.gp_registers[x_val] = self.gp_registers[y_val];
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0001 => {
                                 // OR Vx, Vy: Perform bitwise OR on register[x_val] and register[y_val] and store in regX
-                                self.gp_registers[x_val] = self.gp_registers[x_val] | self.gp_registers[y_val];
+                                self.gp_registers[x_val] |= self.gp_registers[y_val];
+                                self.reset_vf();
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0002 => {
                                 // AND Vx, Vy: Perform bitwise AND on regX and regY and store in regX
-                                self.gp_registers[x_val] = self.gp_registers[x_val] & self.gp_registers[y_val];
+                                self.gp_registers[x_val] &= self.gp_registers[y_val];
+                                self.reset_vf();
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0003 => {
                                 // XOR Vx, Vy: XOR on regX and regY store in regX
-                                self.gp_registers[x_val] = self.gp_registers[x_val] ^ self.gp_registers[y_val];
+                                self.gp_registers[x_val] ^= self.gp_registers[y_val];
+                                self.reset_vf();
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0004 => {
                                 // Add Vx, Vy: Set regX = regX + regY, set regF to 1 if the value is greater than 8 bits
@@ -166,37 +523,41 @@ impl CPU {
                                 self.gp_registers[0xF] = if temp > 255 { 1 } else { 0 };
                                 self.gp_registers[x_val] = temp as u8;
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0005 => {
                                 // SUB Vx, Vy: Set regX = regX - regY, set regF to 1 if there is no borrow (regX > regY)
                                 self.gp_registers[0xF] = if self.gp_registers[x_val] > self.gp_registers[y_val] { 1 } else { 0 };
                                 self.gp_registers[x_val] = self.gp_registers[x_val].wrapping_sub(self.gp_registers[y_val]);
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0006 => {
                                 // SHR Vx: If least-significant digit of regX is 1, set VF to 1, else 0. Divide regX by 2
-                                self.gp_registers[0xF] = self.gp_registers[x_val] & 0x01;
-                                self.gp_registers[x_val] = self.gp_registers[x_val] >> 1;
+                                // With the shift_vy quirk regY is shifted into regX instead
+                                let source = self.shift_source(x_val, y_val);
+                                self.gp_registers[0xF] = source & 0x01;
+                                self.gp_registers[x_val] = source >> 1;
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0007 => {
                                 // SUBN Vx, Vy: Set regX = regY - regX, set regF to 1 if there is no borrow (regY > regX)
                                 self.gp_registers[0xF] = if self.gp_registers[y_val] > self.gp_registers[x_val] { 1 } else { 0 };
                                 self.gp_registers[x_val] = self.gp_registers[y_val].wrapping_sub(self.gp_registers[x_val]);
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x000E => {
                                 // SHL Vx: If most-significant digit of regX is 1, set VF to 1, else 0. Multiply regX by 2
-                                self.gp_registers[0xF] = self.gp_registers[x_val] & 0x80 >> 7;
-                                self.gp_registers[x_val] = self.gp_registers[x_val] << 1;
+                                // With the shift_vy quirk regY is shifted into regX instead
+                                let source = self.shift_source(x_val, y_val);
+                                self.gp_registers[0xF] = (source & 0x80) >> 7;
+                                self.gp_registers[x_val] = source << 1;
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
-                            _ => {println!("Unknown opcode: {}", opcode); self.pc += 2; return}
+                            _ => Err(CpuError::UnknownOpcode(opcode))
                         }
                     },
                     0x9000 => {
@@ -205,70 +566,72 @@ impl CPU {
                             self.pc += 2;
                         }
                         self.pc += 2;
-                        return
+                        Ok(())
                     },
                     0xA000 => {
                         // LD I, addr: Set I = addr
                         self.i = addr;
                         self.pc += 2;
-                        return
+                        Ok(())
                     },
                     0xB000 => {
                         // JP V0, addr: Jump to location addr + V0
-                        self.pc = self.gp_registers[0] as u16 + addr;
-                        return
+                        // With the jump_vx quirk it is BXNN, jumping to XNN + regX
+                        let offset = if self.quirks.jump_vx { self.gp_registers[x_val] } else { self.gp_registers[0] };
+                        self.pc = offset as u16 + addr;
+                        Ok(())
                     },
                     0xC000 => {
                         // RND Vx: Set regX = random byte AND byte
-                        let rand_num: u8 = random();
+                        let rand_num: u8 = self.random_byte();
                         self.gp_registers[x_val] = rand_num & byte;
                         self.pc += 2;
-                        return
+                        Ok(())
                     },
                     0xD000 => {
                         // DRW Vx, Vy, nibble: Display nibble-byte sprite stored at mem loc I at
                         // (regX, regY) on the screen. Set VF to 1 if there is a collision between pixels
-                        self.gp_registers[0x0f] = 0;
-                        for current in 0..(nibble as usize) {
-                            // read up to nibble bytes
-                            let y = (self.gp_registers[y_val] as usize + current) % VIDEO_HEIGHT;
-                            for bit in 0..8 {
-                                let x = (self.gp_registers[x_val] as usize + bit) % VIDEO_WIDTH;
-                                // get bit and shift to place
-                                let colored = self.memory.read_byte(self.i + (current as u16)) >> (7-bit) & 1;
-                                // set Vf
-                                self.gp_registers[0x0f] |= colored & self.mmio.video_memory[y][x];
-                                // set actual color
-                                self.mmio.video_memory[y][x] ^= colored;
-                            }
+                        // With the display_wait quirk it runs again until a frame starts
+                        if self.quirks.display_wait && !self.frame_started {
+                            return Ok(())
                         }
+                        // In SUPER-CHIP DXY0 draws a 16x16 sprite of 32 bytes, two per row
+                        let (length, row_bytes) = if self.schip && nibble == 0 { (32, 2) } else { (nibble, 1) };
+                        self.check_memory(length)?;
+                        let sprite = (0..length)
+                            .map(|current| self.memory.read_byte(self.i + current))
+                            .collect::<Result<Vec<u8>, CpuError>>()?;
+                        let x = self.gp_registers[x_val];
+                        let y = self.gp_registers[y_val];
+                        // set Vf if any pixel was erased
+                        self.gp_registers[0x0f] = self.mmio.blit(x, y, &sprite, row_bytes, self.quirks.clip_sprites);
+                        self.frame_started = false;
 
                         self.d_flag = true;
                         self.pc += 2;
-                        return
+                        Ok(())
                     },
                     0xE000 => {
                         match opcode & 0x00FF {
                             0x009E => {
                                 // SKP Vx: Skip next instruction if key with value regX is pressed
-                                let key = self.gp_registers[x_val] as usize;
-                                if self.mmio.input_memory[key] {
+                                if self.key_pressed(x_val) {
                                     self.pc += 4
                                 } else {
                                     self.pc += 2;
                                 }
+                                Ok(())
                             },
                             0x00A1 => {
                                 // SKNP Vx: Skip next instruction if key with value regX is not pressed
-                                let key = self.gp_registers[x_val] as usize;
-                                if !self.mmio.input_memory[key] {
+                                if !self.key_pressed(x_val) {
                                     self.pc += 4
                                 } else {
                                     self.pc += 2;
                                 }
-                                return
+                                Ok(())
                             },
-                            _ => {println!("Unknown opcode: {}", opcode); self.pc += 2; return}
+                            _ => Err(CpuError::UnknownOpcode(opcode))
                         }
                     },
                     0xF000 => {
@@ -277,7 +640,7 @@ impl CPU {
                                 // LD VX, DT: Set regX = delay timer value
                                 self.gp_registers[x_val] = self.dt;
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x000A => {
                                 // LD Vx, K: Wait for a key press then store that key val in regX
@@ -285,28 +648,28 @@ impl CPU {
                                     if *v {
                                         self.pc += 2;
                                         self.gp_registers[x_val] = i as u8;
-                                        return
+                                        return Ok(())
                                     }
                                 }
-                                return 
+                                Ok(())
                             },
                             0x0015 => {
                                 // LD DT, Vx: Set delay time = regX
                                 self.dt = self.gp_registers[x_val];
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0018 => {
                                 // LD ST, VX: Set sound timer = regX
                                 self.st = self.gp_registers[x_val];
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x001E => {
                                 // ADD I, VX: Set I = I + regX
-                                self.i += self.gp_registers[x_val] as u16;
+                                self.i = self.i.wrapping_add(self.gp_registers[x_val] as u16);
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0029 => {
                                 // LD F, Vx: Set I = location in memory for the hex font sprite for digit regX
@@ -314,45 +677,148 @@ impl CPU {
                                 // All font sprites start at location (their decimal value times 5)
                                 self.i = font_digit * 5;
                                 self.pc += 2;
-                                return
+                                Ok(())
+                            },
+                            0x0030 if self.schip => {
+                                // LD HF, Vx: Set I = location in memory for the big font sprite for digit regX
+                                let font_digit: u16 = self.gp_registers[x_val] as u16;
+                                self.i = BIG_FONT_ADDRESS + font_digit * 10;
+                                self.pc += 2;
+                                Ok(())
                             },
                             0x0033 => {
                                 // LD B, Vx: Store BCD representation of regX in mem locations I, I+1, I+2
+                                self.check_memory(3)?;
                                 let mut num: u8 = self.gp_registers[x_val];
-                                self.memory.write_byte(self.i, num / 100);
+                                self.memory.write_byte(self.i, num / 100)?;
                                 num %= 100;
-                                self.memory.write_byte(self.i + 1, num / 10);
+                                self.memory.write_byte(self.i + 1, num / 10)?;
                                 num %= 10;
-                                self.memory.write_byte(self.i + 2, num);
+                                self.memory.write_byte(self.i + 2, num)?;
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0055 => {
                                 // LD [I], Vx: Store registers reg0 through regX in mem starting at I
+                                self.check_memory(x_val as u16 + 1)?;
                                 for i in 0..=x_val {
-                                    self.memory.write_byte(self.i + i as u16, self.gp_registers[i]);
+                                    self.memory.write_byte(self.i + i as u16, self.gp_registers[i])?;
                                 }
+                                self.increment_i(x_val);
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
                             0x0065 => {
                                 // LD Vx, [I]: Read registers reg0 through regX from mem starting at I
+                                self.check_memory(x_val as u16 + 1)?;
                                 for i in 0..=x_val {
-                                    self.gp_registers[i] = self.memory.read_byte(self.i + i as u16);
+                                    self.gp_registers[i] = self.memory.read_byte(self.i + i as u16)?;
                                 }
+                                self.increment_i(x_val);
                                 self.pc += 2;
-                                return
+                                Ok(())
                             },
-                            _ => {println!("Unknown opcode: {}", opcode); self.pc += 2; return}
+                            0x0075 if self.schip && x_val < self.rpl.len() => {
+                                // LD R, Vx: Store registers reg0 through regX in the RPL user flags
+                                self.rpl[..=x_val].copy_from_slice(&self.gp_registers[..=x_val]);
+                                self.pc += 2;
+                                Ok(())
+                            },
+                            0x0085 if self.schip && x_val < self.rpl.len() => {
+                                // LD Vx, R: Read registers reg0 through regX from the RPL user flags
+                                self.gp_registers[..=x_val].copy_from_slice(&self.rpl[..=x_val]);
+                                self.pc += 2;
+                                Ok(())
+                            },
+                            _ => Err(CpuError::UnknownOpcode(opcode))
                         }
                     },
-                    _ => {println!("Unknown opcode: {}", opcode); self.pc += 2; return}
+                    _ => Err(CpuError::UnknownOpcode(opcode))
                 }
             }
         }
     }
 
+    /**
+     * reset_vf clears VF after 8XY1, 8XY2 and 8XY3 with the reset_vf quirk
+     */
+    fn reset_vf(&mut self) {
+        if self.quirks.reset_vf {
+            self.gp_registers[0xF] = 0;
+        }
+    }
+
+    /**
+     * shift_source returns the register 8XY6 and 8XYE shift, which is regY
+     *    with the shift_vy quirk and regX without
+     */
+    fn shift_source(&self, x: usize, y: usize) -> u8 {
+        if self.quirks.shift_vy {
+            self.gp_registers[y]
+        } else {
+            self.gp_registers[x]
+        }
+    }
+
+    /**
+     * increment_i moves I after FX55 and FX65 have used registers 0 through
+     *    x, as far as the increment_i quirk says
+     */
+    fn increment_i(&mut self, x: usize) {
+        match self.quirks.increment_i {
+            IncrementI::None => {}
+            IncrementI::X => self.i += x as u16,
+            IncrementI::XPlusOne => self.i += x as u16 + 1,
+        }
+    }
+
+    /**
+     * key_pressed returns true if the key in register x is held down.
+     *    Values past the last key are never pressed.
+     */
+    fn key_pressed(&self, x: usize) -> bool {
+        let key = self.gp_registers[x] as usize;
+        self.mmio.input_memory.get(key).copied().unwrap_or(false)
+    }
+
+    /**
+     * check_memory returns an error unless the length bytes from I are all
+     *    in memory, so that stores and loads happen completely or not at all
+     */
+    fn check_memory(&self, length: u16) -> Result<(), CpuError> {
+        let end = self.i as usize + length as usize;
+        if end > self.memory.rom.len() {
+            let first = (self.i as usize).max(self.memory.rom.len());
+            return Err(CpuError::MemoryOutOfBounds(first as u16));
+        }
+        Ok(())
+    }
+
+    /**
+     * seed_random restarts RND from a fixed seed, so that two CPUs seeded
+     *    the same draw the same bytes
+     */
+    pub fn seed_random(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /**
+     * random_byte draws the next byte RND would use
+     */
+    pub fn random_byte(&mut self) -> u8 {
+        self.rng.gen()
+    }
+
+    /**
+     * update_timers is called once after every instruction, and counts DT
+     *    and ST down after the instructions timers_due picks
+     */
     pub fn update_timers(&mut self) {
+        self.instructions += 1;
+        if !timers_due(self.instructions) {
+            return;
+        }
+        self.frame_started = true;
         if self.dt > 0 {
             self.dt -= 1;
         }
@@ -365,4 +831,242 @@ impl CPU {
     pub fn get_draw_flag(&self) -> bool {
         self.d_flag
     }
-}
\ No newline at end of file
+
+    pub fn get_pc(&self) -> u16 {
+        self.pc
+    }
+
+    /**
+     * exited returns true once a SUPER-CHIP program has run 00FD
+     */
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /**
+     * next_opcode returns the opcode at pc, which runs next
+     */
+    pub fn next_opcode(&self) -> Result<u16, CpuError> {
+        self.memory.get_opcode(self.pc)
+    }
+
+    /**
+     * instructions returns how many instructions update_timers has counted
+     */
+    pub fn instructions(&self) -> u64 {
+        self.instructions
+    }
+
+    /**
+     * set_instructions carries on counting from another executor's count,
+     *    so the timers count down on the same instructions
+     */
+    pub fn set_instructions(&mut self, instructions: u64) {
+        self.instructions = instructions;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /**
+     * run makes a CPU for program with the given quirks and runs its first
+     *    count instructions
+     */
+    fn run(program: &[u8], quirks: Quirks, count: usize) -> CPU {
+        let mut cpu = CPU::from_bytes(program);
+        cpu.set_quirks(quirks);
+        for _ in 0..count {
+            cpu.execute_next_opcode().unwrap();
+        }
+        cpu
+    }
+
+    #[test]
+    fn fx55_and_fx65_move_i_as_far_as_each_preset_says() {
+        // LD I, 0x300; LD V0, 0x11; LD V1, 0x22; LD [I], V1; LD V1, [I]
+        let program = [0xA3, 0x00, 0x60, 0x11, 0x61, 0x22, 0xF1, 0x55, 0xF1, 0x65];
+        let presets =
+            [(Quirks::COSMAC_VIP, 0x304), (Quirks::CHIP_48, 0x302), (Quirks::SCHIP, 0x300), (Quirks::MODERN, 0x300)];
+        for (quirks, i) in presets {
+            let cpu = run(&program, quirks, 5);
+            assert_eq!(cpu.i, i, "{:?}", quirks);
+        }
+    }
+
+    #[test]
+    fn shl_sets_vf_to_the_bit_shifted_out() {
+        // LD V1, 0x81; SHL V1
+        let cpu = run(&[0x61, 0x81, 0x81, 0x1E], Quirks::MODERN, 2);
+        assert_eq!((cpu.gp_registers[1], cpu.gp_registers[0xF]), (0x02, 1));
+        // LD V2, 0x40; SHL V2
+        let cpu = run(&[0x62, 0x40, 0x82, 0x2E], Quirks::MODERN, 2);
+        assert_eq!((cpu.gp_registers[2], cpu.gp_registers[0xF]), (0x80, 0));
+    }
+
+    #[test]
+    fn shifts_of_vf_use_vf_from_before_the_flag_is_set() {
+        // LD VF, 0x02; SHR V1, VF
+        let cpu = run(&[0x6F, 0x02, 0x81, 0xF6], Quirks::COSMAC_VIP, 2);
+        assert_eq!((cpu.gp_registers[1], cpu.gp_registers[0xF]), (0x01, 0));
+        // LD VF, 0xC1; SHL V1, VF
+        let cpu = run(&[0x6F, 0xC1, 0x81, 0xFE], Quirks::COSMAC_VIP, 2);
+        assert_eq!((cpu.gp_registers[1], cpu.gp_registers[0xF]), (0x82, 1));
+    }
+
+    /**
+     * schip makes a SUPER-CHIP CPU for program and runs its first count
+     *    instructions
+     */
+    fn schip(program: &[u8], count: usize) -> CPU {
+        let mut cpu = CPU::from_bytes(program);
+        cpu.set_quirks(Quirks::SCHIP);
+        cpu.enable_schip();
+        for _ in 0..count {
+            cpu.execute_next_opcode().unwrap();
+        }
+        cpu
+    }
+
+    /**
+     * lit lists the pixels that are on, as (x, y)
+     */
+    fn lit(cpu: &CPU) -> Vec<(usize, usize)> {
+        let mut pixels = Vec::new();
+        for (y, row) in cpu.mmio.video_memory.iter().enumerate() {
+            for (x, pixel) in row.iter().enumerate() {
+                if *pixel != 0 {
+                    pixels.push((x, y));
+                }
+            }
+        }
+        pixels
+    }
+
+    #[test]
+    fn schip_scrolls_down_right_and_left() {
+        // LD I, 0x20C; DRW V0, V0, 1; SCD 3; SCR; SCL; JP 0x20A; a sprite of one pixel
+        let program = [0xA2, 0x0C, 0xD0, 0x01, 0x00, 0xC3, 0x00, 0xFB, 0x00, 0xFC, 0x12, 0x0A, 0x80];
+        let mut cpu = schip(&program, 2);
+        assert_eq!(lit(&cpu), [(0, 0)]);
+        cpu.execute_next_opcode().unwrap();
+        assert_eq!(lit(&cpu), [(0, 3)]);
+        cpu.execute_next_opcode().unwrap();
+        assert_eq!(lit(&cpu), [(4, 3)]);
+        cpu.execute_next_opcode().unwrap();
+        assert_eq!(lit(&cpu), [(0, 3)]);
+    }
+
+    #[test]
+    fn schip_switches_resolution_and_clears_the_screen() {
+        // HIGH; LD I, 0x20A; LD V0, 100; DRW V0, V0, 1; LOW; a sprite of one pixel
+        let program = [0x00, 0xFF, 0xA2, 0x0A, 0x60, 0x64, 0xD0, 0x01, 0x00, 0xFE, 0x80];
+        let mut cpu = schip(&program, 4);
+        assert!(cpu.mmio.hires);
+        assert_eq!((cpu.mmio.width(), cpu.mmio.height()), (128, 64));
+        assert_eq!(lit(&cpu), [(100, 100 % 64)]);
+        cpu.execute_next_opcode().unwrap();
+        assert!(!cpu.mmio.hires);
+        assert_eq!((cpu.mmio.width(), cpu.mmio.height()), (64, 32));
+        assert_eq!(lit(&cpu), []);
+    }
+
+    #[test]
+    fn schip_dxy0_draws_16x16_and_sets_vf_on_collision() {
+        // HIGH; LD I, 0x20A; DRW V0, V1, 0; DRW V0, V1, 0; JP 0x208; 32 bytes of a filled sprite
+        let mut program = vec![0x00, 0xFF, 0xA2, 0x0A, 0xD0, 0x10, 0xD0, 0x10, 0x12, 0x08];
+        program.extend([0xFF; 32]);
+        let mut cpu = schip(&program, 3);
+        let square: Vec<(usize, usize)> = (0..16).flat_map(|y| (0..16).map(move |x| (x, y))).collect();
+        assert_eq!(lit(&cpu), square);
+        assert_eq!(cpu.gp_registers[0xF], 0);
+        cpu.execute_next_opcode().unwrap();
+        assert_eq!(lit(&cpu), []);
+        assert_eq!(cpu.gp_registers[0xF], 1);
+    }
+
+    #[test]
+    fn schip_fx30_points_i_at_the_big_font() {
+        // LD V3, 7; LD HF, V3
+        let cpu = schip(&[0x63, 0x07, 0xF3, 0x30], 2);
+        assert_eq!(cpu.i, BIG_FONT_ADDRESS + 70);
+        let digit: Vec<u8> = (0..10).map(|n| cpu.memory.read_byte(cpu.i + n).unwrap()).collect();
+        assert_eq!(digit, [0xFF, 0xFF, 0x03, 0x03, 0x06, 0x0C, 0x18, 0x18, 0x18, 0x18]);
+    }
+
+    #[test]
+    fn schip_fx75_and_fx85_round_trip_the_rpl_flags() {
+        // LD V0, 1; LD V1, 2; LD V2, 3; LD R, V2; LD V0, 0; LD V1, 0; LD V2, 0; LD V1, R
+        let program = [0x60, 0x01, 0x61, 0x02, 0x62, 0x03, 0xF2, 0x75, 0x60, 0x00, 0x61, 0x00, 0x62, 0x00, 0xF1, 0x85];
+        let cpu = schip(&program, 8);
+        assert_eq!(cpu.rpl, [1, 2, 3, 0, 0, 0, 0, 0]);
+        assert_eq!(cpu.gp_registers[..3], [1, 2, 0]);
+    }
+
+    #[test]
+    #[cfg(feature = "jit")]
+    fn writes_are_only_logged_while_there_is_a_jit() {
+        // LD I, 0x300; LD [I], V3
+        let program = [0xA3, 0x00, 0xF3, 0x55];
+        let cpu = run(&program, Quirks::MODERN, 2);
+        assert_eq!(cpu.memory.written, None);
+        let mut cpu = CPU::from_bytes(&program);
+        cpu.enable_jit().unwrap();
+        cpu.execute_next_opcode().unwrap();
+        cpu.execute_next_opcode().unwrap();
+        assert_eq!(cpu.memory.written.as_deref(), Some(&[0x300, 0x301, 0x302, 0x303][..]));
+        cpu.set_quirks(Quirks::COSMAC_VIP);
+        assert_eq!(cpu.memory.written, None);
+    }
+
+    /**
+     * fault runs program until an instruction fails, and returns why
+     */
+    fn fault(program: &[u8]) -> CpuError {
+        let mut cpu = CPU::from_bytes(program);
+        for _ in 0..100 {
+            if let Err(error) = cpu.execute_next_opcode() {
+                return error;
+            }
+        }
+        panic!("no fault within 100 instructions")
+    }
+
+    #[test]
+    fn unknown_opcodes_fault() {
+        assert_eq!(fault(&[0x80, 0x08]), CpuError::UnknownOpcode(0x8008));
+    }
+
+    #[test]
+    fn fifteen_calls_fit_on_the_stack() {
+        // CALL 0x202; CALL 0x204; ... so that every CALL nests one deeper
+        let program: Vec<u8> = (0..16u16).flat_map(|n| (0x2202 + 2 * n).to_be_bytes()).collect();
+        let mut cpu = CPU::from_bytes(&program);
+        for _ in 0..15 {
+            cpu.execute_next_opcode().unwrap();
+        }
+        assert_eq!(cpu.execute_next_opcode(), Err(CpuError::StackOverflow));
+        assert_eq!(cpu.get_pc(), 0x21E);
+    }
+
+    #[test]
+    fn ret_with_an_empty_stack_faults() {
+        assert_eq!(fault(&[0x00, 0xEE]), CpuError::StackUnderflow);
+    }
+
+    #[test]
+    fn memory_faults_report_the_first_address_past_memory() {
+        // LD I, 0xFFF; LD [I], V1
+        assert_eq!(fault(&[0xAF, 0xFF, 0xF1, 0x55]), CpuError::MemoryOutOfBounds(0x1000));
+        // LD I, 0xFFE; DRW V0, V0, 3
+        assert_eq!(fault(&[0xAF, 0xFE, 0xD0, 0x03]), CpuError::MemoryOutOfBounds(0x1000));
+        // LD I, 0xFFF; LD V0, 0x10; ADD I, V0; LD V0, [I]
+        assert_eq!(fault(&[0xAF, 0xFF, 0x60, 0x10, 0xF0, 0x1E, 0xF0, 0x65]), CpuError::MemoryOutOfBounds(0x100F));
+    }
+
+    #[test]
+    fn running_off_the_end_of_memory_faults() {
+        // JP 0xFFF, where only one byte of an opcode fits
+        assert_eq!(fault(&[0x1F, 0xFF]), CpuError::PcOutOfBounds(0xFFF));
+    }
+}
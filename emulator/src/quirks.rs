@@ -0,0 +1,107 @@
+/**
+ * quirks.rs
+ *  this file holds the behaviours CHIP-8 interpreters disagree on. Programs
+ *  were written against whichever interpreter their author had, so many
+ *  only run right with that interpreter's combination. The presets are
+ *  the combinations of the best known interpreters:
+ *
+ *  ```text
+ *                  shift VY  increment I  BXNN  reset VF  clip  display wait
+ *      COSMAC VIP     yes       by X+1     no      yes     yes      yes
+ *      CHIP-48        no        by X       yes     no      yes      no
+ *      SCHIP          no         no        yes     no      yes      no
+ *      modern         no         no        no      no      no       no
+ *  ```
+ *
+ *  Modern is what the recompilers implement and what the CPU starts with.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncrementI {
+    // I is left alone
+    None,
+    // I ends up on the last register, as CHIP-48 leaves it
+    X,
+    // I ends up just past the last register, as the COSMAC VIP leaves it
+    XPlusOne,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quirks {
+    // 8XY6 and 8XYE shift VY into VX, rather than shifting VX in place
+    pub shift_vy: bool,
+    // How far FX55 and FX65 move I
+    pub increment_i: IncrementI,
+    // BXNN jumps to XNN + VX, rather than BNNN jumping to NNN + V0
+    pub jump_vx: bool,
+    // 8XY1, 8XY2 and 8XY3 clear VF
+    pub reset_vf: bool,
+    // Sprites are cut off at the edges of the screen, rather than wrapping
+    pub clip_sprites: bool,
+    // DXYN waits for the start of a frame before drawing, so at most one
+    // sprite is drawn each frame
+    pub display_wait: bool,
+}
+
+impl Quirks {
+    // The original interpreter, on the RCA COSMAC VIP
+    pub const COSMAC_VIP: Quirks = Quirks {
+        shift_vy: true,
+        increment_i: IncrementI::XPlusOne,
+        jump_vx: false,
+        reset_vf: true,
+        clip_sprites: true,
+        display_wait: true,
+    };
+
+    // CHIP-48, on the HP-48 calculators
+    pub const CHIP_48: Quirks = Quirks {
+        shift_vy: false,
+        increment_i: IncrementI::X,
+        jump_vx: true,
+        reset_vf: false,
+        clip_sprites: true,
+        display_wait: false,
+    };
+
+    // SUPER-CHIP 1.1, which grew out of CHIP-48
+    pub const SCHIP: Quirks = Quirks {
+        shift_vy: false,
+        increment_i: IncrementI::None,
+        jump_vx: true,
+        reset_vf: false,
+        clip_sprites: true,
+        display_wait: false,
+    };
+
+    // What most emulators written since do
+    pub const MODERN: Quirks = Quirks {
+        shift_vy: false,
+        increment_i: IncrementI::None,
+        jump_vx: false,
+        reset_vf: false,
+        clip_sprites: false,
+        display_wait: false,
+    };
+
+    // Every preset, by the name it is chosen with
+    pub const PRESETS: &'static [(&'static str, Quirks)] = &[
+        ("vip", Quirks::COSMAC_VIP),
+        ("chip48", Quirks::CHIP_48),
+        ("schip", Quirks::SCHIP),
+        ("modern", Quirks::MODERN),
+    ];
+
+    /**
+     * from_name returns the preset with the given name, if there is one
+     */
+    pub fn from_name(name: &str) -> Option<Quirks> {
+        Quirks::PRESETS.iter().find(|(preset, _)| *preset == name).map(|&(_, quirks)| quirks)
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Quirks::MODERN
+    }
+}
@@ -0,0 +1,58 @@
+/**
+ * fallback.rs
+ *  runs the emulator's interpreter over code the recompiler never saw,
+ *  such as BNNN targets and code the program patched at runtime, until
+ *  execution gets back to recompiled code that is still valid.
+ */
+use crate::state::Chip8State;
+
+/**
+ * interpret hands the whole machine to the interpreter and steps it the
+ *    same way the emulator's main loop does, until pc reaches the start of
+ *    a recompiled block whose code has not been patched, or execution
+ *    stops, which includes the interpreter faulting. The machine is then
+ *    copied back for recompiled code to carry on from.
+ */
+pub fn interpret(state: &mut Chip8State) {
+    let cpu_state = state.to_cpu_state();
+    state.host.cpu.load_state(&cpu_state);
+    // The interpreter updates the timers by its own count of instructions
+    state.host.cpu.set_instructions(state.host.instructions);
+
+    loop {
+        let host = &mut state.host;
+        if let Err(error) = host.cpu.execute_next_opcode() {
+            // pc is still on the instruction at fault, for chip8_rt_exit to report
+            state.fault(error.to_string());
+            break;
+        }
+        host.instructions += 1;
+        if host.cpu.get_draw_flag() {
+            host.draw_flag = true;
+        }
+        host.present();
+        host.cpu.update_timers();
+        host.pace();
+        host.poll_input();
+        if host.observer.is_some() {
+            let cpu_state = host.cpu.save_state();
+            state.load_cpu_state(&cpu_state);
+            state.instruction_done();
+        }
+        if !state.running() {
+            break;
+        }
+
+        // Patched code only shows up in the interpreter's copy of memory
+        let cpu_state = state.host.cpu.save_state();
+        if state.host.blocks.binary_search_by_key(&cpu_state.pc, |&(start, _)| start).is_ok() {
+            state.load_cpu_state(&cpu_state);
+            if state.resumable(cpu_state.pc) {
+                return;
+            }
+        }
+    }
+
+    let cpu_state = state.host.cpu.save_state();
+    state.load_cpu_state(&cpu_state);
+}
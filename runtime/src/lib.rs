@@ -0,0 +1,264 @@
+/**
+ * lib.rs
+ *  the host runtime recompiled chip8 programs link against. Generated code
+ *  owns nothing but its registers: the screen, keypad, timers, random
+ *  numbers and font memory all live here and are reached through the
+ *  chip8_rt_* functions below, which use the C ABI.
+ */
+mod fallback;
+mod state;
+
+pub use state::{Chip8State, Host, Observer, Status};
+
+use std::slice;
+
+/**
+ * chip8_rt_init sets up the machine for a program of len bytes and opens
+ *    the window. The returned state is freed by chip8_rt_exit.
+ *
+ * # Safety
+ * rom must point to len readable bytes.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_init(rom: *const u8, len: u32) -> *mut Chip8State {
+    let program = slice::from_raw_parts(rom, len as usize);
+    let mut state = Box::new(Chip8State::new(program));
+    state.host.poll_input();
+    Box::into_raw(state)
+}
+
+/**
+ * chip8_rt_run calls the recompiled chip8_run until execution stops. When
+ *    it reaches an address with no recompiled code, or code the program
+ *    has patched, the interpreter takes over until execution is back at an
+ *    unpatched recompiled block.
+ *
+ * # Safety
+ * state must come from chip8_rt_init and blocks must point to count pairs
+ * of block start and end addresses, in ascending order of start.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_run(
+    state: *mut Chip8State,
+    run: unsafe extern "C" fn(*mut Chip8State),
+    blocks: *const u16,
+    count: u32,
+) {
+    let ranges = slice::from_raw_parts(blocks, count as usize * 2);
+    (*state).host.blocks = ranges.chunks(2).map(|range| (range[0], range[1])).collect();
+    while (*state).running() {
+        run(state);
+        if let Status::Unresolved(_) = (*state).host.status {
+            (*state).host.status = Status::Running;
+            fallback::interpret(&mut *state);
+        }
+    }
+}
+
+/**
+ * chip8_rt_exit reports why execution stopped, frees the state and
+ *    returns the process exit code.
+ *
+ * # Safety
+ * state must come from chip8_rt_init and is invalid afterwards.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_exit(state: *mut Chip8State) -> i32 {
+    let state = Box::from_raw(state);
+    match &state.host.status {
+        Status::Running | Status::Quit => 0,
+        Status::Unresolved(pc) => {
+            eprintln!("No recompiled code for address 0x{:03X}", pc);
+            1
+        }
+        Status::Fault(message) => {
+            eprintln!("Fault at 0x{:03X}: {}", state.pc, message);
+            1
+        }
+    }
+}
+
+/**
+ * chip8_rt_tick runs between instructions: shows the screen, updates the
+ *    timers, keeps the pace and reads the keypad. synced is true at
+ *    safepoints, where generated code has written every register back to
+ *    the state; only then is the observer called, and only then does
+ *    generated code stop if this returns false.
+ *
+ * # Safety
+ * state must come from chip8_rt_init.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_tick(state: *mut Chip8State, synced: bool) -> bool {
+    let state = &mut *state;
+    state.host.instructions += 1;
+    state.host.present();
+    state.update_timers();
+    state.host.pace();
+    state.host.poll_input();
+    if synced {
+        state.instruction_done();
+    }
+    state.running()
+}
+
+/**
+ * chip8_rt_cls clears the screen
+ *
+ * # Safety
+ * state must come from chip8_rt_init.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_cls(state: *mut Chip8State) {
+    let state = &mut *state;
+    state.host.cpu.mmio.clear();
+    state.host.draw_flag = true;
+}
+
+/**
+ * chip8_rt_draw draws the n byte sprite at I to (x, y) and returns 1 if
+ *    any pixel was erased.
+ *
+ * # Safety
+ * state must come from chip8_rt_init.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_draw(state: *mut Chip8State, x: u8, y: u8, n: u8) -> u8 {
+    let state = &mut *state;
+    let start = state.i as usize;
+    let end = start + n as usize;
+    if end > state.memory.len() {
+        chip8_rt_bad_address(state, state.i);
+        return 0;
+    }
+    state.host.draw_flag = true;
+    state.host.cpu.mmio.draw_sprite(x, y, &state.memory[start..end])
+}
+
+/**
+ * chip8_rt_draw_sprite draws the n byte sprite at sprite to (x, y) and
+ *    returns 1 if any pixel was erased. Generated code passes sprites it
+ *    knows are at I and never written, straight from the program image.
+ *
+ * # Safety
+ * state must come from chip8_rt_init and sprite must point to n readable bytes.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_draw_sprite(state: *mut Chip8State, x: u8, y: u8, sprite: *const u8, n: u8) -> u8 {
+    let state = &mut *state;
+    let sprite = slice::from_raw_parts(sprite, n as usize);
+    state.host.draw_flag = true;
+    state.host.cpu.mmio.draw_sprite(x, y, sprite)
+}
+
+/**
+ * chip8_rt_key_pressed returns true if the key is held down
+ *
+ * # Safety
+ * state must come from chip8_rt_init.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_key_pressed(state: *mut Chip8State, key: u8) -> bool {
+    let state = &*state;
+    match state.host.cpu.mmio.input_memory.get(key as usize) {
+        Some(&pressed) => pressed,
+        None => false,
+    }
+}
+
+/**
+ * chip8_rt_wait_key blocks until a key is pressed and returns it. The
+ *    screen and timers keep going while it waits, and every register must
+ *    be in the state.
+ *
+ * # Safety
+ * state must come from chip8_rt_init.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_wait_key(state: *mut Chip8State) -> u8 {
+    loop {
+        let keys = (*state).host.cpu.mmio.input_memory;
+        if let Some(key) = keys.iter().position(|&pressed| pressed) {
+            return key as u8;
+        }
+        if !chip8_rt_tick(state, true) {
+            return 0;
+        }
+    }
+}
+
+/**
+ * chip8_rt_random returns a random byte for RND
+ *
+ * # Safety
+ * state must come from chip8_rt_init.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_random(state: *mut Chip8State) -> u8 {
+    // The interpreter's generator, so fallback code draws from the same stream
+    (*state).host.cpu.random_byte()
+}
+
+/**
+ * chip8_rt_bad_address stops execution after a load, store or sprite read
+ *    from start runs outside memory. Like the interpreter, it reports the
+ *    first address used that is not in memory.
+ *
+ * # Safety
+ * state must come from chip8_rt_init.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_bad_address(state: *mut Chip8State, start: u16) {
+    let state = &mut *state;
+    let first = (start as usize).max(state.memory.len());
+    state.fault(format!("Invalid memory location: 0x{:X}", first));
+}
+
+/**
+ * chip8_rt_bad_stack stops execution on a CALL with a full stack or a RET
+ *    with an empty one
+ *
+ * # Safety
+ * state must come from chip8_rt_init.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_bad_stack(state: *mut Chip8State) {
+    let state = &mut *state;
+    let message = if state.sp == 0 { "Stack underflow" } else { "Stack overflow" };
+    state.fault(message.to_string());
+}
+
+/**
+ * chip8_rt_code_written is called after a store that may have hit
+ *    recompiled code. Returns true if any block in the len bytes from addr
+ *    no longer matches the code that was lifted for it.
+ *
+ * # Safety
+ * state must come from chip8_rt_init.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_code_written(state: *mut Chip8State, addr: u16, len: u16) -> bool {
+    let state = &*state;
+    let end = addr.saturating_add(len);
+    state
+        .host
+        .blocks
+        .iter()
+        .filter(|&&(start, block_end)| start < end && addr < block_end)
+        .any(|&(start, block_end)| state.is_patched(start, block_end))
+}
+
+/**
+ * chip8_rt_unresolved is called when execution reaches a pc with no
+ *    recompiled code
+ *
+ * # Safety
+ * state must come from chip8_rt_init.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn chip8_rt_unresolved(state: *mut Chip8State) {
+    let state = &mut *state;
+    if state.running() {
+        state.host.status = Status::Unresolved(state.pc);
+    }
+}
@@ -0,0 +1,268 @@
+/**
+ * state.rs
+ *  this file holds the machine state recompiled code runs on. The fields
+ *  up to and including memory are read and written directly by generated
+ *  code, so their order and types must match %chip8.state in lift.rs.
+ *  Everything in Host is private to the runtime.
+ */
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use emulator::drivers::FileDriver;
+use emulator::processor::{timers_due, CpuState, CPU};
+
+#[cfg(feature = "sdl")]
+use emulator::drivers::{InputDriver, VideoDriver};
+
+// Same pace as the interpreter's main loop, one instruction every 2ms
+pub const EXECUTION_RATE: Duration = Duration::from_millis(2);
+
+#[repr(C)]
+pub struct Chip8State {
+    // General purpose registers
+    pub v: [u8; 16],
+    // Special registers
+    pub i: u16,
+    pub pc: u16,
+    pub sp: u16,
+    pub dt: u8,
+    pub st: u8,
+    pub stack: [u16; 16],
+    pub memory: [u8; 4096],
+    // Not visible to generated code
+    pub host: Host,
+}
+
+/**
+ * Why recompiled code stopped, or Running if it has not
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Running,
+    // The window was closed
+    Quit,
+    // Execution reached an address nothing was lifted for
+    Unresolved(u16),
+    Fault(String),
+}
+
+/**
+ * Called with the whole machine after instructions that end on a
+ * safepoint, which is every instruction run in the interpreter but only
+ * some run recompiled. Host::instructions says how many ran in all. Used
+ * by tools that check or record execution; it may stop execution by
+ * changing the status.
+ */
+pub type Observer = Box<dyn FnMut(&mut Chip8State)>;
+
+pub struct Host {
+    // Interpreter for code that was not recompiled. Its MMIO is the screen
+    // and keypad for recompiled code as well.
+    pub cpu: CPU,
+    pub draw_flag: bool,
+    pub status: Status,
+    pub last_tick: Instant,
+    // Memory as it was loaded, before the program wrote anything
+    pub original: [u8; 4096],
+    // Start and end address of every recompiled block, sorted by start
+    pub blocks: Vec<(u16, u16)>,
+    // Sleep between instructions to run at the interpreter's speed
+    pub paced: bool,
+    // Instructions run so far, recompiled or interpreted
+    pub instructions: u64,
+    pub observer: Option<Observer>,
+    #[cfg(feature = "sdl")]
+    pub platform: Platform,
+}
+
+/**
+ * The SDL window and keyboard, reusing the emulator's drivers
+ */
+#[cfg(feature = "sdl")]
+pub struct Platform {
+    // The context must outlive the drivers made from it
+    _context: sdl2::Sdl,
+    pub video: VideoDriver,
+    pub input: InputDriver,
+}
+
+#[cfg(feature = "sdl")]
+impl Platform {
+    fn new() -> Platform {
+        let context = sdl2::init().expect("Error: Cannot start SDL");
+        let video = VideoDriver::new(&context);
+        let input = InputDriver::new(&context);
+        Platform { _context: context, video, input }
+    }
+}
+
+impl Chip8State {
+    /**
+     * new loads a program and the font into memory the same way the
+     *    interpreter does and sets pc to the load address.
+     */
+    pub fn new(program: &[u8]) -> Chip8State {
+        let memory = FileDriver::from_bytes(program).rom;
+        Chip8State {
+            v: [0; 16],
+            i: 0,
+            pc: 0x200,
+            sp: 0,
+            dt: 0,
+            st: 0,
+            stack: [0; 16],
+            memory,
+            host: Host {
+                cpu: CPU::from_bytes(program),
+                draw_flag: false,
+                status: Status::Running,
+                last_tick: Instant::now(),
+                original: memory,
+                blocks: Vec::new(),
+                paced: true,
+                instructions: 0,
+                observer: None,
+                #[cfg(feature = "sdl")]
+                platform: Platform::new(),
+            },
+        }
+    }
+
+    pub fn running(&self) -> bool {
+        self.host.running()
+    }
+
+    /**
+     * fault stops execution with a message, keeping the first fault if
+     *    there is more than one
+     */
+    pub fn fault(&mut self, message: String) {
+        if self.running() {
+            self.host.status = Status::Fault(message);
+        }
+    }
+
+    /**
+     * is_patched returns true if the bytes of a recompiled block no longer
+     *    match what was lifted
+     */
+    pub fn is_patched(&self, start: u16, end: u16) -> bool {
+        let range = start as usize..end as usize;
+        self.memory[range.clone()] != self.host.original[range]
+    }
+
+    /**
+     * resumable returns true if recompiled code can carry on from pc: a
+     *    block starts there and its code has not been patched
+     */
+    pub fn resumable(&self, pc: u16) -> bool {
+        match self.host.blocks.binary_search_by_key(&pc, |&(start, _)| start) {
+            Ok(index) => {
+                let (start, end) = self.host.blocks[index];
+                !self.is_patched(start, end)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /**
+     * instruction_done hands the machine to the observer, if there is one
+     */
+    pub fn instruction_done(&mut self) {
+        if let Some(mut observer) = self.host.observer.take() {
+            observer(self);
+            self.host.observer = Some(observer);
+        }
+    }
+
+    /**
+     * update_timers is called once after every instruction is counted in
+     *    Host::instructions, and counts DT and ST down on the same
+     *    instructions as CPU::update_timers
+     */
+    pub fn update_timers(&mut self) {
+        if !timers_due(self.host.instructions) {
+            return;
+        }
+        if self.dt > 0 {
+            self.dt -= 1;
+        }
+        if self.st > 0 {
+            println!("Beep");
+            self.st -= 1;
+        }
+    }
+
+    /**
+     * to_cpu_state copies the machine out in the interpreter's layout
+     */
+    pub fn to_cpu_state(&self) -> CpuState {
+        CpuState {
+            gp_registers: self.v,
+            i: self.i,
+            dt: self.dt,
+            st: self.st,
+            pc: self.pc,
+            sp: self.sp as usize,
+            stack: self.stack,
+            memory: self.memory,
+        }
+    }
+
+    /**
+     * load_cpu_state copies the machine back in from the interpreter
+     */
+    pub fn load_cpu_state(&mut self, cpu_state: &CpuState) {
+        self.v = cpu_state.gp_registers;
+        self.i = cpu_state.i;
+        self.dt = cpu_state.dt;
+        self.st = cpu_state.st;
+        self.pc = cpu_state.pc;
+        self.sp = cpu_state.sp as u16;
+        self.stack = cpu_state.stack;
+        self.memory = cpu_state.memory;
+    }
+}
+
+impl Host {
+    pub fn running(&self) -> bool {
+        self.status == Status::Running
+    }
+
+    /**
+     * poll_input reads the keypad into MMIO. Closing the window stops execution.
+     */
+    pub fn poll_input(&mut self) {
+        #[cfg(feature = "sdl")]
+        match self.platform.input.get_input() {
+            Some(keys) => self.cpu.mmio.input_memory = keys,
+            None => self.status = Status::Quit,
+        }
+    }
+
+    /**
+     * present shows the screen if anything was drawn since it was last shown
+     */
+    pub fn present(&mut self) {
+        if self.draw_flag {
+            #[cfg(feature = "sdl")]
+            self.platform.video.draw(&self.cpu.mmio);
+            self.draw_flag = false;
+        }
+    }
+
+    /**
+     * pace sleeps until one instruction's worth of time has passed since
+     *    it last returned
+     */
+    pub fn pace(&mut self) {
+        if !self.paced {
+            return;
+        }
+        let elapsed = self.last_tick.elapsed();
+        if elapsed < EXECUTION_RATE {
+            sleep(EXECUTION_RATE - elapsed);
+        }
+        self.last_tick = Instant::now();
+    }
+}
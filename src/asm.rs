@@ -0,0 +1,543 @@
+/**
+ * asm.rs
+ *  this file assembles chip8 source into a .ch8 program. It reads the
+ *  same mnemonics the disassembler writes, so a listing assembles back
+ *  into the bytes it came from. On top of the instructions it accepts:
+ *
+ *  ```text
+ *      name:                   a label for the next address
+ *      :const NAME value       a named constant
+ *      :include "file.asm"     the source of another file, in place
+ *      db 0x12, 34, 0b1010     data bytes
+ *      dw 0x1234, label        data words, big endian
+ *      ; comment               to the end of the line
+ *  ```
+ *
+ *  Values are decimal, 0x hex or 0b binary numbers, labels or constants,
+ *  and may be added together or subtracted with + and -.
+ */
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::instruction::Instruction;
+use crate::rom::{LOAD_ADDRESS, MEMORY_SIZE};
+
+// Deepest chain of includes before assuming one includes itself
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/**
+ * An error and where in the source it was found. Line and column count
+ * from 1.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}: {}", self.file, self.line, self.column, self.message)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Location {
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+impl Location {
+    fn error(&self, message: String) -> AsmError {
+        AsmError { file: self.file.clone(), line: self.line, column: self.column, message }
+    }
+}
+
+/**
+ * A word of source text and where it starts
+ */
+#[derive(Debug, Clone)]
+struct Token {
+    text: String,
+    location: Location,
+}
+
+/**
+ * An instruction or data directive waiting for every label to be known
+ */
+struct Statement {
+    mnemonic: Token,
+    operands: Vec<Token>,
+}
+
+struct Assembler {
+    statements: Vec<Statement>,
+    // Labels and constants
+    symbols: HashMap<String, i32>,
+    // Bytes the statements so far will take up
+    size: usize,
+    depth: usize,
+    // Name of the outermost source, for errors about the whole program
+    name: String,
+}
+
+/**
+ * assemble turns source text into program bytes. name is used in error
+ *    messages, and includes are found relative to the current directory.
+ */
+pub fn assemble(source: &str, name: &str) -> Result<Vec<u8>, AsmError> {
+    let mut assembler = Assembler::new(name);
+    assembler.read_source(source, name, Path::new("."))?;
+    assembler.finish()
+}
+
+/**
+ * assemble_file reads and assembles a source file. Includes are found
+ *    relative to the file that includes them.
+ */
+pub fn assemble_file(path: &str) -> Result<Vec<u8>, AsmError> {
+    let mut assembler = Assembler::new(path);
+    let start = Location { file: path.to_string(), line: 1, column: 1 };
+    assembler.read_file(Path::new(path), &start)?;
+    assembler.finish()
+}
+
+/**
+ * split_operands splits the text after a mnemonic on commas, keeping the
+ *    column each operand starts at
+ */
+fn split_operands(text: &str, location: &Location) -> Vec<Token> {
+    let mut operands = Vec::new();
+    let mut offset = 0;
+    for part in text.split(',') {
+        let trimmed = part.trim_start();
+        let column = location.column + offset + (part.len() - trimmed.len());
+        operands.push(Token {
+            text: trimmed.trim_end().to_string(),
+            location: Location { column, ..location.clone() },
+        });
+        offset += part.len() + 1;
+    }
+    operands
+}
+
+/**
+ * is_name returns true for text that can name a label or constant
+ */
+fn is_name(text: &str) -> bool {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/**
+ * register parses V0 through VF
+ */
+fn register(token: &Token) -> Option<u8> {
+    let text = token.text.as_bytes();
+    if text.len() == 2 && (text[0] == b'V' || text[0] == b'v') {
+        return (text[1] as char).to_digit(16).map(|x| x as u8);
+    }
+    None
+}
+
+/**
+ * parse_number reads a decimal, 0x hex or 0b binary literal
+ */
+fn parse_number(text: &str) -> Option<i32> {
+    let lower = text.to_ascii_lowercase();
+    if let Some(hex) = lower.strip_prefix("0x") {
+        i32::from_str_radix(hex, 16).ok()
+    } else if let Some(binary) = lower.strip_prefix("0b") {
+        i32::from_str_radix(binary, 2).ok()
+    } else if lower.chars().all(|c| c.is_ascii_digit()) {
+        lower.parse().ok()
+    } else {
+        None
+    }
+}
+
+impl Assembler {
+    fn new(name: &str) -> Assembler {
+        Assembler { statements: Vec::new(), symbols: HashMap::new(), size: 0, depth: 0, name: name.to_string() }
+    }
+
+    /**
+     * read_file reads the source in a file. from is where it was asked
+     *    for, for errors opening it.
+     */
+    fn read_file(&mut self, path: &Path, from: &Location) -> Result<(), AsmError> {
+        let source = fs::read_to_string(path)
+            .map_err(|e| from.error(format!("Cannot open {}: {}", path.display(), e)))?;
+        let directory = path.parent().map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+        self.read_source(&source, &path.display().to_string(), &directory)
+    }
+
+    /**
+     * read_source runs the first pass over some source: it gives every
+     *    label an address and every constant a value, and keeps the
+     *    instructions and data for finish to encode
+     */
+    fn read_source(&mut self, source: &str, name: &str, directory: &Path) -> Result<(), AsmError> {
+        for (index, full_line) in source.lines().enumerate() {
+            let code = match full_line.find(';') {
+                Some(comment) => &full_line[..comment],
+                None => full_line,
+            };
+            let mut rest = code.trim_start();
+            let mut location =
+                Location { file: name.to_string(), line: index + 1, column: code.len() - rest.len() + 1 };
+
+            // Labels may share a line with what they label
+            while let Some(colon) = rest.find(':') {
+                let label = rest[..colon].trim_end();
+                if label.is_empty() || !is_name(label) {
+                    break;
+                }
+                self.define(label, (LOAD_ADDRESS as usize + self.size) as i32, &location)?;
+                let after = &rest[colon + 1..];
+                let trimmed = after.trim_start();
+                location.column += colon + 1 + after.len() - trimmed.len();
+                rest = trimmed;
+            }
+            if rest.trim_end().is_empty() {
+                continue;
+            }
+
+            let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let mnemonic = Token { text: rest[..word_end].to_string(), location: location.clone() };
+            let after = &rest[word_end..];
+            let trimmed = after.trim_start();
+            let operand_location = Location { column: location.column + word_end + after.len() - trimmed.len(), ..location };
+            let operands = if trimmed.trim_end().is_empty() {
+                Vec::new()
+            } else {
+                split_operands(trimmed, &operand_location)
+            };
+
+            match mnemonic.text.as_str() {
+                ":const" => self.read_const(&mnemonic, trimmed.trim_end(), &operand_location)?,
+                ":include" => self.read_include(&mnemonic, trimmed.trim_end(), &operand_location, directory)?,
+                _ => {
+                    self.size += match mnemonic.text.to_ascii_lowercase().as_str() {
+                        "db" => operands.len(),
+                        "dw" => operands.len() * 2,
+                        _ => 2,
+                    };
+                    if LOAD_ADDRESS as usize + self.size > MEMORY_SIZE {
+                        return Err(mnemonic.location.error("Program does not fit in memory".to_string()));
+                    }
+                    self.statements.push(Statement { mnemonic, operands });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * read_const handles ":const NAME value". The value can only use labels
+     *    and constants defined above it.
+     */
+    fn read_const(&mut self, directive: &Token, text: &str, location: &Location) -> Result<(), AsmError> {
+        let name_end = text.find(char::is_whitespace).unwrap_or(text.len());
+        let name = &text[..name_end];
+        if !is_name(name) {
+            return Err(location.error(format!("{} needs a name and a value", directive.text)));
+        }
+        let after = &text[name_end..];
+        let trimmed = after.trim_start();
+        let value = Token {
+            text: trimmed.to_string(),
+            location: Location { column: location.column + name_end + after.len() - trimmed.len(), ..location.clone() },
+        };
+        if value.text.is_empty() {
+            return Err(location.error(format!("{} needs a name and a value", directive.text)));
+        }
+        let value = self.value(&value)?;
+        self.define(name, value, location)
+    }
+
+    /**
+     * read_include handles ':include "file"', reading the file in place
+     */
+    fn read_include(&mut self, directive: &Token, text: &str, location: &Location, directory: &Path) -> Result<(), AsmError> {
+        let path = match text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+            Some(path) if !path.is_empty() => path,
+            _ => return Err(location.error(format!("{} needs a quoted file name", directive.text))),
+        };
+        if self.depth == MAX_INCLUDE_DEPTH {
+            return Err(location.error(format!("Includes nested more than {} deep", MAX_INCLUDE_DEPTH)));
+        }
+        self.depth += 1;
+        self.read_file(&directory.join(path), location)?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /**
+     * define gives a label or constant its value
+     */
+    fn define(&mut self, name: &str, value: i32, location: &Location) -> Result<(), AsmError> {
+        if register(&Token { text: name.to_string(), location: location.clone() }).is_some() {
+            return Err(location.error(format!("{} is a register and cannot be a name", name)));
+        }
+        if self.symbols.insert(name.to_string(), value).is_some() {
+            return Err(location.error(format!("{} is already defined", name)));
+        }
+        Ok(())
+    }
+
+    /**
+     * value works out a sum of numbers, labels and constants
+     */
+    fn value(&self, token: &Token) -> Result<i32, AsmError> {
+        let mut total: i32 = 0;
+        let mut sign = 1;
+        let mut rest = token.text.as_str();
+        let mut offset = 0;
+        if let Some(negated) = rest.strip_prefix('-') {
+            sign = -1;
+            rest = negated;
+            offset = 1;
+        }
+        loop {
+            let end = rest.find(['+', '-']).unwrap_or(rest.len());
+            let term = rest[..end].trim();
+            let column = token.location.column + offset + rest[..end].len() - rest[..end].trim_start().len();
+            let location = Location { column, ..token.location.clone() };
+            let value = match parse_number(term) {
+                Some(value) => value,
+                None if is_name(term) => match self.symbols.get(term) {
+                    Some(&value) => value,
+                    None => return Err(location.error(format!("Unknown name {}", term))),
+                },
+                None if term.is_empty() => return Err(location.error(format!("Expected a value in '{}'", token.text))),
+                None => return Err(location.error(format!("Invalid value {}", term))),
+            };
+            total = total.saturating_add(sign * value);
+            if end == rest.len() {
+                return Ok(total);
+            }
+            sign = if rest[end..].starts_with('-') { -1 } else { 1 };
+            offset += end + 1;
+            rest = &rest[end + 1..];
+        }
+    }
+
+    /**
+     * ranged works out a value and checks it lies between min and max
+     */
+    fn ranged(&self, token: &Token, min: i32, max: i32, what: &str) -> Result<i32, AsmError> {
+        let value = self.value(token)?;
+        if value < min || value > max {
+            return Err(token.location.error(format!("{} is out of range for {}", token.text, what)));
+        }
+        Ok(value)
+    }
+
+    fn address(&self, token: &Token) -> Result<u16, AsmError> {
+        Ok(self.ranged(token, 0, 0xFFF, "an address")? as u16)
+    }
+
+    // Negative bytes wrap around, so ADD V0, -1 subtracts one
+    fn byte(&self, token: &Token) -> Result<u8, AsmError> {
+        Ok(self.ranged(token, -128, 0xFF, "a byte")? as u8)
+    }
+
+    fn reg(&self, token: &Token) -> Result<u8, AsmError> {
+        register(token).ok_or_else(|| token.location.error(format!("Expected a register, found '{}'", token.text)))
+    }
+
+    // The RPL user flags only hold V0 to V7
+    fn rpl_reg(&self, token: &Token) -> Result<u8, AsmError> {
+        match self.reg(token)? {
+            x if x < 8 => Ok(x),
+            _ => Err(token.location.error(format!("{} has no RPL user flag", token.text))),
+        }
+    }
+
+    /**
+     * finish runs the second pass, encoding every statement now that all
+     *    labels are known
+     */
+    fn finish(self) -> Result<Vec<u8>, AsmError> {
+        let mut program = Vec::with_capacity(self.size);
+        for statement in &self.statements {
+            match statement.mnemonic.text.to_ascii_lowercase().as_str() {
+                "db" => {
+                    for operand in &statement.operands {
+                        program.push(self.byte(operand)?);
+                    }
+                }
+                "dw" => {
+                    for operand in &statement.operands {
+                        let word = self.ranged(operand, -0x8000, 0xFFFF, "a word")? as u16;
+                        program.extend_from_slice(&word.to_be_bytes());
+                    }
+                }
+                _ => {
+                    let opcode = self.instruction(statement)?.encode();
+                    program.extend_from_slice(&opcode.to_be_bytes());
+                }
+            }
+        }
+        if program.is_empty() {
+            let location = Location { file: self.name.clone(), line: 1, column: 1 };
+            return Err(location.error("Program is empty".to_string()));
+        }
+        Ok(program)
+    }
+
+    /**
+     * instruction encodes one mnemonic and its operands
+     */
+    fn instruction(&self, statement: &Statement) -> Result<Instruction, AsmError> {
+        let mnemonic = &statement.mnemonic;
+        let ops = &statement.operands;
+        let upper: Vec<String> = ops.iter().map(|op| op.text.to_ascii_uppercase()).collect();
+        let upper: Vec<&str> = upper.iter().map(|op| op.as_str()).collect();
+        let is_reg = |index: usize| register(&ops[index]).is_some();
+        let wrong = || {
+            mnemonic.location.error(format!("Invalid operands for {}: '{}'", mnemonic.text, upper.join(", ")))
+        };
+
+        let instruction = match (mnemonic.text.to_ascii_uppercase().as_str(), upper.as_slice()) {
+            ("CLS", []) => Instruction::Cls,
+            ("RET", []) => Instruction::Ret,
+            ("SYS", [_]) => Instruction::Sys(self.address(&ops[0])?),
+            ("JP", ["V0", _]) => Instruction::JpV0(self.address(&ops[1])?),
+            ("JP", [_]) => Instruction::Jp(self.address(&ops[0])?),
+            ("CALL", [_]) => Instruction::Call(self.address(&ops[0])?),
+            ("SE", [_, _]) if is_reg(1) => Instruction::SeReg(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("SE", [_, _]) => Instruction::SeByte(self.reg(&ops[0])?, self.byte(&ops[1])?),
+            ("SNE", [_, _]) if is_reg(1) => Instruction::SneReg(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("SNE", [_, _]) => Instruction::SneByte(self.reg(&ops[0])?, self.byte(&ops[1])?),
+            ("LD", ["I", _]) => Instruction::LdI(self.address(&ops[1])?),
+            ("LD", ["DT", _]) => Instruction::LdDtVx(self.reg(&ops[1])?),
+            ("LD", ["ST", _]) => Instruction::LdStVx(self.reg(&ops[1])?),
+            ("LD", ["F", _]) => Instruction::LdF(self.reg(&ops[1])?),
+            ("LD", ["B", _]) => Instruction::LdB(self.reg(&ops[1])?),
+            ("LD", ["[I]", _]) => Instruction::Store(self.reg(&ops[1])?),
+            ("LD", ["HF", _]) => Instruction::LdHf(self.reg(&ops[1])?),
+            ("LD", ["R", _]) => Instruction::StoreRpl(self.rpl_reg(&ops[1])?),
+            ("LD", [_, "R"]) => Instruction::LoadRpl(self.rpl_reg(&ops[0])?),
+            ("LD", [_, "DT"]) => Instruction::LdVxDt(self.reg(&ops[0])?),
+            ("LD", [_, "K"]) => Instruction::LdVxK(self.reg(&ops[0])?),
+            ("LD", [_, "[I]"]) => Instruction::Load(self.reg(&ops[0])?),
+            ("LD", [_, _]) if is_reg(1) => Instruction::LdReg(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("LD", [_, _]) => Instruction::LdByte(self.reg(&ops[0])?, self.byte(&ops[1])?),
+            ("ADD", ["I", _]) => Instruction::AddI(self.reg(&ops[1])?),
+            ("ADD", [_, _]) if is_reg(1) => Instruction::AddReg(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("ADD", [_, _]) => Instruction::AddByte(self.reg(&ops[0])?, self.byte(&ops[1])?),
+            ("OR", [_, _]) => Instruction::Or(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("AND", [_, _]) => Instruction::And(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("XOR", [_, _]) => Instruction::Xor(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("SUB", [_, _]) => Instruction::Sub(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("SUBN", [_, _]) => Instruction::Subn(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("SHR", [_]) => Instruction::Shr(self.reg(&ops[0])?, 0),
+            ("SHR", [_, _]) => Instruction::Shr(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("SHL", [_]) => Instruction::Shl(self.reg(&ops[0])?, 0),
+            ("SHL", [_, _]) => Instruction::Shl(self.reg(&ops[0])?, self.reg(&ops[1])?),
+            ("RND", [_, _]) => Instruction::Rnd(self.reg(&ops[0])?, self.byte(&ops[1])?),
+            ("DRW", [_, _, _]) => {
+                let n = self.ranged(&ops[2], 0, 0xF, "a sprite height")? as u8;
+                Instruction::Drw(self.reg(&ops[0])?, self.reg(&ops[1])?, n)
+            }
+            ("SKP", [_]) => Instruction::Skp(self.reg(&ops[0])?),
+            ("SKNP", [_]) => Instruction::Sknp(self.reg(&ops[0])?),
+            ("SCD", [_]) => Instruction::ScrollDown(self.ranged(&ops[0], 0, 0xF, "a scroll distance")? as u8),
+            ("SCR", []) => Instruction::ScrollRight,
+            ("SCL", []) => Instruction::ScrollLeft,
+            ("EXIT", []) => Instruction::Exit,
+            ("LOW", []) => Instruction::Low,
+            ("HIGH", []) => Instruction::High,
+            (
+                "CLS" | "RET" | "SYS" | "JP" | "CALL" | "SE" | "SNE" | "LD" | "ADD" | "OR" | "AND" | "XOR" | "SUB"
+                | "SUBN" | "SHR" | "SHL" | "RND" | "DRW" | "SKP" | "SKNP" | "SCD" | "SCR" | "SCL" | "EXIT" | "LOW"
+                | "HIGH",
+                _,
+            ) => return Err(wrong()),
+            _ => return Err(mnemonic.location.error(format!("Unknown instruction {}", mnemonic.text))),
+        };
+        Ok(instruction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schip_instructions_assemble() {
+        let source = "HIGH\nSCD 4\nSCR\nSCL\nLD HF, V3\nLD R, V7\nLD V7, R\nDRW V0, V1, 0\nLOW\nEXIT\n";
+        let program = assemble(source, "schip.asm").unwrap();
+        let expected = [
+            0x00, 0xFF, 0x00, 0xC4, 0x00, 0xFB, 0x00, 0xFC, 0xF3, 0x30, 0xF7, 0x75, 0xF7, 0x85, 0xD0, 0x10, 0x00, 0xFE,
+            0x00, 0xFD,
+        ];
+        assert_eq!(program, expected);
+    }
+
+    use crate::disasm::{disassemble, listing};
+    use crate::rom::Rom;
+    use std::fs;
+
+    #[test]
+    fn bundled_roms_survive_a_round_trip() {
+        for name in ["pong", "maze8", "chp8_IBM_logo", "test_opcode"] {
+            let path = format!("{}/emulator/assets/{}.ch8", env!("CARGO_MANIFEST_DIR"), name);
+            let rom = Rom::from_file(&path).unwrap();
+            let source = listing(&disassemble(&rom));
+            let program = assemble(&source, name).unwrap();
+            assert_eq!(program, rom.bytes, "{} changed after reassembly", name);
+            let again = listing(&disassemble(&Rom::from_bytes(program).unwrap()));
+            assert_eq!(assemble(&again, name).unwrap(), rom.bytes, "{} changed after a second round trip", name);
+        }
+    }
+
+    #[test]
+    fn directives_labels_and_data_assemble() {
+        let dir = std::env::temp_dir().join(format!("chip8_asm_directives_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let main = "; Constants, labels and an included data block\n\
+                    :const SPEED 2\n\
+                    start:\n    LD V0, SPEED + 1\n    LD I, sprite\n    JP start\n\
+                    :include \"sprite.asm\"\n";
+        fs::write(dir.join("main.asm"), main).unwrap();
+        fs::write(dir.join("sprite.asm"), "sprite:\n    db 0xF0, 0b1001, 144\n    dw 0x1234, start\n").unwrap();
+        let program = assemble_file(dir.join("main.asm").to_str().unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+        let expected = [0x60, 0x03, 0xA2, 0x06, 0x12, 0x00, 0xF0, 0x09, 0x90, 0x12, 0x34, 0x02, 0x00];
+        assert_eq!(program.unwrap(), expected);
+    }
+
+    #[test]
+    fn undefined_labels_report_where_they_are_used() {
+        let error = assemble("CLS\n  JP nowhere\n", "bad.asm").unwrap_err();
+        assert_eq!((error.file.as_str(), error.line, error.column), ("bad.asm", 2, 6));
+        assert_eq!(error.to_string(), "bad.asm:2:6: Unknown name nowhere");
+    }
+
+    #[test]
+    fn bad_operands_report_where_they_are() {
+        let error = assemble("CLS\nCLS\n  ADD V0, VG\n", "bad.asm").unwrap_err();
+        assert_eq!((error.file.as_str(), error.line, error.column), ("bad.asm", 3, 11));
+        let error = assemble("CLS\n  DRW V0, V1, 16\n", "bad.asm").unwrap_err();
+        assert_eq!((error.file.as_str(), error.line, error.column), ("bad.asm", 2, 15));
+        assert!(error.message.contains("out of range for a sprite height"), "{}", error);
+    }
+
+    #[test]
+    fn rpl_flags_stop_at_v7() {
+        let error = assemble("LD R, V8\n", "schip.asm").unwrap_err();
+        assert!(error.to_string().contains("V8 has no RPL user flag"), "{}", error);
+    }
+}
@@ -0,0 +1,385 @@
+/**
+ * c.rs
+ *  this file translates a chip8 program into a single self-contained C
+ *  file, for platforms LLVM does not target and for reading what the
+ *  lifter makes of a program. Every block the cfg module recovers becomes
+ *  a label inside chip8_run, and a switch on pc picks the block for
+ *  indirect jumps and returns, with the same semantics as
+ *  CPU::execute_next_opcode.
+ *
+ *  The file calls into the runtime through the same chip8_rt_* functions
+ *  as lifted LLVM IR, and defines the same chip8_run and main, so it links
+ *  against libchip8_rt.a the same way. Registers live in the runtime's
+ *  state throughout; a block only stops at its end and on the instructions
+ *  the runtime can stop in the middle of, like lifted IR does.
+ */
+use crate::cfg::{BasicBlock as Block, Cfg};
+use crate::classify::code_cfg;
+use crate::instruction::Instruction;
+use crate::rom::{Rom, LOAD_ADDRESS};
+use crate::smc::SelfModification;
+
+// Bytes in each line of the ROM table
+const BYTES_PER_LINE: usize = 12;
+
+const PRELUDE: &str = "#include <stdbool.h>
+#include <stdint.h>
+#include <string.h>
+
+/* The start of the runtime's Chip8State, the rest is private to the runtime */
+struct chip8_state {
+    uint8_t v[16];
+    uint16_t i;
+    uint16_t pc;
+    uint16_t sp;
+    uint8_t dt;
+    uint8_t st;
+    uint16_t stack[16];
+    uint8_t memory[4096];
+};
+
+struct chip8_state *chip8_rt_init(const uint8_t *rom, uint32_t length);
+void chip8_rt_run(struct chip8_state *state, void (*run)(struct chip8_state *), const uint16_t *blocks,
+                  uint32_t count);
+int32_t chip8_rt_exit(struct chip8_state *state);
+bool chip8_rt_tick(struct chip8_state *state, bool synced);
+void chip8_rt_cls(struct chip8_state *state);
+uint8_t chip8_rt_draw(struct chip8_state *state, uint8_t x, uint8_t y, uint8_t n);
+bool chip8_rt_key_pressed(struct chip8_state *state, uint8_t key);
+uint8_t chip8_rt_wait_key(struct chip8_state *state);
+uint8_t chip8_rt_random(struct chip8_state *state);
+void chip8_rt_bad_address(struct chip8_state *state, uint16_t start);
+void chip8_rt_bad_stack(struct chip8_state *state);
+void chip8_rt_unresolved(struct chip8_state *state);
+bool chip8_rt_code_written(struct chip8_state *state, uint16_t addr, uint16_t len);
+
+/* Ends an instruction at a safepoint, leaving chip8_run if the runtime says to stop */
+#define SAFEPOINT(next)                         \\
+    do {                                        \\
+        state->pc = (next);                     \\
+        if (!chip8_rt_tick(state, true))        \\
+            return;                             \\
+    } while (0)
+
+/* Leaves chip8_run with pc on a faulting instruction, after telling the runtime */
+#define FAULT(at, report)                       \\
+    do {                                        \\
+        state->pc = (at);                       \\
+        report;                                 \\
+        return;                                 \\
+    } while (0)
+";
+
+/**
+ * translate writes a whole program out as C. name goes in the comment at
+ *    the top of the file.
+ */
+pub fn translate(rom: &Rom, name: &str) -> String {
+    let cfg = code_cfg(rom);
+    let smc = SelfModification::analyze(&cfg);
+    let mut translator = Translator { cfg: &cfg, smc: &smc, out: String::new(), indent: 1, uses_dispatch: false };
+    for block in cfg.blocks() {
+        translator.translate_block(block);
+    }
+
+    let mut out = String::new();
+    out.push_str(&format!("/*\n * {}, recompiled by chip8_retargeter_llvm.\n *\n", name));
+    out.push_str(" * Build it against the runtime library, leaving out -lSDL2 for a headless runtime:\n");
+    out.push_str(" *     cc -O2 <this file> libchip8_rt.a -lSDL2 -lpthread -ldl -lm -lrt -lutil -o <program>\n */\n");
+    out.push_str(PRELUDE);
+
+    out.push_str(&format!("\nstatic const uint8_t chip8_rom[{}] = {{\n", rom.bytes.len()));
+    for line in rom.bytes.chunks(BYTES_PER_LINE) {
+        let bytes: Vec<String> = line.iter().map(|byte| format!("0x{:02X},", byte)).collect();
+        out.push_str(&format!("    {}\n", bytes.join(" ")));
+    }
+    out.push_str("};\n");
+
+    // Start and end address of every block, for the runtime
+    let blocks: Vec<String> =
+        cfg.blocks().map(|block| format!("    0x{:03X}, 0x{:03X},\n", block.start, block.end())).collect();
+    out.push_str(&format!("\nstatic const uint16_t chip8_blocks[{}] = {{\n", blocks.len() * 2));
+    out.push_str(&blocks.concat());
+    out.push_str("};\n");
+
+    out.push_str("\nvoid chip8_run(struct chip8_state *state)\n{\n");
+    if translator.uses_dispatch {
+        out.push_str("dispatch:\n");
+    }
+    out.push_str("    switch (state->pc) {\n");
+    for block in cfg.blocks() {
+        out.push_str(&format!("    case 0x{:03X}: goto block_{:03X};\n", block.start, block.start));
+    }
+    out.push_str("    default: goto unresolved;\n    }\n");
+    out.push_str(&translator.out);
+    out.push_str("\nunresolved:\n    /* Nothing was recompiled at pc, let the runtime decide what to do */\n");
+    out.push_str("    chip8_rt_unresolved(state);\n}\n");
+
+    out.push_str("\nint main(void)\n{\n");
+    out.push_str("    struct chip8_state *state = chip8_rt_init(chip8_rom, sizeof chip8_rom);\n");
+    out.push_str("    uint32_t count = sizeof chip8_blocks / sizeof chip8_blocks[0] / 2;\n");
+    out.push_str("    chip8_rt_run(state, chip8_run, chip8_blocks, count);\n");
+    out.push_str("    return chip8_rt_exit(state);\n}\n");
+    out
+}
+
+struct Translator<'a> {
+    cfg: &'a Cfg,
+    smc: &'a SelfModification,
+    // Body of chip8_run after the dispatch switch
+    out: String,
+    // Depth of the scope being written, chip8_run's body is 1
+    indent: usize,
+    // Set once anything jumps back to the dispatch switch
+    uses_dispatch: bool,
+}
+
+impl<'a> Translator<'a> {
+    fn line(&mut self, text: &str) {
+        self.out.push_str(&"    ".repeat(self.indent));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    /**
+     * target returns the label for a known address, or the dispatch
+     *    switch which hands unknown addresses to the runtime
+     */
+    fn target(&mut self, addr: u16) -> String {
+        if self.cfg.block(addr).is_some() {
+            return format!("block_{:03X}", addr);
+        }
+        self.uses_dispatch = true;
+        "dispatch".to_string()
+    }
+
+    /**
+     * goto ends the block at a safepoint and carries on at addr
+     */
+    fn goto(&mut self, addr: u16) {
+        self.line(&format!("SAFEPOINT(0x{:03X});", addr));
+        let target = self.target(addr);
+        self.line(&format!("goto {};", target));
+    }
+
+    /**
+     * goto_dispatch ends the block at a safepoint with pc set to next, a C
+     *    expression, and finds the block to run with the switch
+     */
+    fn goto_dispatch(&mut self, next: &str) {
+        self.uses_dispatch = true;
+        self.line(&format!("SAFEPOINT({});", next));
+        self.line("goto dispatch;");
+    }
+
+    fn translate_block(&mut self, block: &Block) {
+        self.out.push_str(&format!("\nblock_{:03X}:\n", block.start));
+        if self.smc.may_be_written(block.start) {
+            // Leave the block to the interpreter if it no longer holds what was recompiled
+            let length = block.end() - block.start;
+            self.line(&format!(
+                "if (memcmp(&state->memory[0x{:03X}], &chip8_rom[0x{:03X}], {}) != 0) {{",
+                block.start,
+                block.start - LOAD_ADDRESS,
+                length
+            ));
+            self.line(&format!("    state->pc = 0x{:03X};", block.start));
+            self.line("    goto unresolved;");
+            self.line("}");
+        }
+        let last = block.instructions.len() - 1;
+        for (index, &(pc, instruction)) in block.instructions.iter().enumerate() {
+            self.line(&format!("/* 0x{:03X}  {} */", pc, instruction));
+            self.translate_instruction(pc, instruction, index == last);
+        }
+        if block.falls_through() {
+            let target = self.target(block.end());
+            self.line(&format!("goto {};", target));
+        }
+    }
+
+    /**
+     * translate_instruction writes one instruction. last is set for the
+     *    final instruction in its block, which always ends on a safepoint.
+     */
+    fn translate_instruction(&mut self, pc: u16, instruction: Instruction, last: bool) {
+        // Set for stores that may have patched recompiled code
+        let mut patched = false;
+        match instruction {
+            Instruction::Cls => self.line("chip8_rt_cls(state);"),
+            Instruction::Ret => {
+                // RET with an empty stack is a fault rather than an underflow
+                self.line("if (state->sp == 0)");
+                self.line(&format!("    FAULT(0x{:03X}, chip8_rt_bad_stack(state));", pc));
+                self.line("state->sp--;");
+                return self.goto_dispatch("state->stack[state->sp + 1] + 2");
+            }
+            Instruction::Sys(_)
+            | Instruction::ScrollDown(_)
+            | Instruction::ScrollRight
+            | Instruction::ScrollLeft
+            | Instruction::Exit
+            | Instruction::Low
+            | Instruction::High => {
+                // Ignored by the interpreter, it just moves on. Outside
+                // SUPER-CHIP mode that includes SUPER-CHIP's 00CN to 00FF.
+            }
+            Instruction::Unknown(_) | Instruction::LdHf(_) | Instruction::StoreRpl(_) | Instruction::LoadRpl(_) => {
+                // A fault, which the interpreter is left to report
+                self.line(&format!("state->pc = 0x{:03X};", pc));
+                self.line("goto unresolved;");
+            }
+            Instruction::Jp(addr) => return self.goto(addr),
+            Instruction::Call(addr) => {
+                // Stack slot 0 is never used, the interpreter increments sp first
+                self.line("if (state->sp + 1 >= 16)");
+                self.line(&format!("    FAULT(0x{:03X}, chip8_rt_bad_stack(state));", pc));
+                self.line("state->sp++;");
+                self.line(&format!("state->stack[state->sp] = 0x{:03X};", pc));
+                return self.goto(addr);
+            }
+            Instruction::SeByte(x, byte) => return self.skip_if(pc, &format!("state->v[0x{:X}] == 0x{:02X}", x, byte)),
+            Instruction::SneByte(x, byte) => {
+                return self.skip_if(pc, &format!("state->v[0x{:X}] != 0x{:02X}", x, byte));
+            }
+            Instruction::SeReg(x, y) => return self.skip_if(pc, &format!("state->v[0x{:X}] == state->v[0x{:X}]", x, y)),
+            Instruction::SneReg(x, y) => {
+                return self.skip_if(pc, &format!("state->v[0x{:X}] != state->v[0x{:X}]", x, y));
+            }
+            Instruction::LdByte(x, byte) => self.line(&format!("state->v[0x{:X}] = 0x{:02X};", x, byte)),
+            Instruction::AddByte(x, byte) => self.line(&format!("state->v[0x{:X}] += 0x{:02X};", x, byte)),
+            Instruction::LdReg(x, y) => self.line(&format!("state->v[0x{:X}] = state->v[0x{:X}];", x, y)),
+            Instruction::Or(x, y) => self.line(&format!("state->v[0x{:X}] |= state->v[0x{:X}];", x, y)),
+            Instruction::And(x, y) => self.line(&format!("state->v[0x{:X}] &= state->v[0x{:X}];", x, y)),
+            Instruction::Xor(x, y) => self.line(&format!("state->v[0x{:X}] ^= state->v[0x{:X}];", x, y)),
+            Instruction::AddReg(x, y) => {
+                self.line("{");
+                self.line(&format!("    unsigned sum = state->v[0x{:X}] + state->v[0x{:X}];", x, y));
+                self.line("    state->v[0xF] = sum > 0xFF;");
+                self.line(&format!("    state->v[0x{:X}] = (uint8_t)sum;", x));
+                self.line("}");
+            }
+            // The interpreter writes VF before computing Vx, which matters
+            // when either of them is VF
+            Instruction::Sub(x, y) => {
+                self.line(&format!("state->v[0xF] = state->v[0x{:X}] > state->v[0x{:X}];", x, y));
+                self.line(&format!("state->v[0x{:X}] = state->v[0x{:X}] - state->v[0x{:X}];", x, x, y));
+            }
+            Instruction::Shr(x, _) => {
+                self.line(&format!("state->v[0xF] = state->v[0x{:X}] & 0x01;", x));
+                self.line(&format!("state->v[0x{:X}] >>= 1;", x));
+            }
+            Instruction::Subn(x, y) => {
+                self.line(&format!("state->v[0xF] = state->v[0x{:X}] > state->v[0x{:X}];", y, x));
+                self.line(&format!("state->v[0x{:X}] = state->v[0x{:X}] - state->v[0x{:X}];", x, y, x));
+            }
+            Instruction::Shl(x, _) => {
+                self.line(&format!("state->v[0xF] = state->v[0x{:X}] >> 7;", x));
+                self.line(&format!("state->v[0x{:X}] <<= 1;", x));
+            }
+            Instruction::LdI(addr) => self.line(&format!("state->i = 0x{:03X};", addr)),
+            Instruction::JpV0(addr) => return self.goto_dispatch(&format!("state->v[0x0] + 0x{:03X}", addr)),
+            Instruction::Rnd(x, byte) => {
+                self.line(&format!("state->v[0x{:X}] = chip8_rt_random(state) & 0x{:02X};", x, byte));
+            }
+            Instruction::Drw(x, y, n) => {
+                self.check_i(pc, n);
+                // The runtime reports faults at pc
+                self.line(&format!("state->pc = 0x{:03X};", pc));
+                self.line(&format!(
+                    "state->v[0xF] = chip8_rt_draw(state, state->v[0x{:X}], state->v[0x{:X}], {});",
+                    x, y, n
+                ));
+            }
+            Instruction::Skp(x) => {
+                self.line(&format!("state->pc = 0x{:03X};", pc));
+                return self.skip_if(pc, &format!("chip8_rt_key_pressed(state, state->v[0x{:X}])", x));
+            }
+            Instruction::Sknp(x) => {
+                self.line(&format!("state->pc = 0x{:03X};", pc));
+                return self.skip_if(pc, &format!("!chip8_rt_key_pressed(state, state->v[0x{:X}])", x));
+            }
+            Instruction::LdVxDt(x) => self.line(&format!("state->v[0x{:X}] = state->dt;", x)),
+            Instruction::LdVxK(x) => {
+                // The runtime ticks while it waits
+                self.line(&format!("state->pc = 0x{:03X};", pc));
+                self.line(&format!("state->v[0x{:X}] = chip8_rt_wait_key(state);", x));
+            }
+            Instruction::LdDtVx(x) => self.line(&format!("state->dt = state->v[0x{:X}];", x)),
+            Instruction::LdStVx(x) => self.line(&format!("state->st = state->v[0x{:X}];", x)),
+            Instruction::AddI(x) => self.line(&format!("state->i += state->v[0x{:X}];", x)),
+            // All font sprites start at their digit times 5
+            Instruction::LdF(x) => self.line(&format!("state->i = state->v[0x{:X}] * 5;", x)),
+            Instruction::LdB(x) => {
+                self.check_i(pc, 3);
+                self.line(&format!("state->memory[state->i] = state->v[0x{:X}] / 100;", x));
+                self.line(&format!("state->memory[state->i + 1] = state->v[0x{:X}] / 10 % 10;", x));
+                self.line(&format!("state->memory[state->i + 2] = state->v[0x{:X}] % 10;", x));
+                patched = self.report_write(pc, 3);
+            }
+            Instruction::Store(x) => {
+                self.check_i(pc, x + 1);
+                self.line(&format!("memcpy(&state->memory[state->i], state->v, {});", x as u16 + 1));
+                patched = self.report_write(pc, x as u16 + 1);
+            }
+            Instruction::Load(x) => {
+                self.check_i(pc, x + 1);
+                self.line(&format!("memcpy(state->v, &state->memory[state->i], {});", x as u16 + 1));
+            }
+        }
+
+        // Everything else moves on to the next instruction. Unless the block
+        // can be left here, the tick is not a safepoint and cannot stop it.
+        let leaves = matches!(instruction, Instruction::Drw(..) | Instruction::LdVxK(_));
+        if !(last || leaves || patched) {
+            self.line("chip8_rt_tick(state, false);");
+            return;
+        }
+        self.line(&format!("SAFEPOINT(0x{:03X});", pc + 2));
+        if patched {
+            // Code after this store may have changed, so the interpreter runs it
+            self.line("if (patched)");
+            self.line("    goto unresolved;");
+            self.indent -= 1;
+            self.line("}");
+        }
+    }
+
+    /**
+     * report_write tells the runtime about a store the smc module says may
+     *    hit recompiled code, opening a scope holding whether it was patched
+     */
+    fn report_write(&mut self, pc: u16, length: u16) -> bool {
+        if !self.smc.writes_code(pc) {
+            return false;
+        }
+        self.line("{");
+        self.indent += 1;
+        self.line(&format!("bool patched = chip8_rt_code_written(state, state->i, {});", length));
+        true
+    }
+
+    /**
+     * skip_if ends a block on a skip instruction, jumping over the next
+     *    instruction when cond holds
+     */
+    fn skip_if(&mut self, pc: u16, cond: &str) {
+        self.line(&format!("if ({}) {{", cond));
+        self.indent += 1;
+        self.goto(pc + 4);
+        self.indent -= 1;
+        self.line("}");
+        self.goto(pc + 2);
+    }
+
+    /**
+     * check_i faults unless the length bytes from I are all in memory
+     */
+    fn check_i(&mut self, pc: u16, length: u8) {
+        self.line(&format!("if (state->i + {} > 0x1000)", length));
+        self.line(&format!(
+            "    FAULT(0x{:03X}, chip8_rt_bad_address(state, state->i));",
+            pc
+        ));
+    }
+}
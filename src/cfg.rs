@@ -0,0 +1,473 @@
+/**
+ * cfg.rs
+ *  this file recovers the control flow graph of a chip8 program.
+ *  It walks the program from the load address following jumps, calls,
+ *  returns and skips, splits everything it reaches into basic blocks,
+ *  groups blocks into subroutines and remembers every BNNN it could not
+ *  follow. Given a trace of the program running, it also follows the
+ *  BNNN targets seen and starts from code that ran but was not reached.
+ */
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::instruction::Instruction;
+use crate::rom::{Rom, LOAD_ADDRESS};
+use crate::trace::Trace;
+
+/**
+ * A straight line run of instructions. Only the last one may change pc
+ * in any way other than moving on to the next instruction.
+ */
+#[derive(Debug, Clone)]
+pub struct BasicBlock {
+    pub start: u16,
+    pub instructions: Vec<(u16, Instruction)>,
+}
+
+impl BasicBlock {
+    /**
+     * end returns the address just past the last instruction in the block
+     */
+    pub fn end(&self) -> u16 {
+        self.instructions.last().map_or(self.start, |&(pc, _)| pc + 2)
+    }
+
+    /**
+     * terminator returns the last instruction of the block and its address
+     */
+    pub fn terminator(&self) -> (u16, Instruction) {
+        *self.instructions.last().unwrap()
+    }
+
+    /**
+     * falls_through returns true if the block runs straight into the next
+     *    address instead of ending on a control flow instruction
+     */
+    pub fn falls_through(&self) -> bool {
+        !ends_block(&self.terminator().1)
+    }
+
+    pub fn contains(&self, location: u16) -> bool {
+        location >= self.start && location < self.end()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    // Straight on to the next instruction, including a skip that is not taken
+    FallThrough,
+    // A skip instruction jumping over the next instruction
+    Skip,
+    // JP addr
+    Jump,
+    // CALL addr, from the calling block to the subroutine entry
+    Call,
+    // RET, from the returning block back to the instruction after each CALL
+    Return,
+    // JP V0, addr to a target seen while tracing
+    Indirect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    // Start address of the block the edge leaves
+    pub from: u16,
+    // Start address of the block the edge enters
+    pub to: u16,
+    pub kind: EdgeKind,
+}
+
+/**
+ * A subroutine: every block reachable from its entry without following
+ * calls or returns.
+ */
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub entry: u16,
+    pub blocks: BTreeSet<u16>,
+    // Blocks ending in RET
+    pub returns: BTreeSet<u16>,
+    // Addresses of the CALL instructions that enter this function
+    pub callers: BTreeSet<u16>,
+}
+
+pub struct Cfg {
+    blocks: BTreeMap<u16, BasicBlock>,
+    edges: Vec<Edge>,
+    functions: BTreeMap<u16, Function>,
+    // Addresses of BNNN instructions whose targets are only known at runtime
+    indirect_jumps: BTreeSet<u16>,
+    // Address of each BNNN and a target it was traced jumping to
+    traced_jumps: BTreeSet<(u16, u16)>,
+}
+
+/**
+ * ends_block returns true for instructions that do not simply fall through
+ */
+pub fn ends_block(instruction: &Instruction) -> bool {
+    match instruction {
+        Instruction::Jp(_) | Instruction::Call(_) | Instruction::Ret | Instruction::JpV0(_) => true,
+        _ => instruction.is_skip(),
+    }
+}
+
+impl Cfg {
+    /**
+     * build recovers the control flow graph of a program starting at the
+     *    load address.
+     */
+    pub fn build(rom: &Rom) -> Cfg {
+        Cfg::build_from(rom, &[LOAD_ADDRESS], BTreeSet::new())
+    }
+
+    /**
+     * build_traced recovers the control flow graph of a program with the
+     *    help of a trace of it running. Every BNNN target seen is an extra
+     *    entry point and edge, and so is the first of every run of executed
+     *    instructions that static recovery does not reach.
+     */
+    pub fn build_traced(rom: &Rom, trace: &Trace) -> Cfg {
+        let traced_jumps: BTreeSet<(u16, u16)> = trace.jumps().collect();
+        let mut entries: BTreeSet<u16> = traced_jumps.iter().map(|&(_, target)| target).collect();
+        entries.insert(LOAD_ADDRESS);
+        loop {
+            let entry_list: Vec<u16> = entries.iter().copied().collect();
+            let cfg = Cfg::build_from(rom, &entry_list, traced_jumps.clone());
+            let reached: BTreeSet<u16> =
+                cfg.blocks().flat_map(|block| block.instructions.iter().map(|&(pc, _)| pc)).collect();
+            let missed: Vec<u16> =
+                trace.executed().filter(|pc| !reached.contains(pc) && rom.get_opcode(*pc).is_some()).collect();
+            if missed.is_empty() {
+                return cfg;
+            }
+            // Walking from the start of a run reaches the rest of it, unless
+            // it gets there some other way. The lowest missed address always
+            // starts a run, so every pass adds at least one entry.
+            let starts_run = |pc: &u16| !trace.ran(pc.wrapping_sub(2)) || reached.contains(&pc.wrapping_sub(2));
+            entries.extend(missed.into_iter().filter(starts_run));
+        }
+    }
+
+    /**
+     * build_from recovers the control flow graph reachable from entries,
+     *    with traced_jumps as extra edges
+     */
+    fn build_from(rom: &Rom, entries: &[u16], traced_jumps: BTreeSet<(u16, u16)>) -> Cfg {
+        let leaders = find_leaders(rom, entries);
+        let mut blocks = BTreeMap::new();
+        for &start in leaders.iter().filter(|&&leader| rom.get_opcode(leader).is_some()) {
+            let mut instructions = Vec::new();
+            let mut pc = start;
+            while let Some(opcode) = rom.get_opcode(pc) {
+                if pc != start && leaders.contains(&pc) {
+                    break;
+                }
+                let instruction = Instruction::decode(opcode);
+                instructions.push((pc, instruction));
+                pc += 2;
+                if ends_block(&instruction) {
+                    break;
+                }
+            }
+            blocks.insert(start, BasicBlock { start, instructions });
+        }
+
+        let mut cfg = Cfg {
+            blocks,
+            edges: Vec::new(),
+            functions: BTreeMap::new(),
+            indirect_jumps: BTreeSet::new(),
+            traced_jumps,
+        };
+        cfg.connect();
+        cfg.find_functions();
+        cfg
+    }
+
+    /**
+     * retain_blocks drops every block keep returns false for, along with
+     *    the edges and subroutines that went through it. Jumps to a dropped
+     *    block are left unresolved.
+     */
+    pub fn retain_blocks<F: Fn(&BasicBlock) -> bool>(&mut self, keep: F) {
+        self.blocks.retain(|_, block| keep(block));
+        self.functions.clear();
+        self.connect();
+        self.find_functions();
+    }
+
+    /**
+     * connect adds every edge except returns, which need functions first
+     */
+    fn connect(&mut self) {
+        let mut edges = Vec::new();
+        let mut indirect_jumps = BTreeSet::new();
+        for block in self.blocks.values() {
+            let (pc, instruction) = block.terminator();
+            let mut add = |to: u16, kind: EdgeKind| {
+                if self.blocks.contains_key(&to) {
+                    edges.push(Edge { from: block.start, to, kind });
+                }
+            };
+            match instruction {
+                Instruction::Jp(addr) => add(addr, EdgeKind::Jump),
+                Instruction::Call(addr) => add(addr, EdgeKind::Call),
+                Instruction::Ret => (),
+                Instruction::JpV0(_) => {
+                    indirect_jumps.insert(pc);
+                    for &(_, target) in self.traced_jumps.range((pc, 0)..=(pc, u16::MAX)) {
+                        add(target, EdgeKind::Indirect);
+                    }
+                }
+                _ if instruction.is_skip() => {
+                    add(pc + 2, EdgeKind::FallThrough);
+                    add(pc + 4, EdgeKind::Skip);
+                }
+                _ => add(pc + 2, EdgeKind::FallThrough),
+            }
+        }
+        self.edges = edges;
+        self.indirect_jumps = indirect_jumps;
+    }
+
+    /**
+     * find_functions groups blocks into subroutines, one for the program
+     *    entry and one per CALL target, then adds the return edges.
+     */
+    fn find_functions(&mut self) {
+        let mut callers: BTreeMap<u16, BTreeSet<u16>> = BTreeMap::new();
+        callers.entry(LOAD_ADDRESS).or_default();
+        for block in self.blocks.values() {
+            if let (pc, Instruction::Call(addr)) = block.terminator() {
+                if self.blocks.contains_key(&addr) {
+                    callers.entry(addr).or_default().insert(pc);
+                }
+            }
+        }
+
+        for (entry, callers) in callers {
+            if !self.blocks.contains_key(&entry) {
+                continue;
+            }
+            let mut blocks = BTreeSet::new();
+            let mut returns = BTreeSet::new();
+            let mut worklist = vec![entry];
+            while let Some(start) = worklist.pop() {
+                if !blocks.insert(start) {
+                    continue;
+                }
+                let block = &self.blocks[&start];
+                match block.terminator() {
+                    (_, Instruction::Ret) => {
+                        returns.insert(start);
+                    }
+                    // Execution carries on after the CALL once the subroutine returns
+                    (pc, Instruction::Call(_)) if self.blocks.contains_key(&(pc + 2)) => {
+                        worklist.push(pc + 2);
+                    }
+                    _ => {}
+                }
+                worklist.extend(
+                    self.successors(start)
+                        .filter(|edge| edge.kind != EdgeKind::Call)
+                        .map(|edge| edge.to),
+                );
+            }
+            self.functions.insert(entry, Function { entry, blocks, returns, callers });
+        }
+
+        for function in self.functions.values() {
+            for &from in &function.returns {
+                for &call in &function.callers {
+                    if self.blocks.contains_key(&(call + 2)) {
+                        self.edges.push(Edge { from, to: call + 2, kind: EdgeKind::Return });
+                    }
+                }
+            }
+        }
+    }
+
+    /**
+     * blocks returns every basic block in address order
+     */
+    pub fn blocks(&self) -> impl Iterator<Item = &BasicBlock> {
+        self.blocks.values()
+    }
+
+    /**
+     * block returns the basic block starting at an address
+     */
+    pub fn block(&self, start: u16) -> Option<&BasicBlock> {
+        self.blocks.get(&start)
+    }
+
+    /**
+     * block_containing returns a basic block with an instruction at or
+     *    covering an address
+     */
+    pub fn block_containing(&self, location: u16) -> Option<&BasicBlock> {
+        self.blocks
+            .range(..=location)
+            .rev()
+            .map(|(_, block)| block)
+            .find(|block| block.contains(location))
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /**
+     * successors returns the edges leaving the block starting at an address
+     */
+    pub fn successors(&self, start: u16) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.from == start)
+    }
+
+    /**
+     * predecessors returns the edges entering the block starting at an address
+     */
+    pub fn predecessors(&self, start: u16) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.to == start)
+    }
+
+    /**
+     * functions returns every subroutine, including the program entry
+     */
+    pub fn functions(&self) -> impl Iterator<Item = &Function> {
+        self.functions.values()
+    }
+
+    /**
+     * function returns the subroutine with an entry address
+     */
+    pub fn function(&self, entry: u16) -> Option<&Function> {
+        self.functions.get(&entry)
+    }
+
+    /**
+     * well_formed_functions returns every subroutine that always hands
+     *    control back to its caller the ordinary way: it has a RET, and
+     *    none of its blocks can be reached other than through its entry.
+     */
+    pub fn well_formed_functions(&self) -> impl Iterator<Item = &Function> {
+        self.functions.values().filter(move |function| {
+            function.entry != LOAD_ADDRESS
+                && !function.returns.is_empty()
+                && function.blocks.iter().all(|&start| self.functions_containing(start).count() == 1)
+        })
+    }
+
+    /**
+     * functions_containing returns every subroutine a block belongs to.
+     *    Blocks can be shared when subroutines jump into each other.
+     */
+    pub fn functions_containing(&self, start: u16) -> impl Iterator<Item = &Function> {
+        self.functions
+            .values()
+            .filter(move |function| function.blocks.contains(&start))
+    }
+
+    /**
+     * indirect_jumps returns the address of every BNNN, whose target
+     *    cannot be resolved statically
+     */
+    pub fn indirect_jumps(&self) -> impl Iterator<Item = u16> + '_ {
+        self.indirect_jumps.iter().copied()
+    }
+}
+
+/**
+ * find_leaders walks every instruction reachable from the entry points
+ *    and returns the addresses that start a basic block.
+ */
+fn find_leaders(rom: &Rom, entries: &[u16]) -> BTreeSet<u16> {
+    let mut leaders: BTreeSet<u16> = entries.iter().copied().collect();
+    let mut visited = BTreeSet::new();
+    let mut worklist = entries.to_vec();
+
+    while let Some(mut pc) = worklist.pop() {
+        while let Some(opcode) = rom.get_opcode(pc) {
+            if !visited.insert(pc) {
+                break;
+            }
+            let instruction = Instruction::decode(opcode);
+            let targets = match instruction {
+                Instruction::Jp(addr) => vec![addr],
+                // RET comes back to the instruction after the CALL
+                Instruction::Call(addr) => vec![addr, pc + 2],
+                Instruction::Ret | Instruction::JpV0(_) => vec![],
+                _ if instruction.is_skip() => vec![pc + 2, pc + 4],
+                _ => {
+                    pc += 2;
+                    continue;
+                }
+            };
+            for target in targets {
+                leaders.insert(target);
+                worklist.push(target);
+            }
+            break;
+        }
+    }
+    leaders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(bytes: &[u8]) -> Cfg {
+        Cfg::build(&Rom::from_bytes(bytes.to_vec()).unwrap())
+    }
+
+    /**
+     * edges_from lists the edges leaving a block as (to, kind)
+     */
+    fn edges_from(cfg: &Cfg, start: u16) -> Vec<(u16, EdgeKind)> {
+        let mut edges: Vec<(u16, EdgeKind)> = cfg.successors(start).map(|edge| (edge.to, edge.kind)).collect();
+        edges.sort_by_key(|&(to, _)| to);
+        edges
+    }
+
+    #[test]
+    fn skips_fall_through_to_pc_plus_2_and_skip_to_pc_plus_4() {
+        // SE V0, 5; LD V0, 1; JP 0x200
+        let cfg = build(&[0x30, 0x05, 0x60, 0x01, 0x12, 0x00]);
+        assert_eq!(cfg.blocks().map(|block| block.start).collect::<Vec<_>>(), [0x200, 0x202, 0x204]);
+        assert_eq!(edges_from(&cfg, 0x200), [(0x202, EdgeKind::FallThrough), (0x204, EdgeKind::Skip)]);
+    }
+
+    #[test]
+    fn calls_and_rets_make_functions() {
+        // CALL 0x206; CALL 0x206; JP 0x204; LD V0, 1; RET
+        let cfg = build(&[0x22, 0x06, 0x22, 0x06, 0x12, 0x04, 0x60, 0x01, 0x00, 0xEE]);
+        assert_eq!(cfg.functions().map(|function| function.entry).collect::<Vec<_>>(), [LOAD_ADDRESS, 0x206]);
+        let function = cfg.function(0x206).unwrap();
+        assert_eq!(function.blocks.iter().copied().collect::<Vec<_>>(), [0x206]);
+        assert_eq!(function.returns.iter().copied().collect::<Vec<_>>(), [0x206]);
+        assert_eq!(function.callers.iter().copied().collect::<Vec<_>>(), [0x200, 0x202]);
+        assert_eq!(edges_from(&cfg, 0x200), [(0x206, EdgeKind::Call)]);
+        assert_eq!(edges_from(&cfg, 0x206), [(0x202, EdgeKind::Return), (0x204, EdgeKind::Return)]);
+        assert_eq!(cfg.well_formed_functions().map(|function| function.entry).collect::<Vec<_>>(), [0x206]);
+    }
+
+    #[test]
+    fn bnnn_is_an_indirect_jump_with_no_edges() {
+        // LD V0, 2; JP V0, 0x206; JP 0x204; JP 0x206
+        let cfg = build(&[0x60, 0x02, 0xB2, 0x06, 0x12, 0x04, 0x12, 0x06]);
+        assert_eq!(cfg.indirect_jumps().collect::<Vec<_>>(), [0x202]);
+        assert_eq!(edges_from(&cfg, 0x200), []);
+        assert!(cfg.block(0x206).is_none());
+    }
+
+    #[test]
+    fn jumps_into_the_middle_of_a_block_split_it() {
+        // LD V0, 1; ADD V0, 1; JP 0x202
+        let cfg = build(&[0x60, 0x01, 0x70, 0x01, 0x12, 0x02]);
+        let ranges: Vec<(u16, u16)> = cfg.blocks().map(|block| (block.start, block.end())).collect();
+        assert_eq!(ranges, [(0x200, 0x202), (0x202, 0x206)]);
+        assert_eq!(edges_from(&cfg, 0x200), [(0x202, EdgeKind::FallThrough)]);
+        assert_eq!(edges_from(&cfg, 0x202), [(0x202, EdgeKind::Jump)]);
+        assert_eq!(cfg.block_containing(0x204).map(|block| block.start), Some(0x202));
+    }
+}
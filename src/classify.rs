@@ -0,0 +1,300 @@
+/**
+ * classify.rs
+ *  this file works out what each byte of a program is for. Bytes the
+ *  control flow graph reaches as instructions are code, bytes a DXYN
+ *  draws are sprite data and bytes FX33, FX55 or FX65 use are scratch
+ *  memory. Anything else is unknown. What I holds comes from the
+ *  constants module. Where it is known exactly, the bytes used are marked
+ *  whatever else they are, except that code the program is known to run
+ *  stays code and the overlap is reported. Where I is only known to be in
+ *  a range, every byte a DXYN might draw is sprite data unless it is code,
+ *  and bytes other instructions might use are only marked if nothing else
+ *  claims them.
+ *
+ *  Code is known to run when the edges of the control flow graph lead to
+ *  it from the entry. Blocks only found as the instruction after a CALL
+ *  whose subroutine never returns are not, and sprite data those run into
+ *  is most likely a path the program never takes. code_cfg leaves such
+ *  blocks out, so neither the disassembler nor the recompilers treat them
+ *  as code.
+ */
+use std::collections::BTreeSet;
+
+use std::fmt;
+
+use crate::cfg::Cfg;
+use crate::constants::Constants;
+use crate::instruction::Instruction;
+use crate::rom::{Rom, LOAD_ADDRESS};
+
+// Widest range of I whose bytes are marked, enough for a table indexed by a register
+const WIDEST_RANGE: u16 = 0x100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteKind {
+    // Part of an instruction the program can reach
+    Code,
+    // Drawn by a DXYN
+    Sprite,
+    // Written by FX33 or FX55, or read by FX65
+    Scratch,
+    Unknown,
+}
+
+/**
+ * A run of bytes of the same kind
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u16,
+    // One past the last byte
+    pub end: u16,
+    pub kind: ByteKind,
+}
+
+pub struct Classification {
+    // Kind of every program byte, starting at the load address
+    kinds: Vec<ByteKind>,
+    // Whether each byte is part of code the program is known to run
+    executed: Vec<bool>,
+    // Addresses of code bytes that are also drawn as sprites
+    overlaps: BTreeSet<u16>,
+}
+
+/**
+ * executed_blocks returns the start of every block the edges of cfg lead
+ *    to from the load address
+ */
+fn executed_blocks(cfg: &Cfg) -> BTreeSet<u16> {
+    let mut executed = BTreeSet::new();
+    let mut worklist = vec![LOAD_ADDRESS];
+    while let Some(start) = worklist.pop() {
+        if cfg.block(start).is_some() && executed.insert(start) {
+            worklist.extend(cfg.successors(start).map(|edge| edge.to));
+        }
+    }
+    executed
+}
+
+/**
+ * memory_use returns how many bytes from I an instruction draws, stores
+ *    or loads, and what that makes them
+ */
+fn memory_use(instruction: &Instruction) -> Option<(u16, ByteKind)> {
+    match *instruction {
+        Instruction::Drw(_, _, n) => Some((n as u16, ByteKind::Sprite)),
+        Instruction::LdB(_) => Some((3, ByteKind::Scratch)),
+        Instruction::Store(x) | Instruction::Load(x) => Some((x as u16 + 1, ByteKind::Scratch)),
+        _ => None,
+    }
+}
+
+impl Classification {
+    /**
+     * analyze classifies every byte of a program. Where a byte is used in
+     *    more than one way, sprite data wins over code the program is not
+     *    known to run, and code wins over scratch memory, which the smc
+     *    module already guards.
+     */
+    pub fn analyze(rom: &Rom, cfg: &Cfg) -> Classification {
+        let mut classification = Classification {
+            kinds: vec![ByteKind::Unknown; rom.bytes.len()],
+            executed: vec![false; rom.bytes.len()],
+            overlaps: BTreeSet::new(),
+        };
+
+        let executed = executed_blocks(cfg);
+        for block in cfg.blocks() {
+            for &(pc, _) in &block.instructions {
+                classification.mark(pc, pc + 2, ByteKind::Code);
+                if executed.contains(&block.start) {
+                    for addr in pc..pc + 2 {
+                        if let Some(executed) = classification.executed.get_mut((addr - LOAD_ADDRESS) as usize) {
+                            *executed = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        let constants = Constants::analyze(cfg);
+        let mut ranged = Vec::new();
+        for &(pc, instruction) in cfg.blocks().flat_map(|block| block.instructions.iter()) {
+            let (length, kind) = match memory_use(&instruction) {
+                Some(usage) => usage,
+                None => continue,
+            };
+            let i = constants.i(pc);
+            match i.value() {
+                Some(addr) => classification.mark(addr, addr.saturating_add(length), kind),
+                None if i.high - i.low <= WIDEST_RANGE => ranged.push((i.low, i.high.saturating_add(length), kind)),
+                None => {}
+            }
+        }
+        for (start, end, kind) in ranged {
+            classification.fill(start, end, kind);
+        }
+        classification
+    }
+
+    /**
+     * mark records that the bytes from start up to end are used as kind,
+     *    unless they are already used in a way that wins over it
+     */
+    fn mark(&mut self, start: u16, end: u16, kind: ByteKind) {
+        for addr in start.max(LOAD_ADDRESS)..end {
+            let offset = (addr - LOAD_ADDRESS) as usize;
+            let current = match self.kinds.get_mut(offset) {
+                Some(current) => current,
+                None => break,
+            };
+            *current = match (*current, kind) {
+                (ByteKind::Code, ByteKind::Sprite) if self.executed[offset] => {
+                    self.overlaps.insert(addr);
+                    ByteKind::Code
+                }
+                (ByteKind::Sprite, _) | (ByteKind::Code, ByteKind::Scratch) => *current,
+                _ => kind,
+            };
+        }
+    }
+
+    /**
+     * fill records that the bytes from start up to end may be used as
+     *    kind. Sprite data takes every byte that is not code, anything else
+     *    only those nothing else is known about.
+     */
+    fn fill(&mut self, start: u16, end: u16, kind: ByteKind) {
+        let start = start.max(LOAD_ADDRESS);
+        for addr in start..end.max(start) {
+            match (self.kinds.get_mut((addr - LOAD_ADDRESS) as usize), kind) {
+                (Some(current @ ByteKind::Unknown), _) => *current = kind,
+                (Some(current @ ByteKind::Scratch), ByteKind::Sprite) => *current = kind,
+                (Some(_), _) => {}
+                (None, _) => break,
+            }
+        }
+    }
+
+    /**
+     * kind returns what the byte at an address is used for. Addresses
+     *    outside the program are unknown.
+     */
+    pub fn kind(&self, addr: u16) -> ByteKind {
+        match addr.checked_sub(LOAD_ADDRESS) {
+            Some(offset) => self.kinds.get(offset as usize).copied().unwrap_or(ByteKind::Unknown),
+            None => ByteKind::Unknown,
+        }
+    }
+
+    /**
+     * contains returns true if any byte from start up to end is of kind
+     */
+    pub fn contains(&self, start: u16, end: u16, kind: ByteKind) -> bool {
+        (start..end).any(|addr| self.kind(addr) == kind)
+    }
+
+    /**
+     * ranges returns the whole program as runs of bytes of the same kind,
+     *    in address order
+     */
+    pub fn ranges(&self) -> Vec<ByteRange> {
+        let mut ranges: Vec<ByteRange> = Vec::new();
+        for (offset, &kind) in self.kinds.iter().enumerate() {
+            let addr = LOAD_ADDRESS + offset as u16;
+            match ranges.last_mut() {
+                Some(range) if range.kind == kind => range.end = addr + 1,
+                _ => ranges.push(ByteRange { start: addr, end: addr + 1, kind }),
+            }
+        }
+        ranges
+    }
+
+    /**
+     * overlaps returns the runs of code the program is known to run that
+     *    are also drawn as sprites, as start and one past the end
+     */
+    pub fn overlaps(&self) -> Vec<(u16, u16)> {
+        let mut overlaps: Vec<(u16, u16)> = Vec::new();
+        for &addr in &self.overlaps {
+            match overlaps.last_mut() {
+                Some(overlap) if overlap.1 == addr => overlap.1 = addr + 1,
+                _ => overlaps.push((addr, addr + 1)),
+            }
+        }
+        overlaps
+    }
+}
+
+/**
+ * code_cfg recovers the control flow graph of a program without the
+ *    blocks that run into sprite data and are not known to run, which the
+ *    recompilers leave to the interpreter in case they are ever reached
+ */
+pub fn code_cfg(rom: &Rom) -> Cfg {
+    let mut cfg = Cfg::build(rom);
+    let classification = Classification::analyze(rom, &cfg);
+    cfg.retain_blocks(|block| !classification.contains(block.start, block.end(), ByteKind::Sprite));
+    cfg
+}
+
+impl fmt::Display for ByteKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ByteKind::Code => "code",
+            ByteKind::Sprite => "sprite",
+            ByteKind::Scratch => "scratch",
+            ByteKind::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(rom: &Rom) -> Vec<(u16, u16, ByteKind)> {
+        let classification = Classification::analyze(rom, &Cfg::build(rom));
+        classification.ranges().iter().map(|range| (range.start, range.end, range.kind)).collect()
+    }
+
+    #[test]
+    fn maze8_sprites_drawn_through_a_range_of_i() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/emulator/assets/maze8.ch8");
+        let rom = Rom::from_file(path).unwrap();
+        assert_eq!(ranges(&rom), vec![(0x200, 0x21A, ByteKind::Code), (0x21A, 0x222, ByteKind::Sprite)]);
+    }
+
+    #[test]
+    fn sprites_in_a_range_take_over_scratch_memory_but_not_code() {
+        // LD I, 0x20C; SE V0, 0; LD I, 0x20E; LD V1, [I]; DRW V0, V0, 3; JP 0x20A; then data
+        let rom = Rom::from_bytes(vec![
+            0xA2, 0x0C, 0x30, 0x00, 0xA2, 0x0E, 0xF1, 0x65, 0xD0, 0x03, 0x12, 0x0A, 0x11, 0x22, 0x33, 0x44, 0x55,
+        ])
+        .unwrap();
+        let expected = vec![(0x200, 0x20C, ByteKind::Code), (0x20C, 0x211, ByteKind::Sprite)];
+        assert_eq!(ranges(&rom), expected);
+    }
+
+    #[test]
+    fn code_that_runs_stays_code_when_i_points_into_it() {
+        // LD I, 0x200; DRW V0, V1, 3; JP 0x202
+        let rom = Rom::from_bytes(vec![0xA2, 0x00, 0xD0, 0x13, 0x12, 0x02]).unwrap();
+        assert_eq!(ranges(&rom), vec![(0x200, 0x206, ByteKind::Code)]);
+        assert_eq!(Classification::analyze(&rom, &Cfg::build(&rom)).overlaps(), [(0x200, 0x203)]);
+        assert_eq!(code_cfg(&rom).blocks().map(|block| block.start).collect::<Vec<_>>(), [0x200, 0x202]);
+    }
+
+    #[test]
+    fn sprites_after_a_call_that_never_returns_are_not_code() {
+        // CALL 0x206; then data; JP 0x204; LD I, 0x202; DRW V0, V1, 2; JP 0x20A
+        let rom =
+            Rom::from_bytes(vec![0x22, 0x06, 0xF0, 0x90, 0x12, 0x04, 0xA2, 0x02, 0xD0, 0x12, 0x12, 0x0A]).unwrap();
+        let expected =
+            vec![(0x200, 0x202, ByteKind::Code), (0x202, 0x204, ByteKind::Sprite), (0x204, 0x20C, ByteKind::Code)];
+        assert_eq!(ranges(&rom), expected);
+        assert_eq!(Classification::analyze(&rom, &Cfg::build(&rom)).overlaps(), []);
+        assert!(code_cfg(&rom).block(0x202).is_none());
+    }
+}
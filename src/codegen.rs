@@ -0,0 +1,112 @@
+/**
+ * codegen.rs
+ *  this file turns a lifted module into a native executable: it parses the
+ *  module's text into LLVM, runs LLVM's optimizer over it, has a target
+ *  machine write it out as an object file and links that with the runtime
+ *  library using the system C compiler. Only built with the llvm feature.
+ */
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use inkwell::context::Context;
+use inkwell::memory_buffer::MemoryBuffer;
+use inkwell::module::Module;
+use inkwell::passes::{PassManager, PassManagerBuilder};
+use inkwell::targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple};
+use inkwell::OptimizationLevel;
+
+use crate::ir;
+
+// Libraries the Rust standard library inside the runtime needs
+const SYSTEM_LIBS: [&str; 5] = ["-lpthread", "-ldl", "-lm", "-lrt", "-lutil"];
+
+/**
+ * to_llvm parses a module built with the ir module into an LLVM module
+ *    owned by context
+ */
+pub fn to_llvm<'ctx>(context: &'ctx Context, module: &ir::Module) -> Result<Module<'ctx>, String> {
+    let text = module.to_string();
+    let buffer = MemoryBuffer::create_from_memory_range_copy(text.as_bytes(), module.name());
+    let module = context.create_module_from_ir(buffer).map_err(|e| format!("Generated invalid IR: {}", e))?;
+    module.verify().map_err(|e| format!("Generated invalid IR: {}", e))?;
+    Ok(module)
+}
+
+/**
+ * optimize runs LLVM's standard module passes for an optimization level
+ */
+pub fn optimize(module: &Module, level: OptimizationLevel) {
+    if level == OptimizationLevel::None {
+        return;
+    }
+    let builder = PassManagerBuilder::create();
+    builder.set_optimization_level(level);
+    let passes = PassManager::create(());
+    builder.populate_module_pass_manager(&passes);
+    passes.run_on(module);
+}
+
+/**
+ * write_object compiles a module to an object file for a target triple,
+ *    or the host if there is none
+ */
+pub fn write_object(module: &Module, triple: Option<&str>, level: OptimizationLevel, path: &Path) -> Result<(), String> {
+    Target::initialize_all(&InitializationConfig::default());
+    let triple = match triple {
+        Some(triple) => TargetTriple::create(triple),
+        None => TargetMachine::get_default_triple(),
+    };
+    let target = Target::from_triple(&triple).map_err(|e| format!("Unknown target {}: {}", triple, e))?;
+    // Position independent, since most C compilers link PIE by default
+    let machine = target
+        .create_target_machine(&triple, "generic", "", level, RelocMode::PIC, CodeModel::Default)
+        .ok_or(format!("Cannot create a target machine for {}", triple))?;
+
+    module.set_triple(&triple);
+    module.set_data_layout(&machine.get_target_data().get_data_layout());
+    machine
+        .write_to_file(module, FileType::Object, path)
+        .map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+}
+
+/**
+ * default_runtime finds the runtime static library: $CHIP8_RT_LIB if it
+ *    is set, otherwise a release or debug build of the runtime crate
+ */
+pub fn default_runtime() -> Result<PathBuf, String> {
+    if let Ok(path) = env::var("CHIP8_RT_LIB") {
+        return Ok(PathBuf::from(path));
+    }
+    let target = Path::new(env!("CARGO_MANIFEST_DIR")).join("runtime").join("target");
+    ["release", "debug"]
+        .iter()
+        .map(|profile| target.join(profile).join("libchip8_rt.a"))
+        .find(|path| path.exists())
+        .ok_or_else(|| {
+            "Cannot find libchip8_rt.a: build it with `cargo build --release --manifest-path runtime/Cargo.toml`, \
+             set CHIP8_RT_LIB or pass --runtime"
+                .to_string()
+        })
+}
+
+/**
+ * link links an object file with the runtime into an executable using
+ *    $CC, or cc. sdl should match whether the runtime was built with its
+ *    sdl feature.
+ */
+pub fn link(object: &Path, runtime: &Path, output: &Path, sdl: bool) -> Result<(), String> {
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let mut command = Command::new(&compiler);
+    command.arg(object).arg(runtime).arg("-o").arg(output);
+    if sdl {
+        command.arg("-lSDL2");
+    }
+    command.args(SYSTEM_LIBS);
+
+    let status = command.status().map_err(|e| format!("Cannot run {}: {}", compiler, e))?;
+    if !status.success() {
+        return Err(format!("{} failed to link {}", compiler, output.display()));
+    }
+    Ok(())
+}
@@ -0,0 +1,504 @@
+/**
+ * constants.rs
+ *  this file works out what values V0-VF and I can hold before every
+ *  instruction, as the range between the lowest and highest value. Most
+ *  DXYN, FX33, FX55 and FX65 use an I set by a nearby ANNN, so I is often
+ *  known exactly, and V registers are when they were loaded with a byte.
+ *
+ *  The analysis runs forwards over the whole control flow graph, calls and
+ *  returns included, from the registers the machine starts with. Programs
+ *  that may ever run in the interpreter are the exception: the interpreter
+ *  can hand back to any block with any values, so then nothing is known
+ *  at the start of a block and only what a block does itself is tracked.
+ *  The same goes for programs whose RETs may go somewhere the return edges
+ *  do not lead, such as a subroutine that jumps away instead of returning.
+ */
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::cfg::{BasicBlock, Cfg};
+use crate::instruction::Instruction;
+use crate::rom::LOAD_ADDRESS;
+use crate::smc::SelfModification;
+
+// Times a register's range can grow at the start of a block before it is
+// given up on
+const WIDEN_AFTER: u32 = 8;
+
+// Growth counted for each of V0-VF, then I
+type Growth = [u32; 17];
+
+/**
+ * Every value from low to high, inclusive
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range {
+    pub low: u16,
+    pub high: u16,
+}
+
+impl Range {
+    pub fn constant(value: u16) -> Range {
+        Range { low: value, high: value }
+    }
+
+    /**
+     * byte returns the range of a V register that could hold anything
+     */
+    pub fn byte() -> Range {
+        Range { low: 0, high: u8::MAX as u16 }
+    }
+
+    /**
+     * word returns the range of I when it could hold anything
+     */
+    pub fn word() -> Range {
+        Range { low: 0, high: u16::MAX }
+    }
+
+    /**
+     * value returns the one value in the range, if there is only one
+     */
+    pub fn value(&self) -> Option<u16> {
+        if self.low == self.high {
+            Some(self.low)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, value: u16) -> bool {
+        self.low <= value && value <= self.high
+    }
+
+    fn join(&self, other: &Range) -> Range {
+        Range { low: self.low.min(other.low), high: self.high.max(other.high) }
+    }
+
+    /**
+     * add returns the range of sums, or full when they might wrap past max
+     */
+    fn add(&self, other: &Range, max: u16, full: Range) -> Range {
+        match (self.high.checked_add(other.high), self.low.checked_add(other.low)) {
+            (Some(high), Some(low)) if high <= max => Range { low, high },
+            _ => full,
+        }
+    }
+}
+
+/**
+ * What the registers can hold at one point in the program
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Registers {
+    pub v: [Range; 16],
+    pub i: Range,
+}
+
+impl Registers {
+    /**
+     * start returns the registers as the machine starts with them
+     */
+    fn start() -> Registers {
+        Registers { v: [Range::constant(0); 16], i: Range::constant(0) }
+    }
+
+    /**
+     * unknown returns registers that could hold anything
+     */
+    fn unknown() -> Registers {
+        Registers { v: [Range::byte(); 16], i: Range::word() }
+    }
+
+    fn join(&self, other: &Registers) -> Registers {
+        let mut joined = *self;
+        for (joined, other) in joined.v.iter_mut().zip(other.v.iter()) {
+            *joined = joined.join(other);
+        }
+        joined.i = self.i.join(&other.i);
+        joined
+    }
+
+    /**
+     * widen counts each register that grew from old in growth, and gives
+     *    up on those that have grown more than WIDEN_AFTER times. Others
+     *    keep their range, however often the rest grow.
+     */
+    fn widen(&self, old: &Registers, growth: &mut Growth) -> Registers {
+        let mut widened = *self;
+        for (x, (widened, old)) in widened.v.iter_mut().zip(old.v.iter()).enumerate() {
+            if widened != old {
+                growth[x] += 1;
+                if growth[x] > WIDEN_AFTER {
+                    *widened = Range::byte();
+                }
+            }
+        }
+        if widened.i != old.i {
+            growth[16] += 1;
+            if growth[16] > WIDEN_AFTER {
+                widened.i = Range::word();
+            }
+        }
+        widened
+    }
+
+    /**
+     * constant_pair returns the values of two V registers if both are known
+     */
+    fn constant_pair(&self, x: u8, y: u8) -> Option<(u8, u8)> {
+        Some((self.v[x as usize].value()? as u8, self.v[y as usize].value()? as u8))
+    }
+
+    /**
+     * execute updates the registers for one instruction, the same way
+     *    CPU::execute_next_opcode changes them
+     */
+    fn execute(&mut self, instruction: &Instruction) {
+        let flag = Range { low: 0, high: 1 };
+        match *instruction {
+            Instruction::LdByte(x, byte) => self.v[x as usize] = Range::constant(byte as u16),
+            Instruction::AddByte(x, byte) => {
+                let vx = self.v[x as usize];
+                self.v[x as usize] = match vx.value() {
+                    Some(value) => Range::constant((value as u8).wrapping_add(byte) as u16),
+                    None => vx.add(&Range::constant(byte as u16), u8::MAX as u16, Range::byte()),
+                };
+            }
+            Instruction::LdReg(x, y) => self.v[x as usize] = self.v[y as usize],
+            Instruction::Or(x, y) => {
+                self.v[x as usize] = match self.constant_pair(x, y) {
+                    Some((vx, vy)) => Range::constant((vx | vy) as u16),
+                    None => Range::byte(),
+                };
+            }
+            Instruction::Xor(x, y) => {
+                self.v[x as usize] = match self.constant_pair(x, y) {
+                    Some((vx, vy)) => Range::constant((vx ^ vy) as u16),
+                    None => Range::byte(),
+                };
+            }
+            Instruction::And(x, y) => {
+                self.v[x as usize] = match self.constant_pair(x, y) {
+                    Some((vx, vy)) => Range::constant((vx & vy) as u16),
+                    None => Range { low: 0, high: self.v[x as usize].high.min(self.v[y as usize].high) },
+                };
+            }
+            Instruction::AddReg(x, y) => {
+                let (vx, vy) = (self.v[x as usize], self.v[y as usize]);
+                let (low, high) = (vx.low + vy.low, vx.high + vy.high);
+                let (sum, carry) = if high <= 0xFF {
+                    (Range { low, high }, Range::constant(0))
+                } else if low > 0xFF {
+                    (Range { low: low - 0x100, high: high - 0x100 }, Range::constant(1))
+                } else {
+                    (Range::byte(), flag)
+                };
+                self.v[0xF] = carry;
+                self.v[x as usize] = sum;
+            }
+            // VF is written before Vx, which may itself be VF
+            Instruction::Sub(x, y) | Instruction::Subn(x, y) => {
+                let pair = self.constant_pair(x, y);
+                let subn = matches!(instruction, Instruction::Subn(..));
+                self.v[0xF] = match pair {
+                    Some((vx, vy)) if subn => Range::constant((vy > vx) as u16),
+                    Some((vx, vy)) => Range::constant((vx > vy) as u16),
+                    None => flag,
+                };
+                self.v[x as usize] = match self.constant_pair(x, y) {
+                    Some((vx, vy)) if subn => Range::constant(vy.wrapping_sub(vx) as u16),
+                    Some((vx, vy)) => Range::constant(vx.wrapping_sub(vy) as u16),
+                    None => Range::byte(),
+                };
+            }
+            Instruction::Shr(x, _) => {
+                let vx = self.v[x as usize];
+                self.v[0xF] = match vx.value() {
+                    Some(value) => Range::constant(value & 0x01),
+                    None => flag,
+                };
+                let vx = self.v[x as usize];
+                self.v[x as usize] = Range { low: vx.low >> 1, high: vx.high >> 1 };
+            }
+            Instruction::Shl(x, _) => {
+                let vx = self.v[x as usize];
+                self.v[0xF] = match vx.value() {
+                    Some(value) => Range::constant((value & 0x80) >> 7),
+                    None => flag,
+                };
+                self.v[x as usize] = match self.v[x as usize].value() {
+                    Some(value) => Range::constant((value << 1) & 0xFF),
+                    None => Range::byte(),
+                };
+            }
+            Instruction::LdI(addr) => self.i = Range::constant(addr),
+            Instruction::AddI(x) => self.i = self.i.add(&self.v[x as usize], u16::MAX, Range::word()),
+            Instruction::LdF(x) => {
+                let vx = self.v[x as usize];
+                self.i = Range { low: vx.low * 5, high: vx.high * 5 };
+            }
+            Instruction::Rnd(x, byte) => self.v[x as usize] = Range { low: 0, high: byte as u16 },
+            Instruction::Drw(..) => self.v[0xF] = flag,
+            Instruction::LdVxDt(x) => self.v[x as usize] = Range::byte(),
+            Instruction::LdVxK(x) => self.v[x as usize] = Range { low: 0, high: 0xF },
+            Instruction::Load(x) => {
+                for v in &mut self.v[..=x as usize] {
+                    *v = Range::byte();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct Constants {
+    // Registers before every lifted instruction, by address
+    before: BTreeMap<u16, Registers>,
+    // First and one past the last address each FX33 and FX55 might write
+    stores: Vec<(u32, u32)>,
+    // Whether the program may ever run in the interpreter
+    interpreted: bool,
+}
+
+/**
+ * successors returns every address control can go to from the end of a
+ *    block, other than through a RET
+ */
+fn successors(block: &BasicBlock) -> Vec<u16> {
+    let (pc, instruction) = block.terminator();
+    match instruction {
+        Instruction::Jp(addr) => vec![addr],
+        Instruction::Call(addr) => vec![addr, pc + 2],
+        Instruction::Ret | Instruction::JpV0(_) => vec![],
+        _ if instruction.is_skip() => vec![pc + 2, pc + 4],
+        _ => vec![pc + 2],
+    }
+}
+
+/**
+ * may_leave_code returns true if a program can end up in the interpreter
+ *    without patching any code: it has a BNNN or a way to reach an address
+ *    with no block
+ */
+fn may_leave_code(cfg: &Cfg) -> bool {
+    cfg.indirect_jumps().next().is_some()
+        || cfg.block(LOAD_ADDRESS).is_none()
+        || cfg.blocks().flat_map(successors).any(|addr| cfg.block(addr).is_none())
+}
+
+/**
+ * may_interpret returns true if a program can ever end up in the
+ *    interpreter: it may leave its code, or has a store that may patch
+ *    lifted code
+ */
+fn may_interpret(cfg: &Cfg) -> bool {
+    may_leave_code(cfg) || SelfModification::analyze(cfg).writes().next().is_some()
+}
+
+/**
+ * returns_covered returns true if the return edges lead everywhere a RET
+ *    can go: every subroutine is well formed, so it is only left by its
+ *    own RET back to one of its callers, and the program entry never
+ *    returns
+ */
+fn returns_covered(cfg: &Cfg) -> bool {
+    let well_formed: BTreeSet<u16> = cfg.well_formed_functions().map(|function| function.entry).collect();
+    cfg.functions().all(|function| match function.entry {
+        LOAD_ADDRESS => function.returns.is_empty(),
+        entry => well_formed.contains(&entry),
+    })
+}
+
+impl Constants {
+    /**
+     * analyze works out the registers before every instruction in cfg
+     */
+    pub fn analyze(cfg: &Cfg) -> Constants {
+        Constants::solve(cfg, may_interpret(cfg))
+    }
+
+    /**
+     * unpatched works out the registers as if no store ever patched lifted
+     *    code. Until the first store that does, that is what they hold, so
+     *    the smc module finds such stores with it.
+     */
+    pub fn unpatched(cfg: &Cfg) -> Constants {
+        Constants::solve(cfg, may_leave_code(cfg))
+    }
+
+    /**
+     * interpreted works out the registers as if the interpreter could hand
+     *    back to any block, so only what each block does itself is known
+     */
+    pub fn interpreted(cfg: &Cfg) -> Constants {
+        Constants::solve(cfg, true)
+    }
+
+    /**
+     * solve works out the registers before every instruction in cfg, from
+     *    unknown registers at every block if interpreted is set
+     */
+    fn solve(cfg: &Cfg, interpreted: bool) -> Constants {
+        // Whether a block can be entered with registers the analysis never sees
+        let unknown_entries = interpreted || !returns_covered(cfg);
+        let mut entries: BTreeMap<u16, Registers> = BTreeMap::new();
+        let mut growth: BTreeMap<u16, Growth> = BTreeMap::new();
+        let mut worklist = Vec::new();
+        if unknown_entries {
+            for block in cfg.blocks() {
+                entries.insert(block.start, Registers::unknown());
+                worklist.push(block.start);
+            }
+        } else {
+            entries.insert(LOAD_ADDRESS, Registers::start());
+            worklist.push(LOAD_ADDRESS);
+        }
+
+        let mut before = BTreeMap::new();
+        while let Some(start) = worklist.pop() {
+            let block = match cfg.block(start) {
+                Some(block) => block,
+                None => continue,
+            };
+            let mut registers = entries[&start];
+            for (pc, instruction) in &block.instructions {
+                before.insert(*pc, registers);
+                registers.execute(instruction);
+            }
+            if unknown_entries {
+                continue;
+            }
+
+            for edge in cfg.successors(start) {
+                let entry = match entries.get(&edge.to) {
+                    Some(old) => {
+                        let joined = old.join(&registers);
+                        if joined == *old {
+                            continue;
+                        }
+                        joined.widen(old, growth.entry(edge.to).or_insert([0; 17]))
+                    }
+                    None => registers,
+                };
+                entries.insert(edge.to, entry);
+                worklist.push(edge.to);
+            }
+        }
+
+        // Only instructions the analysis reached can ever run
+        let mut stores = Vec::new();
+        for (pc, instruction) in cfg.blocks().flat_map(|block| block.instructions.iter()) {
+            let length = match instruction {
+                Instruction::LdB(_) => 3,
+                Instruction::Store(x) => *x as u32 + 1,
+                _ => continue,
+            };
+            if let Some(registers) = before.get(pc) {
+                stores.push((registers.i.low as u32, registers.i.high as u32 + length));
+            }
+        }
+
+        Constants { before, stores, interpreted }
+    }
+
+    /**
+     * before returns the registers just before the instruction at pc runs,
+     *    or None if no instruction there was lifted
+     */
+    pub fn before(&self, pc: u16) -> Option<&Registers> {
+        self.before.get(&pc)
+    }
+
+    /**
+     * may_write returns true if any of the bytes from start up to end
+     *    might ever be written, by lifted code or the interpreter
+     */
+    pub fn may_write(&self, start: u16, end: u16) -> bool {
+        self.interpreted || self.stores.iter().any(|&(low, high)| low < end as u32 && (start as u32) < high)
+    }
+
+    /**
+     * i returns the range I is in before the instruction at pc
+     */
+    pub fn i(&self, pc: u16) -> Range {
+        self.before(pc).map_or(Range::word(), |registers| registers.i)
+    }
+
+    /**
+     * v returns the range Vx is in before the instruction at pc
+     */
+    pub fn v(&self, pc: u16, x: u8) -> Range {
+        self.before(pc).map_or(Range::byte(), |registers| registers.v[x as usize])
+    }
+}
+
+/**
+ * The default knows nothing, for code lifted without the rest of its program
+ */
+impl Default for Constants {
+    fn default() -> Constants {
+        Constants { before: BTreeMap::new(), stores: Vec::new(), interpreted: true }
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value() {
+            Some(value) => write!(f, "0x{:X}", value),
+            None => write!(f, "0x{:X}-0x{:X}", self.low, self.high),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rom::Rom;
+
+    fn analyze(rom: &Rom) -> Constants {
+        Constants::analyze(&Cfg::build(rom))
+    }
+
+    #[test]
+    fn maze8_draws_from_its_two_sprites() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/emulator/assets/maze8.ch8");
+        let constants = analyze(&Rom::from_file(path).unwrap());
+        assert_eq!(constants.i(0x208), Range { low: 0x21A, high: 0x21E });
+    }
+
+    #[test]
+    fn only_registers_that_keep_growing_are_widened() {
+        // LD I, 0x300; ADD V0, 1; ADD I, V1; SE V2, 0; LD I, 0x310; JP 0x202
+        let rom = Rom::from_bytes(vec![0xA3, 0x00, 0x70, 0x01, 0xF1, 0x1E, 0x32, 0x00, 0xA3, 0x10, 0x12, 0x02]).unwrap();
+        let constants = analyze(&rom);
+        assert_eq!(constants.v(0x202, 0), Range::byte());
+        assert_eq!(constants.i(0x202), Range { low: 0x300, high: 0x310 });
+    }
+
+    #[test]
+    fn i_is_carried_back_from_well_formed_subroutines() {
+        // CALL 0x206; LD V0, [I]; JP 0x204; LD I, 0x300; RET
+        let rom = Rom::from_bytes(vec![0x22, 0x06, 0xF0, 0x65, 0x12, 0x04, 0xA3, 0x00, 0x00, 0xEE]).unwrap();
+        assert_eq!(analyze(&rom).i(0x202), Range::constant(0x300));
+    }
+
+    #[test]
+    fn nothing_is_known_at_entries_when_a_subroutine_jumps_away() {
+        // LD I, 0x300; CALL 0x20C; LD V0, [I]; JP 0x206; then at 0x20C LD I, 0xFFF; JP 0x204
+        let rom = Rom::from_bytes(vec![
+            0xA3, 0x00, 0x22, 0x0C, 0xF0, 0x65, 0x12, 0x06, 0x00, 0x00, 0x00, 0x00, 0xAF, 0xFF, 0x12, 0x04,
+        ])
+        .unwrap();
+        let constants = analyze(&rom);
+        assert_eq!(constants.i(0x204), Range::word());
+        assert_eq!(constants.i(0x20E), Range::constant(0xFFF));
+    }
+
+    #[test]
+    fn nothing_is_known_at_entries_when_the_program_entry_returns() {
+        // CALL 0x206; LD V0, [I]; RET; LD I, 0x300; RET
+        let rom = Rom::from_bytes(vec![0x22, 0x06, 0xF0, 0x65, 0x00, 0xEE, 0xA3, 0x00, 0x00, 0xEE]).unwrap();
+        assert_eq!(analyze(&rom).i(0x202), Range::word());
+    }
+}
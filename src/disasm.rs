@@ -0,0 +1,263 @@
+/**
+ * disasm.rs
+ *  this file turns a chip8 program back into a readable listing. Code the
+ *  control flow graph reaches is printed as mnemonics, jump and call
+ *  targets get generated labels, and everything else is printed as db
+ *  byte directives so that no byte of the program is lost. Each db line
+ *  holds bytes of one kind from the classify module, so sprite data and
+ *  scratch memory are set apart from bytes nothing was seen using.
+ *
+ *  debug_listing writes a second, simpler listing with one line for every
+ *  address, which debug info in lifted modules uses as its source file.
+ */
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::cfg::Cfg;
+use crate::classify::{code_cfg, ByteKind, Classification};
+use crate::instruction::Instruction;
+use crate::rom::{Rom, LOAD_ADDRESS};
+
+// Most bytes a single db line holds
+const BYTES_PER_DATA_LINE: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    // An instruction the program can reach
+    Code(Instruction),
+    // Bytes that were never reached as code, and what they are used for
+    Data(ByteKind),
+}
+
+/**
+ * One line of a listing: an instruction or a run of data bytes
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisasmLine {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    // Label for this address, if anything jumps or calls here
+    pub label: Option<String>,
+    pub kind: LineKind,
+    // Mnemonic or db directive, with targets written as labels
+    pub text: String,
+}
+
+/**
+ * find_code returns every reachable instruction that can be written back
+ *    out exactly. Where two reachable instructions overlap the first wins
+ *    and the bytes of the second are left to the first.
+ */
+fn find_code(rom: &Rom, cfg: &Cfg) -> BTreeMap<u16, Instruction> {
+    let mut reached = BTreeMap::new();
+    for block in cfg.blocks() {
+        for &(pc, instruction) in &block.instructions {
+            reached.insert(pc, instruction);
+        }
+    }
+
+    let mut code = BTreeMap::new();
+    let mut next_free = LOAD_ADDRESS;
+    for (pc, instruction) in reached {
+        let opcode = rom.get_opcode(pc).unwrap();
+        // Unknown opcodes and ignored bits can only be kept as data
+        let exact = instruction.encode() == opcode && !matches!(instruction, Instruction::Unknown(_));
+        if pc >= next_free && exact {
+            code.insert(pc, instruction);
+            next_free = pc + 2;
+        }
+    }
+    code
+}
+
+/**
+ * find_labels names every jump and call target that starts a line.
+ *    Subroutine entries are sub_XXX, everything else label_XXX.
+ */
+fn find_labels(rom: &Rom, code: &BTreeMap<u16, Instruction>) -> BTreeMap<u16, String> {
+    // Targets inside an instruction cannot be labelled
+    let starts_line = |addr: u16| {
+        rom.contains(addr) && (code.contains_key(&addr) || !code.contains_key(&(addr.wrapping_sub(1))))
+    };
+
+    let mut labels = BTreeMap::new();
+    for instruction in code.values() {
+        match *instruction {
+            Instruction::Call(addr) if starts_line(addr) => {
+                labels.insert(addr, format!("sub_{:03X}", addr));
+            }
+            Instruction::Jp(addr) | Instruction::JpV0(addr) if starts_line(addr) => {
+                labels.entry(addr).or_insert_with(|| format!("label_{:03X}", addr));
+            }
+            _ => {}
+        }
+    }
+    labels
+}
+
+/**
+ * code_text writes an instruction, using a label in place of its target
+ *    address when there is one
+ */
+fn code_text(instruction: Instruction, labels: &BTreeMap<u16, String>) -> String {
+    let label = |addr: u16| labels.get(&addr);
+    match instruction {
+        Instruction::Jp(addr) if label(addr).is_some() => format!("JP {}", label(addr).unwrap()),
+        Instruction::Call(addr) if label(addr).is_some() => format!("CALL {}", label(addr).unwrap()),
+        Instruction::JpV0(addr) if label(addr).is_some() => format!("JP V0, {}", label(addr).unwrap()),
+        Instruction::LdI(addr) if label(addr).is_some() => format!("LD I, {}", label(addr).unwrap()),
+        _ => instruction.to_string(),
+    }
+}
+
+/**
+ * data_text writes a db directive for some bytes
+ */
+fn data_text(bytes: &[u8]) -> String {
+    let bytes: Vec<String> = bytes.iter().map(|b| format!("0x{:02X}", b)).collect();
+    format!("db {}", bytes.join(", "))
+}
+
+/**
+ * disassemble lists every byte of a program, in address order
+ */
+pub fn disassemble(rom: &Rom) -> Vec<DisasmLine> {
+    let cfg = code_cfg(rom);
+    let code = find_code(rom, &cfg);
+    let labels = find_labels(rom, &code);
+    let classification = Classification::analyze(rom, &cfg);
+
+    let mut lines = Vec::new();
+    let mut address = LOAD_ADDRESS;
+    while address < rom.end() {
+        let label = labels.get(&address).cloned();
+        if let Some(&instruction) = code.get(&address) {
+            lines.push(DisasmLine {
+                address,
+                bytes: vec![rom.read_byte(address).unwrap(), rom.read_byte(address + 1).unwrap()],
+                label,
+                kind: LineKind::Code(instruction),
+                text: code_text(instruction, &labels),
+            });
+            address += 2;
+            continue;
+        }
+
+        // Data runs until the next instruction or label, or bytes of another kind
+        let kind = classification.kind(address);
+        let mut bytes = vec![rom.read_byte(address).unwrap()];
+        let mut next = address + 1;
+        while next < rom.end()
+            && bytes.len() < BYTES_PER_DATA_LINE
+            && !code.contains_key(&next)
+            && !labels.contains_key(&next)
+            && classification.kind(next) == kind
+        {
+            bytes.push(rom.read_byte(next).unwrap());
+            next += 1;
+        }
+        lines.push(DisasmLine { address, text: data_text(&bytes), bytes, label, kind: LineKind::Data(kind) });
+        address = next;
+    }
+    lines
+}
+
+/**
+ * listing writes lines out as assembler source, with the address and raw
+ *    bytes of each line in a comment
+ */
+pub fn listing(lines: &[DisasmLine]) -> String {
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&line.to_string());
+        out.push('\n');
+    }
+    out
+}
+
+/**
+ * debug_line returns the line of the debug listing describing address
+ */
+pub fn debug_line(address: u16) -> u32 {
+    (address - LOAD_ADDRESS) as u32 + 1
+}
+
+/**
+ * debug_listing writes a program out with exactly one line per address.
+ *    Lines for addresses a reachable instruction starts at show it, even
+ *    where instructions overlap, and every other line shows its byte.
+ */
+pub fn debug_listing(rom: &Rom) -> String {
+    let cfg = code_cfg(rom);
+    let reached: BTreeMap<u16, Instruction> =
+        cfg.blocks().flat_map(|block| block.instructions.iter().copied()).collect();
+
+    let mut out = String::new();
+    for address in LOAD_ADDRESS..rom.end() {
+        let byte = rom.read_byte(address).unwrap();
+        match reached.get(&address) {
+            Some(instruction) => {
+                let opcode = rom.get_opcode(address).unwrap();
+                out.push_str(&format!("0x{:03X}  {:04X}  {}\n", address, opcode, instruction));
+            }
+            None => out.push_str(&format!("0x{:03X}  {:02X}\n", address, byte)),
+        }
+    }
+    out
+}
+
+impl fmt::Display for DisasmLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(label) = &self.label {
+            writeln!(f, "{}:", label)?;
+        }
+        let bytes: Vec<String> = self.bytes.iter().map(|b| format!("{:02X}", b)).collect();
+        write!(f, "    {:<31} ; 0x{:03X}  {}", self.text, self.address, bytes.join(" "))?;
+        match self.kind {
+            LineKind::Data(kind @ (ByteKind::Sprite | ByteKind::Scratch)) => write!(f, "  ({})", kind),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // CALL 0x208; LD I, 0x20C; DRW V0, V0, 2; JP 0x206; CLS; RET; a sprite; two bytes nothing uses
+    const PROGRAM: &[u8] = &[
+        0x22, 0x08, 0xA2, 0x0C, 0xD0, 0x02, 0x12, 0x06, 0x00, 0xE0, 0x00, 0xEE, 0xF0, 0x90, 0x12, 0x34,
+    ];
+
+    fn lines() -> Vec<DisasmLine> {
+        disassemble(&Rom::from_bytes(PROGRAM.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn jump_and_call_targets_are_labelled() {
+        let lines = lines();
+        let labels: Vec<(u16, &str)> =
+            lines.iter().filter_map(|line| Some((line.address, line.label.as_deref()?))).collect();
+        assert_eq!(labels, [(0x206, "label_206"), (0x208, "sub_208")]);
+        assert_eq!(lines[0].text, "CALL sub_208");
+        assert_eq!(lines[3].text, "JP label_206");
+        assert_eq!(lines[3].to_string(), "label_206:\n    JP label_206                    ; 0x206  12 06");
+    }
+
+    #[test]
+    fn bytes_that_are_not_code_are_db_lines_of_one_kind() {
+        let lines = lines();
+        let data: Vec<(u16, LineKind, &str)> = lines
+            .iter()
+            .filter(|line| matches!(line.kind, LineKind::Data(_)))
+            .map(|line| (line.address, line.kind, line.text.as_str()))
+            .collect();
+        let expected = [
+            (0x20C, LineKind::Data(ByteKind::Sprite), "db 0xF0, 0x90"),
+            (0x20E, LineKind::Data(ByteKind::Unknown), "db 0x12, 0x34"),
+        ];
+        assert_eq!(data, expected);
+        assert!(lines[6].to_string().ends_with("; 0x20C  F0 90  (sprite)"));
+        assert!(lines[7].to_string().ends_with("; 0x20E  12 34"));
+    }
+}
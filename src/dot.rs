@@ -0,0 +1,163 @@
+/**
+ * dot.rs
+ *  this file writes a control flow graph out as a Graphviz graph, for
+ *  looking over how a program was recovered. Each basic block is a node
+ *  listing its instructions, each edge is drawn in the style of its kind
+ *  and each subroutine is a cluster. A block shared between subroutines
+ *  is drawn in the one with the lowest entry.
+ *
+ *  Blocks ending in a BNNN are filled red and lead to a red node standing
+ *  for the unknown target, labelled with what V0 may hold there. Targets
+ *  seen in a trace are drawn as red edges as well.
+ */
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::cfg::{BasicBlock, Cfg, EdgeKind};
+use crate::constants::Constants;
+use crate::instruction::Instruction;
+use crate::rom::{Rom, LOAD_ADDRESS};
+
+/**
+ * edge_style returns the Graphviz attributes edges of a kind are drawn with
+ */
+fn edge_style(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::FallThrough => "color=black",
+        EdgeKind::Skip => "color=blue, style=dashed, label=\"skip\"",
+        EdgeKind::Jump => "color=black, style=bold",
+        EdgeKind::Call => "color=darkgreen, style=bold, label=\"call\"",
+        EdgeKind::Return => "color=gray50, style=dotted, label=\"ret\"",
+        EdgeKind::Indirect => "color=red, style=bold, label=\"traced\"",
+    }
+}
+
+/**
+ * block_label lists the instructions of a block, one left aligned line
+ *    each, with their address and opcode
+ */
+fn block_label(rom: &Rom, block: &BasicBlock) -> String {
+    let mut label = String::new();
+    for &(pc, instruction) in &block.instructions {
+        let opcode = rom.get_opcode(pc).unwrap();
+        write!(label, "0x{:03X}  {:04X}  {}\\l", pc, opcode, instruction).unwrap();
+    }
+    label
+}
+
+/**
+ * to_dot writes cfg, recovered from rom, as a Graphviz digraph
+ */
+pub fn to_dot(rom: &Rom, cfg: &Cfg) -> String {
+    let constants = Constants::analyze(cfg);
+    let mut clusters: BTreeMap<u16, Vec<&BasicBlock>> = BTreeMap::new();
+    let mut top = Vec::new();
+    for block in cfg.blocks() {
+        let mut entries = cfg.functions_containing(block.start).map(|function| function.entry);
+        match entries.find(|&entry| entry != LOAD_ADDRESS) {
+            Some(entry) => clusters.entry(entry).or_default().push(block),
+            None => top.push(block),
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("digraph chip8 {\n");
+    out.push_str("    node [shape=box, fontname=monospace];\n");
+    out.push_str("    edge [fontname=monospace];\n");
+    let node = |out: &mut String, indent: &str, block: &BasicBlock| {
+        let unresolved = matches!(block.terminator().1, Instruction::JpV0(_));
+        let style = if unresolved { ", style=filled, fillcolor=\"#ffcccc\", color=red" } else { "" };
+        writeln!(out, "{}b{:03X} [label=\"{}\"{}];", indent, block.start, block_label(rom, block), style).unwrap();
+    };
+    for block in top {
+        node(&mut out, "    ", block);
+    }
+    for (entry, blocks) in &clusters {
+        writeln!(out, "    subgraph cluster_{:03X} {{", entry).unwrap();
+        writeln!(out, "        label=\"sub_{:03X}\";", entry).unwrap();
+        for block in blocks {
+            node(&mut out, "        ", block);
+        }
+        out.push_str("    }\n");
+    }
+
+    for edge in cfg.edges() {
+        writeln!(out, "    b{:03X} -> b{:03X} [{}];", edge.from, edge.to, edge_style(edge.kind)).unwrap();
+    }
+    for pc in cfg.indirect_jumps() {
+        let block = cfg.block_containing(pc).unwrap();
+        let base = rom.get_opcode(pc).unwrap() & 0x0FFF;
+        let v0 = constants.v(pc, 0);
+        writeln!(
+            out,
+            "    u{:03X} [label=\"0x{:03X} + V0\\nV0 = {}\", shape=octagon, color=red, fontcolor=red];",
+            pc, base, v0
+        )
+        .unwrap();
+        let style = "color=red, style=dashed, label=\"unresolved\"";
+        writeln!(out, "    b{:03X} -> u{:03X} [{}];", block.start, pc, style).unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::Trace;
+
+    /**
+     * program_dot draws a program with an edge of every kind: SE V0, 0;
+     *    CALL 0x20A; LD V0, 2; JP V0, 0x20C; JP 0x208; RET; 0x0000; JP 0x200,
+     *    with its BNNN traced going to 0x20E
+     */
+    fn program_dot() -> String {
+        let rom = Rom::from_bytes(vec![
+            0x30, 0x00, 0x22, 0x0A, 0x60, 0x02, 0xB2, 0x0C, 0x12, 0x08, 0x00, 0xEE, 0x00, 0x00, 0x12, 0x00,
+        ])
+        .unwrap();
+        let cfg = Cfg::build_traced(&rom, &Trace::parse("jump 0x206 0x20E\n").unwrap());
+        to_dot(&rom, &cfg)
+    }
+
+    #[test]
+    fn edges_are_drawn_in_the_style_of_their_kind() {
+        let dot = program_dot();
+        let edges = [
+            (0x200, 0x202, EdgeKind::FallThrough),
+            (0x200, 0x204, EdgeKind::Skip),
+            (0x202, 0x20A, EdgeKind::Call),
+            (0x204, 0x20E, EdgeKind::Indirect),
+            (0x20E, 0x200, EdgeKind::Jump),
+            (0x20A, 0x204, EdgeKind::Return),
+        ];
+        for (from, to, kind) in edges {
+            let line = format!("    b{:03X} -> b{:03X} [{}];\n", from, to, edge_style(kind));
+            assert!(dot.contains(&line), "no {:?} edge {}", kind, line);
+        }
+        let mut styles: Vec<&str> = edges.iter().map(|&(_, _, kind)| edge_style(kind)).collect();
+        styles.sort_unstable();
+        styles.dedup();
+        assert_eq!(styles.len(), edges.len());
+    }
+
+    #[test]
+    fn subroutines_are_clusters() {
+        let dot = program_dot();
+        let cluster = "    subgraph cluster_20A {\n        label=\"sub_20A\";\n        \
+                       b20A [label=\"0x20A  00EE  RET\\l\"];\n    }\n";
+        assert!(dot.contains(cluster), "{}", dot);
+        assert_eq!(dot.matches("subgraph").count(), 1);
+    }
+
+    #[test]
+    fn unresolved_jumps_are_red() {
+        let dot = program_dot();
+        let label = "0x204  6002  LD V0, 0x02\\l0x206  B20C  JP V0, 0x20C\\l";
+        let block = format!("    b204 [label=\"{}\", style=filled, fillcolor=\"#ffcccc\", color=red];\n", label);
+        assert!(dot.contains(&block), "{}", dot);
+        let node = "    u206 [label=\"0x20C + V0\\nV0 = 0x2\", shape=octagon, color=red, fontcolor=red];\n";
+        assert!(dot.contains(node), "{}", dot);
+        assert!(dot.contains("    b204 -> u206 [color=red, style=dashed, label=\"unresolved\"];\n"));
+    }
+}
@@ -0,0 +1,364 @@
+/**
+ * instruction.rs
+ *  this file decodes raw chip8 opcodes into an Instruction enum so the
+ *  rest of the retargeter never has to pick nibbles apart by hand.
+ *  The variants follow the mnemonics used in emulator/src/processor.rs.
+ *
+ *  SUPER-CHIP's opcodes decode to their own variants so that listings name
+ *  them. The recompilers only run CHIP-8, where 00CN to 00FF are SYS and
+ *  the rest are unknown, and treat them that way.
+ */
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    // 00E0 CLS
+    Cls,
+    // 00EE RET
+    Ret,
+    // 0nnn SYS addr
+    Sys(u16),
+    // 1nnn JP addr
+    Jp(u16),
+    // 2nnn CALL addr
+    Call(u16),
+    // 3xkk SE Vx, byte
+    SeByte(u8, u8),
+    // 4xkk SNE Vx, byte
+    SneByte(u8, u8),
+    // 5xy0 SE Vx, Vy
+    SeReg(u8, u8),
+    // 6xkk LD Vx, byte
+    LdByte(u8, u8),
+    // 7xkk ADD Vx, byte
+    AddByte(u8, u8),
+    // 8xy0 LD Vx, Vy
+    LdReg(u8, u8),
+    // 8xy1 OR Vx, Vy
+    Or(u8, u8),
+    // 8xy2 AND Vx, Vy
+    And(u8, u8),
+    // 8xy3 XOR Vx, Vy
+    Xor(u8, u8),
+    // 8xy4 ADD Vx, Vy
+    AddReg(u8, u8),
+    // 8xy5 SUB Vx, Vy
+    Sub(u8, u8),
+    // 8xy6 SHR Vx
+    Shr(u8, u8),
+    // 8xy7 SUBN Vx, Vy
+    Subn(u8, u8),
+    // 8xyE SHL Vx
+    Shl(u8, u8),
+    // 9xy0 SNE Vx, Vy
+    SneReg(u8, u8),
+    // Annn LD I, addr
+    LdI(u16),
+    // Bnnn JP V0, addr
+    JpV0(u16),
+    // Cxkk RND Vx, byte
+    Rnd(u8, u8),
+    // Dxyn DRW Vx, Vy, nibble
+    Drw(u8, u8, u8),
+    // Ex9E SKP Vx
+    Skp(u8),
+    // ExA1 SKNP Vx
+    Sknp(u8),
+    // Fx07 LD Vx, DT
+    LdVxDt(u8),
+    // Fx0A LD Vx, K
+    LdVxK(u8),
+    // Fx15 LD DT, Vx
+    LdDtVx(u8),
+    // Fx18 LD ST, Vx
+    LdStVx(u8),
+    // Fx1E ADD I, Vx
+    AddI(u8),
+    // Fx29 LD F, Vx
+    LdF(u8),
+    // Fx33 LD B, Vx
+    LdB(u8),
+    // Fx55 LD [I], Vx
+    Store(u8),
+    // Fx65 LD Vx, [I]
+    Load(u8),
+    // 00Cn SCD nibble, SUPER-CHIP
+    ScrollDown(u8),
+    // 00FB SCR, SUPER-CHIP
+    ScrollRight,
+    // 00FC SCL, SUPER-CHIP
+    ScrollLeft,
+    // 00FD EXIT, SUPER-CHIP
+    Exit,
+    // 00FE LOW, SUPER-CHIP
+    Low,
+    // 00FF HIGH, SUPER-CHIP
+    High,
+    // Fx30 LD HF, Vx, SUPER-CHIP
+    LdHf(u8),
+    // Fx75 LD R, Vx, SUPER-CHIP, for V0 to V7
+    StoreRpl(u8),
+    // Fx85 LD Vx, R, SUPER-CHIP, for V0 to V7
+    LoadRpl(u8),
+    // Anything the interpreter would fault on as an unknown opcode
+    Unknown(u16),
+}
+
+impl Instruction {
+    /**
+     * decode takes a raw two byte opcode and returns the matching Instruction.
+     *    SUPER-CHIP opcodes always decode to their own variants, though the
+     *    interpreter only runs them once enable_schip is called. Opcodes
+     *    neither CHIP-8 nor SUPER-CHIP define decode to Unknown.
+     */
+    pub fn decode(opcode: u16) -> Instruction {
+        let addr = opcode & 0x0FFF;
+        let nibble = (opcode & 0x000F) as u8;
+        let x = ((opcode & 0x0F00) >> 8) as u8;
+        let y = ((opcode & 0x00F0) >> 4) as u8;
+        let byte = (opcode & 0x00FF) as u8;
+
+        match opcode {
+            0x00E0 => Instruction::Cls,
+            0x00EE => Instruction::Ret,
+            0x00C0..=0x00CF => Instruction::ScrollDown(nibble),
+            0x00FB => Instruction::ScrollRight,
+            0x00FC => Instruction::ScrollLeft,
+            0x00FD => Instruction::Exit,
+            0x00FE => Instruction::Low,
+            0x00FF => Instruction::High,
+            _ => match opcode & 0xF000 {
+                0x0000 => Instruction::Sys(addr),
+                0x1000 => Instruction::Jp(addr),
+                0x2000 => Instruction::Call(addr),
+                0x3000 => Instruction::SeByte(x, byte),
+                0x4000 => Instruction::SneByte(x, byte),
+                // The interpreter ignores the low nibble of 5xy0 and 9xy0
+                0x5000 => Instruction::SeReg(x, y),
+                0x6000 => Instruction::LdByte(x, byte),
+                0x7000 => Instruction::AddByte(x, byte),
+                0x8000 => match nibble {
+                    0x0 => Instruction::LdReg(x, y),
+                    0x1 => Instruction::Or(x, y),
+                    0x2 => Instruction::And(x, y),
+                    0x3 => Instruction::Xor(x, y),
+                    0x4 => Instruction::AddReg(x, y),
+                    0x5 => Instruction::Sub(x, y),
+                    0x6 => Instruction::Shr(x, y),
+                    0x7 => Instruction::Subn(x, y),
+                    0xE => Instruction::Shl(x, y),
+                    _ => Instruction::Unknown(opcode),
+                },
+                0x9000 => Instruction::SneReg(x, y),
+                0xA000 => Instruction::LdI(addr),
+                0xB000 => Instruction::JpV0(addr),
+                0xC000 => Instruction::Rnd(x, byte),
+                0xD000 => Instruction::Drw(x, y, nibble),
+                0xE000 => match byte {
+                    0x9E => Instruction::Skp(x),
+                    0xA1 => Instruction::Sknp(x),
+                    _ => Instruction::Unknown(opcode),
+                },
+                _ => match byte {
+                    0x07 => Instruction::LdVxDt(x),
+                    0x0A => Instruction::LdVxK(x),
+                    0x15 => Instruction::LdDtVx(x),
+                    0x18 => Instruction::LdStVx(x),
+                    0x1E => Instruction::AddI(x),
+                    0x29 => Instruction::LdF(x),
+                    0x33 => Instruction::LdB(x),
+                    0x55 => Instruction::Store(x),
+                    0x65 => Instruction::Load(x),
+                    0x30 => Instruction::LdHf(x),
+                    0x75 if x < 8 => Instruction::StoreRpl(x),
+                    0x85 if x < 8 => Instruction::LoadRpl(x),
+                    _ => Instruction::Unknown(opcode),
+                },
+            },
+        }
+    }
+
+    /**
+     * encode turns an Instruction back into its two byte opcode. For
+     *    opcodes with bits the interpreter ignores, like the low nibble of
+     *    5xy0, those bits come back as zero.
+     */
+    pub fn encode(&self) -> u16 {
+        let xy = |x: u8, y: u8| (x as u16) << 8 | (y as u16) << 4;
+        let xkk = |x: u8, byte: u8| (x as u16) << 8 | byte as u16;
+        match *self {
+            Instruction::Cls => 0x00E0,
+            Instruction::Ret => 0x00EE,
+            Instruction::Sys(addr) => addr & 0x0FFF,
+            Instruction::Jp(addr) => 0x1000 | (addr & 0x0FFF),
+            Instruction::Call(addr) => 0x2000 | (addr & 0x0FFF),
+            Instruction::SeByte(x, byte) => 0x3000 | xkk(x, byte),
+            Instruction::SneByte(x, byte) => 0x4000 | xkk(x, byte),
+            Instruction::SeReg(x, y) => 0x5000 | xy(x, y),
+            Instruction::LdByte(x, byte) => 0x6000 | xkk(x, byte),
+            Instruction::AddByte(x, byte) => 0x7000 | xkk(x, byte),
+            Instruction::LdReg(x, y) => 0x8000 | xy(x, y),
+            Instruction::Or(x, y) => 0x8001 | xy(x, y),
+            Instruction::And(x, y) => 0x8002 | xy(x, y),
+            Instruction::Xor(x, y) => 0x8003 | xy(x, y),
+            Instruction::AddReg(x, y) => 0x8004 | xy(x, y),
+            Instruction::Sub(x, y) => 0x8005 | xy(x, y),
+            Instruction::Shr(x, y) => 0x8006 | xy(x, y),
+            Instruction::Subn(x, y) => 0x8007 | xy(x, y),
+            Instruction::Shl(x, y) => 0x800E | xy(x, y),
+            Instruction::SneReg(x, y) => 0x9000 | xy(x, y),
+            Instruction::LdI(addr) => 0xA000 | (addr & 0x0FFF),
+            Instruction::JpV0(addr) => 0xB000 | (addr & 0x0FFF),
+            Instruction::Rnd(x, byte) => 0xC000 | xkk(x, byte),
+            Instruction::Drw(x, y, n) => 0xD000 | xy(x, y) | (n & 0xF) as u16,
+            Instruction::Skp(x) => 0xE09E | xkk(x, 0),
+            Instruction::Sknp(x) => 0xE0A1 | xkk(x, 0),
+            Instruction::LdVxDt(x) => 0xF007 | xkk(x, 0),
+            Instruction::LdVxK(x) => 0xF00A | xkk(x, 0),
+            Instruction::LdDtVx(x) => 0xF015 | xkk(x, 0),
+            Instruction::LdStVx(x) => 0xF018 | xkk(x, 0),
+            Instruction::AddI(x) => 0xF01E | xkk(x, 0),
+            Instruction::LdF(x) => 0xF029 | xkk(x, 0),
+            Instruction::LdB(x) => 0xF033 | xkk(x, 0),
+            Instruction::Store(x) => 0xF055 | xkk(x, 0),
+            Instruction::Load(x) => 0xF065 | xkk(x, 0),
+            Instruction::ScrollDown(n) => 0x00C0 | (n & 0xF) as u16,
+            Instruction::ScrollRight => 0x00FB,
+            Instruction::ScrollLeft => 0x00FC,
+            Instruction::Exit => 0x00FD,
+            Instruction::Low => 0x00FE,
+            Instruction::High => 0x00FF,
+            Instruction::LdHf(x) => 0xF030 | xkk(x, 0),
+            Instruction::StoreRpl(x) => 0xF075 | xkk(x, 0),
+            Instruction::LoadRpl(x) => 0xF085 | xkk(x, 0),
+            Instruction::Unknown(opcode) => opcode,
+        }
+    }
+
+    /**
+     * is_schip returns true for the SUPER-CHIP instructions, which CHIP-8
+     *    ignores as SYS if they start with 00 and faults on otherwise
+     */
+    pub fn is_schip(&self) -> bool {
+        matches!(
+            self,
+            Instruction::ScrollDown(_)
+                | Instruction::ScrollRight
+                | Instruction::ScrollLeft
+                | Instruction::Exit
+                | Instruction::Low
+                | Instruction::High
+                | Instruction::LdHf(_)
+                | Instruction::StoreRpl(_)
+                | Instruction::LoadRpl(_)
+        )
+    }
+
+    /**
+     * is_skip returns true for the instructions that conditionally add 4 to pc
+     */
+    pub fn is_skip(&self) -> bool {
+        matches!(
+            self,
+            Instruction::SeByte(..)
+                | Instruction::SneByte(..)
+                | Instruction::SeReg(..)
+                | Instruction::SneReg(..)
+                | Instruction::Skp(_)
+                | Instruction::Sknp(_)
+        )
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Instruction::Cls => write!(f, "CLS"),
+            Instruction::Ret => write!(f, "RET"),
+            Instruction::Sys(addr) => write!(f, "SYS 0x{:03X}", addr),
+            Instruction::Jp(addr) => write!(f, "JP 0x{:03X}", addr),
+            Instruction::Call(addr) => write!(f, "CALL 0x{:03X}", addr),
+            Instruction::SeByte(x, byte) => write!(f, "SE V{:X}, 0x{:02X}", x, byte),
+            Instruction::SneByte(x, byte) => write!(f, "SNE V{:X}, 0x{:02X}", x, byte),
+            Instruction::SeReg(x, y) => write!(f, "SE V{:X}, V{:X}", x, y),
+            Instruction::LdByte(x, byte) => write!(f, "LD V{:X}, 0x{:02X}", x, byte),
+            Instruction::AddByte(x, byte) => write!(f, "ADD V{:X}, 0x{:02X}", x, byte),
+            Instruction::LdReg(x, y) => write!(f, "LD V{:X}, V{:X}", x, y),
+            Instruction::Or(x, y) => write!(f, "OR V{:X}, V{:X}", x, y),
+            Instruction::And(x, y) => write!(f, "AND V{:X}, V{:X}", x, y),
+            Instruction::Xor(x, y) => write!(f, "XOR V{:X}, V{:X}", x, y),
+            Instruction::AddReg(x, y) => write!(f, "ADD V{:X}, V{:X}", x, y),
+            Instruction::Sub(x, y) => write!(f, "SUB V{:X}, V{:X}", x, y),
+            // The interpreter ignores Vy in SHR and SHL, but keep it if it was set
+            Instruction::Shr(x, 0) => write!(f, "SHR V{:X}", x),
+            Instruction::Shr(x, y) => write!(f, "SHR V{:X}, V{:X}", x, y),
+            Instruction::Subn(x, y) => write!(f, "SUBN V{:X}, V{:X}", x, y),
+            Instruction::Shl(x, 0) => write!(f, "SHL V{:X}", x),
+            Instruction::Shl(x, y) => write!(f, "SHL V{:X}, V{:X}", x, y),
+            Instruction::SneReg(x, y) => write!(f, "SNE V{:X}, V{:X}", x, y),
+            Instruction::LdI(addr) => write!(f, "LD I, 0x{:03X}", addr),
+            Instruction::JpV0(addr) => write!(f, "JP V0, 0x{:03X}", addr),
+            Instruction::Rnd(x, byte) => write!(f, "RND V{:X}, 0x{:02X}", x, byte),
+            Instruction::Drw(x, y, n) => write!(f, "DRW V{:X}, V{:X}, {}", x, y, n),
+            Instruction::Skp(x) => write!(f, "SKP V{:X}", x),
+            Instruction::Sknp(x) => write!(f, "SKNP V{:X}", x),
+            Instruction::LdVxDt(x) => write!(f, "LD V{:X}, DT", x),
+            Instruction::LdVxK(x) => write!(f, "LD V{:X}, K", x),
+            Instruction::LdDtVx(x) => write!(f, "LD DT, V{:X}", x),
+            Instruction::LdStVx(x) => write!(f, "LD ST, V{:X}", x),
+            Instruction::AddI(x) => write!(f, "ADD I, V{:X}", x),
+            Instruction::LdF(x) => write!(f, "LD F, V{:X}", x),
+            Instruction::LdB(x) => write!(f, "LD B, V{:X}", x),
+            Instruction::Store(x) => write!(f, "LD [I], V{:X}", x),
+            Instruction::Load(x) => write!(f, "LD V{:X}, [I]", x),
+            Instruction::ScrollDown(n) => write!(f, "SCD {}", n),
+            Instruction::ScrollRight => write!(f, "SCR"),
+            Instruction::ScrollLeft => write!(f, "SCL"),
+            Instruction::Exit => write!(f, "EXIT"),
+            Instruction::Low => write!(f, "LOW"),
+            Instruction::High => write!(f, "HIGH"),
+            Instruction::LdHf(x) => write!(f, "LD HF, V{:X}", x),
+            Instruction::StoreRpl(x) => write!(f, "LD R, V{:X}", x),
+            Instruction::LoadRpl(x) => write!(f, "LD V{:X}, R", x),
+            Instruction::Unknown(opcode) => write!(f, "0x{:04X}", opcode),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schip_opcodes_are_named() {
+        let named = [
+            (0x00C4, "SCD 4"),
+            (0x00FB, "SCR"),
+            (0x00FC, "SCL"),
+            (0x00FD, "EXIT"),
+            (0x00FE, "LOW"),
+            (0x00FF, "HIGH"),
+            (0xF330, "LD HF, V3"),
+            (0xF775, "LD R, V7"),
+            (0xF785, "LD V7, R"),
+        ];
+        for (opcode, text) in named {
+            let instruction = Instruction::decode(opcode);
+            assert!(instruction.is_schip(), "{:04X}", opcode);
+            assert_eq!(instruction.to_string(), text);
+            assert_eq!(instruction.encode(), opcode);
+        }
+    }
+
+    #[test]
+    fn rpl_flags_stop_at_v7() {
+        assert_eq!(Instruction::decode(0xF875), Instruction::Unknown(0xF875));
+        assert_eq!(Instruction::decode(0xF885), Instruction::Unknown(0xF885));
+    }
+
+    #[test]
+    fn other_low_opcodes_are_still_sys() {
+        assert_eq!(Instruction::decode(0x00FA), Instruction::Sys(0x0FA));
+        assert_eq!(Instruction::decode(0x00B0), Instruction::Sys(0x0B0));
+    }
+}
@@ -0,0 +1,828 @@
+/**
+ * ir.rs
+ *  a small builder for textual LLVM IR that needs no LLVM install. It
+ *  covers what the lifter emits: integers, arrays, named structs, typed
+ *  pointers, calls, branches and debug info metadata, and prints modules
+ *  the way LLVM 14 reads them. With the llvm feature, codegen.rs parses
+ *  the text back into LLVM to optimize it and generate code.
+ *
+ *  Values are named the way LLVM names them, so the same program always
+ *  prints the same text.
+ */
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    Void,
+    Int(u32),
+    Array(u64, Box<Type>),
+    // A named struct and its fields
+    Struct(String, Vec<Type>),
+    Pointer(Box<Type>),
+    // Return type and parameter types
+    Function(Box<Type>, Vec<Type>),
+    // Operands of debug info intrinsics
+    Metadata,
+}
+
+impl Type {
+    pub fn ptr(&self) -> Type {
+        Type::Pointer(Box::new(self.clone()))
+    }
+
+    pub fn array(&self, length: u64) -> Type {
+        Type::Array(length, Box::new(self.clone()))
+    }
+
+    /**
+     * function returns the type of a function returning this type
+     */
+    pub fn function(&self, params: &[Type]) -> Type {
+        Type::Function(Box::new(self.clone()), params.to_vec())
+    }
+
+    /**
+     * pointee returns the type a pointer points to
+     */
+    fn pointee(&self) -> &Type {
+        match self {
+            Type::Pointer(pointee) => pointee,
+            _ => panic!("{} is not a pointer", self),
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Void => write!(f, "void"),
+            Type::Int(bits) => write!(f, "i{}", bits),
+            Type::Array(length, element) => write!(f, "[{} x {}]", length, element),
+            Type::Struct(name, _) => write!(f, "%{}", name),
+            Type::Pointer(pointee) => write!(f, "{}*", pointee),
+            Type::Function(ret, params) => {
+                write!(f, "{} (", ret)?;
+                write_list(f, params)?;
+                write!(f, ")")
+            }
+            Type::Metadata => write!(f, "metadata"),
+        }
+    }
+}
+
+/**
+ * write_list writes items separated by commas
+ */
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter, items: &[T]) -> fmt::Result {
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+/**
+ * quote writes bytes as an LLVM string literal, escaping anything that is
+ *    not printable
+ */
+pub fn quote(bytes: &[u8]) -> String {
+    let mut text = String::from("\"");
+    for &byte in bytes {
+        if (byte.is_ascii_graphic() && byte != b'"' && byte != b'\\') || byte == b' ' {
+            text.push(byte as char);
+        } else {
+            text.push_str(&format!("\\{:02X}", byte));
+        }
+    }
+    text.push('"');
+    text
+}
+
+/**
+ * A constant, a global or the result of an instruction, along with its type
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Value {
+    ty: Type,
+    // How an operand refers to it, without the type
+    text: String,
+}
+
+impl Value {
+    /**
+     * int returns an integer constant of the given width. LLVM prints
+     *    integers as signed, so this does too.
+     */
+    pub fn int(bits: u32, value: u64) -> Value {
+        let text = if bits == 1 {
+            (if value & 1 == 1 { "true" } else { "false" }).to_string()
+        } else if bits < 64 {
+            let value = value & ((1 << bits) - 1);
+            let signed = if value >> (bits - 1) == 1 { value as i64 - (1 << bits) } else { value as i64 };
+            signed.to_string()
+        } else {
+            (value as i64).to_string()
+        };
+        Value { ty: Type::Int(bits), text }
+    }
+
+    pub fn bool(value: bool) -> Value {
+        Value::int(1, value as u64)
+    }
+
+    /**
+     * bytes returns a constant byte array
+     */
+    pub fn bytes(bytes: &[u8]) -> Value {
+        Value { ty: Type::Int(8).array(bytes.len() as u64), text: format!("c{}", quote(bytes)) }
+    }
+
+    /**
+     * array returns a constant array of constants of one type
+     */
+    pub fn array(element: &Type, values: &[Value]) -> Value {
+        let items: Vec<String> = values.iter().map(|value| value.typed()).collect();
+        Value { ty: element.array(values.len() as u64), text: format!("[{}]", items.join(", ")) }
+    }
+
+    /**
+     * metadata returns inline metadata, such as a DIExpression, as an operand
+     */
+    pub fn metadata(text: &str) -> Value {
+        Value { ty: Type::Metadata, text: text.to_string() }
+    }
+
+    /**
+     * as_metadata wraps the value up as a metadata operand
+     */
+    pub fn as_metadata(&self) -> Value {
+        Value { ty: Type::Metadata, text: self.typed() }
+    }
+
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    /**
+     * typed returns the value as an operand with its type in front
+     */
+    fn typed(&self) -> String {
+        format!("{} {}", self.ty, self.text)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/**
+ * A numbered metadata node in the module that created it
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Metadata(usize);
+
+impl Metadata {
+    /**
+     * value returns the node as an operand
+     */
+    pub fn value(&self) -> Value {
+        Value { ty: Type::Metadata, text: self.to_string() }
+    }
+}
+
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "!{}", self.0)
+    }
+}
+
+/**
+ * Integer comparisons, by their LLVM names
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntPredicate {
+    EQ,
+    NE,
+    UGT,
+    UGE,
+    ULT,
+    ULE,
+}
+
+impl fmt::Display for IntPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            IntPredicate::EQ => "eq",
+            IntPredicate::NE => "ne",
+            IntPredicate::UGT => "ugt",
+            IntPredicate::UGE => "uge",
+            IntPredicate::ULT => "ult",
+            IntPredicate::ULE => "ule",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/**
+ * A basic block in the function that created it
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BlockId(usize);
+
+struct Block {
+    label: String,
+    instructions: Vec<String>,
+    terminated: bool,
+}
+
+/**
+ * A function definition, built one instruction at a time at the end of
+ * the block it is positioned at
+ */
+pub struct Function {
+    name: String,
+    ty: Type,
+    params: Vec<Value>,
+    blocks: Vec<Block>,
+    // Every value and block name used so far
+    names: HashSet<String>,
+    current: Option<BlockId>,
+    // DISubprogram describing the function, if it has debug info
+    subprogram: Option<Metadata>,
+    // DILocation attached to every instruction emitted from now on
+    location: Option<Metadata>,
+    // Only visible inside the module
+    internal: bool,
+}
+
+impl Function {
+    pub fn new(name: &str, ret: Type, params: &[(Type, &str)]) -> Function {
+        let types: Vec<Type> = params.iter().map(|(ty, _)| ty.clone()).collect();
+        let mut function = Function {
+            name: name.to_string(),
+            ty: ret.function(&types),
+            params: Vec::new(),
+            blocks: Vec::new(),
+            names: HashSet::new(),
+            current: None,
+            subprogram: None,
+            location: None,
+            internal: false,
+        };
+        for (ty, name) in params {
+            let name = function.unique_name(name);
+            function.params.push(Value { ty: ty.clone(), text: format!("%{}", name) });
+        }
+        function
+    }
+
+    /**
+     * value returns a pointer to the function, for calls and function pointers
+     */
+    pub fn value(&self) -> Value {
+        Value { ty: self.ty.ptr(), text: format!("@{}", self.name) }
+    }
+
+    pub fn param(&self, index: usize) -> Value {
+        self.params[index].clone()
+    }
+
+    pub fn append_block(&mut self, name: &str) -> BlockId {
+        let label = self.unique_name(name);
+        self.blocks.push(Block { label, instructions: Vec::new(), terminated: false });
+        BlockId(self.blocks.len() - 1)
+    }
+
+    pub fn position_at_end(&mut self, block: BlockId) {
+        self.current = Some(block);
+    }
+
+    /**
+     * set_internal hides the function from other modules, which leaves
+     *    LLVM free to inline it everywhere and drop it
+     */
+    pub fn set_internal(&mut self) {
+        self.internal = true;
+    }
+
+    pub fn set_subprogram(&mut self, subprogram: Metadata) {
+        self.subprogram = Some(subprogram);
+    }
+
+    /**
+     * set_location picks the DILocation for the instructions that follow.
+     *    Once a function has a subprogram every call in it needs one.
+     */
+    pub fn set_location(&mut self, location: Option<Metadata>) {
+        self.location = location;
+    }
+
+    /**
+     * unique_name returns name, with a number after it if it is taken.
+     *    LLVM keeps purely numeric names for itself, so unnamed values
+     *    are called tmp.
+     */
+    fn unique_name(&mut self, name: &str) -> String {
+        let name = if name.is_empty() { "tmp" } else { name };
+        let mut unique = name.to_string();
+        let mut suffix = 0;
+        while self.names.contains(&unique) {
+            suffix += 1;
+            unique = format!("{}{}", name, suffix);
+        }
+        self.names.insert(unique.clone());
+        unique
+    }
+
+    fn label(&self, block: BlockId) -> String {
+        format!("label %{}", self.blocks[block.0].label)
+    }
+
+    /**
+     * emit adds an instruction to the current block
+     */
+    fn emit(&mut self, mut instruction: String, terminator: bool) {
+        if let Some(location) = self.location {
+            instruction.push_str(&format!(", !dbg {}", location));
+        }
+        let block = &mut self.blocks[self.current.expect("builder is not positioned").0];
+        assert!(!block.terminated, "block {} is already terminated", block.label);
+        block.instructions.push(instruction);
+        block.terminated = terminator;
+    }
+
+    /**
+     * define adds an instruction that produces a value
+     */
+    fn define(&mut self, ty: Type, name: &str, instruction: String) -> Value {
+        let name = self.unique_name(name);
+        self.emit(format!("%{} = {}", name, instruction), false);
+        Value { ty, text: format!("%{}", name) }
+    }
+
+    /**
+     * struct_gep returns a pointer to field index of the struct ptr points to
+     */
+    pub fn struct_gep(&mut self, ptr: &Value, index: u32, name: &str) -> Value {
+        let pointee = ptr.ty.pointee();
+        let field = match pointee {
+            Type::Struct(_, fields) => fields[index as usize].clone(),
+            _ => panic!("{} is not a struct", pointee),
+        };
+        let instruction = format!("getelementptr inbounds {}, {}, i32 0, i32 {}", pointee, ptr.typed(), index);
+        self.define(field.ptr(), name, instruction)
+    }
+
+    /**
+     * array_gep returns a pointer to element index of the array ptr points to
+     */
+    pub fn array_gep(&mut self, ptr: &Value, index: &Value, name: &str) -> Value {
+        let pointee = ptr.ty.pointee();
+        let element = match pointee {
+            Type::Array(_, element) => element.as_ref().clone(),
+            _ => panic!("{} is not an array", pointee),
+        };
+        let instruction =
+            format!("getelementptr inbounds {}, {}, i32 0, {}", pointee, ptr.typed(), index.typed());
+        self.define(element.ptr(), name, instruction)
+    }
+
+    pub fn load(&mut self, ptr: &Value, name: &str) -> Value {
+        let ty = ptr.ty.pointee().clone();
+        let instruction = format!("load {}, {}", ty, ptr.typed());
+        self.define(ty, name, instruction)
+    }
+
+    pub fn store(&mut self, ptr: &Value, value: &Value) {
+        self.emit(format!("store {}, {}", value.typed(), ptr.typed()), false);
+    }
+
+    fn binary(&mut self, op: &str, a: &Value, b: &Value, name: &str) -> Value {
+        self.define(a.ty.clone(), name, format!("{} {}, {}", op, a.typed(), b))
+    }
+
+    pub fn add(&mut self, a: &Value, b: &Value, name: &str) -> Value {
+        self.binary("add", a, b, name)
+    }
+
+    pub fn sub(&mut self, a: &Value, b: &Value, name: &str) -> Value {
+        self.binary("sub", a, b, name)
+    }
+
+    pub fn mul(&mut self, a: &Value, b: &Value, name: &str) -> Value {
+        self.binary("mul", a, b, name)
+    }
+
+    pub fn udiv(&mut self, a: &Value, b: &Value, name: &str) -> Value {
+        self.binary("udiv", a, b, name)
+    }
+
+    pub fn urem(&mut self, a: &Value, b: &Value, name: &str) -> Value {
+        self.binary("urem", a, b, name)
+    }
+
+    pub fn and(&mut self, a: &Value, b: &Value, name: &str) -> Value {
+        self.binary("and", a, b, name)
+    }
+
+    pub fn or(&mut self, a: &Value, b: &Value, name: &str) -> Value {
+        self.binary("or", a, b, name)
+    }
+
+    pub fn xor(&mut self, a: &Value, b: &Value, name: &str) -> Value {
+        self.binary("xor", a, b, name)
+    }
+
+    pub fn shl(&mut self, a: &Value, b: &Value, name: &str) -> Value {
+        self.binary("shl", a, b, name)
+    }
+
+    pub fn lshr(&mut self, a: &Value, b: &Value, name: &str) -> Value {
+        self.binary("lshr", a, b, name)
+    }
+
+    pub fn not(&mut self, value: &Value, name: &str) -> Value {
+        let ones = Value::int(bits(&value.ty), u64::MAX);
+        self.binary("xor", value, &ones, name)
+    }
+
+    pub fn icmp(&mut self, predicate: IntPredicate, a: &Value, b: &Value, name: &str) -> Value {
+        self.define(Type::Int(1), name, format!("icmp {} {}, {}", predicate, a.typed(), b))
+    }
+
+    fn cast(&mut self, op: &str, value: &Value, ty: &Type, name: &str) -> Value {
+        self.define(ty.clone(), name, format!("{} {} to {}", op, value.typed(), ty))
+    }
+
+    pub fn zext(&mut self, value: &Value, ty: &Type, name: &str) -> Value {
+        self.cast("zext", value, ty, name)
+    }
+
+    pub fn trunc(&mut self, value: &Value, ty: &Type, name: &str) -> Value {
+        self.cast("trunc", value, ty, name)
+    }
+
+    pub fn bitcast(&mut self, value: &Value, ty: &Type, name: &str) -> Value {
+        self.cast("bitcast", value, ty, name)
+    }
+
+    pub fn select(&mut self, cond: &Value, a: &Value, b: &Value, name: &str) -> Value {
+        let instruction = format!("select {}, {}, {}", cond.typed(), a.typed(), b.typed());
+        self.define(a.ty.clone(), name, instruction)
+    }
+
+    /**
+     * call calls a function pointer and returns its result, or None for a
+     *    void function
+     */
+    pub fn call(&mut self, function: &Value, args: &[Value], name: &str) -> Option<Value> {
+        let ret = match function.ty.pointee() {
+            Type::Function(ret, _) => ret.as_ref().clone(),
+            other => panic!("{} is not a function", other),
+        };
+        let args: Vec<String> = args.iter().map(|arg| arg.typed()).collect();
+        let instruction = format!("call {} {}({})", ret, function, args.join(", "));
+        if ret == Type::Void {
+            self.emit(instruction, false);
+            return None;
+        }
+        Some(self.define(ret, name, instruction))
+    }
+
+    pub fn br(&mut self, target: BlockId) {
+        let instruction = format!("br {}", self.label(target));
+        self.emit(instruction, true);
+    }
+
+    pub fn cond_br(&mut self, cond: &Value, then: BlockId, otherwise: BlockId) {
+        let instruction = format!("br {}, {}, {}", cond.typed(), self.label(then), self.label(otherwise));
+        self.emit(instruction, true);
+    }
+
+    pub fn switch(&mut self, value: &Value, default: BlockId, cases: &[(Value, BlockId)]) {
+        let mut instruction = format!("switch {}, {} [", value.typed(), self.label(default));
+        for (case, block) in cases {
+            instruction.push_str(&format!("\n    {}, {}", case.typed(), self.label(*block)));
+        }
+        instruction.push_str("\n  ]");
+        self.emit(instruction, true);
+    }
+
+    pub fn ret(&mut self, value: Option<&Value>) {
+        let instruction = match value {
+            Some(value) => format!("ret {}", value.typed()),
+            None => "ret void".to_string(),
+        };
+        self.emit(instruction, true);
+    }
+}
+
+fn bits(ty: &Type) -> u32 {
+    match ty {
+        Type::Int(bits) => *bits,
+        _ => panic!("{} is not an integer", ty),
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ret = match &self.ty {
+            Type::Function(ret, _) => ret,
+            _ => unreachable!(),
+        };
+        let params: Vec<String> = self.params.iter().map(|param| param.typed()).collect();
+        let linkage = if self.internal { "internal " } else { "" };
+        write!(f, "define {}{} @{}({})", linkage, ret, self.name, params.join(", "))?;
+        if let Some(subprogram) = self.subprogram {
+            write!(f, " !dbg {}", subprogram)?;
+        }
+        writeln!(f, " {{")?;
+        for (index, block) in self.blocks.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "{}:", block.label)?;
+            for instruction in &block.instructions {
+                writeln!(f, "  {}", instruction)?;
+            }
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/**
+ * A whole module: struct types, private constants, function definitions,
+ * declarations of functions defined elsewhere and metadata
+ */
+pub struct Module {
+    name: String,
+    structs: Vec<Type>,
+    constants: Vec<(String, Value)>,
+    functions: Vec<Function>,
+    declarations: Vec<(String, Type)>,
+    // Text of every numbered metadata node, in order
+    metadata: Vec<String>,
+    // Named metadata and the nodes it lists
+    named_metadata: Vec<(String, Vec<Metadata>)>,
+}
+
+impl Module {
+    pub fn new(name: &str) -> Module {
+        Module {
+            name: name.to_string(),
+            structs: Vec::new(),
+            constants: Vec::new(),
+            functions: Vec::new(),
+            declarations: Vec::new(),
+            metadata: Vec::new(),
+            named_metadata: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /**
+     * add_struct defines a named struct type
+     */
+    pub fn add_struct(&mut self, name: &str, fields: &[Type]) -> Type {
+        let ty = Type::Struct(name.to_string(), fields.to_vec());
+        self.structs.push(ty.clone());
+        ty
+    }
+
+    /**
+     * declare adds a function defined outside the module and returns a
+     *    pointer to it
+     */
+    pub fn declare(&mut self, name: &str, ret: Type, params: &[Type]) -> Value {
+        let ty = ret.function(params);
+        self.declarations.push((name.to_string(), ty.clone()));
+        Value { ty: ty.ptr(), text: format!("@{}", name) }
+    }
+
+    /**
+     * add_constant adds a private constant global and returns a pointer to it
+     */
+    pub fn add_constant(&mut self, name: &str, value: Value) -> Value {
+        let ptr = Value { ty: value.ty.ptr(), text: format!("@{}", name) };
+        self.constants.push((name.to_string(), value));
+        ptr
+    }
+
+    /**
+     * add_metadata adds a numbered metadata node, such as a DILocation,
+     *    written out as LLVM prints it
+     */
+    pub fn add_metadata(&mut self, text: &str) -> Metadata {
+        self.metadata.push(text.to_string());
+        Metadata(self.metadata.len() - 1)
+    }
+
+    /**
+     * add_named_metadata adds named metadata listing nodes, like
+     *    llvm.module.flags
+     */
+    pub fn add_named_metadata(&mut self, name: &str, nodes: &[Metadata]) {
+        self.named_metadata.push((name.to_string(), nodes.to_vec()));
+    }
+
+    pub fn add_function(&mut self, function: Function) {
+        self.functions.push(function);
+    }
+
+    /**
+     * declarations lists the name of every function the module calls but
+     *    does not define
+     */
+    pub fn declarations(&self) -> impl Iterator<Item = &str> {
+        self.declarations.iter().map(|(name, _)| name.as_str())
+    }
+
+    /**
+     * verify checks every block of every function ends in a terminator
+     */
+    pub fn verify(&self) -> Result<(), String> {
+        for function in &self.functions {
+            if let Some(block) = function.blocks.iter().find(|block| !block.terminated) {
+                return Err(format!("Block {} in {} has no terminator", block.label, function.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "; ModuleID = '{}'", self.name)?;
+        writeln!(f, "source_filename = \"{}\"", self.name)?;
+        for ty in &self.structs {
+            if let Type::Struct(name, fields) = ty {
+                write!(f, "\n%{} = type {{ ", name)?;
+                write_list(f, fields)?;
+                writeln!(f, " }}")?;
+            }
+        }
+        if !self.constants.is_empty() {
+            writeln!(f)?;
+        }
+        for (name, value) in &self.constants {
+            writeln!(f, "@{} = private constant {}", name, value.typed())?;
+        }
+        for function in &self.functions {
+            writeln!(f)?;
+            write!(f, "{}", function)?;
+        }
+        if !self.declarations.is_empty() {
+            writeln!(f)?;
+        }
+        for (name, ty) in &self.declarations {
+            if let Type::Function(ret, params) = ty {
+                write!(f, "declare {} @{}(", ret, name)?;
+                write_list(f, params)?;
+                writeln!(f, ")")?;
+            }
+        }
+        if !self.named_metadata.is_empty() {
+            writeln!(f)?;
+        }
+        for (name, nodes) in &self.named_metadata {
+            write!(f, "!{} = !{{", name)?;
+            write_list(f, nodes)?;
+            writeln!(f, "}}")?;
+        }
+        if !self.metadata.is_empty() {
+            writeln!(f)?;
+        }
+        for (index, text) in self.metadata.iter().enumerate() {
+            writeln!(f, "!{} = {}", index, text)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::slice;
+
+    #[test]
+    fn integers_print_signed() {
+        assert_eq!(Value::int(8, 0xFF).to_string(), "-1");
+        assert_eq!(Value::int(8, 0x17F).to_string(), "127");
+        assert_eq!(Value::int(16, 0x200).to_string(), "512");
+        assert_eq!(Value::int(64, u64::MAX).to_string(), "-1");
+        assert_eq!(Value::bool(true).to_string(), "true");
+        assert_eq!(Value::bool(false).to_string(), "false");
+    }
+
+    #[test]
+    fn quote_escapes_unprintable_bytes() {
+        assert_eq!(quote(b"a b"), "\"a b\"");
+        assert_eq!(quote(b"\"\\\n\x00"), "\"\\22\\5C\\0A\\00\"");
+        assert_eq!(Value::bytes(&[0x12, 0x00]).to_string(), "c\"\\12\\00\"");
+    }
+
+    #[test]
+    fn types_print_as_llvm_reads_them() {
+        let i8_type = Type::Int(8);
+        assert_eq!(i8_type.array(16).ptr().to_string(), "[16 x i8]*");
+        assert_eq!(Type::Void.function(&[i8_type.clone(), Type::Int(16)]).to_string(), "void (i8, i16)");
+        let state = Type::Struct("chip8.state".to_string(), vec![i8_type]);
+        assert_eq!(state.ptr().to_string(), "%chip8.state*");
+    }
+
+    #[test]
+    fn names_are_made_unique() {
+        let mut function = Function::new("f", Type::Int(8), &[(Type::Int(8), "v")]);
+        let entry = function.append_block("entry");
+        function.position_at_end(entry);
+        let v = function.param(0);
+        let sum = function.add(&v, &v, "v");
+        let unnamed = function.add(&sum, &Value::int(8, 1), "");
+        function.ret(Some(&unnamed));
+        assert_eq!(
+            function.to_string(),
+            "define i8 @f(i8 %v) {\nentry:\n  %v1 = add i8 %v, %v\n  %tmp = add i8 %v1, 1\n  ret i8 %tmp\n}\n"
+        );
+    }
+
+    #[test]
+    fn module_prints_every_part_in_order() {
+        let mut module = Module::new("test.ch8");
+        let state = module.add_struct("state", &[Type::Int(8).array(2)]);
+        let rom = module.add_constant("rom", Value::bytes(&[1, 2]));
+        let tick = module.declare("tick", Type::Int(1), &[state.ptr()]);
+        let mut function = Function::new("run", Type::Void, &[(state.ptr(), "state")]);
+        let entry = function.append_block("entry");
+        let done = function.append_block("done");
+        function.position_at_end(entry);
+        let state_ptr = function.param(0);
+        let stop = function.call(&tick, slice::from_ref(&state_ptr), "stop").unwrap();
+        function.cond_br(&stop, done, entry);
+        function.position_at_end(done);
+        let registers = function.struct_gep(&state_ptr, 0, "registers");
+        let first = function.array_gep(&registers, &Value::int(8, 0), "first");
+        let byte = function.array_gep(&rom, &Value::int(8, 1), "byte");
+        let value = function.load(&byte, "value");
+        function.store(&first, &value);
+        function.ret(None);
+        module.add_function(function);
+        assert_eq!(module.declarations().collect::<Vec<_>>(), vec!["tick"]);
+        assert_eq!(module.verify(), Ok(()));
+        assert_eq!(
+            module.to_string(),
+            "; ModuleID = 'test.ch8'
+source_filename = \"test.ch8\"
+
+%state = type { [2 x i8] }
+
+@rom = private constant [2 x i8] c\"\\01\\02\"
+
+define void @run(%state* %state) {
+entry:
+  %stop = call i1 @tick(%state* %state)
+  br i1 %stop, label %done, label %entry
+
+done:
+  %registers = getelementptr inbounds %state, %state* %state, i32 0, i32 0
+  %first = getelementptr inbounds [2 x i8], [2 x i8]* %registers, i32 0, i8 0
+  %byte = getelementptr inbounds [2 x i8], [2 x i8]* @rom, i32 0, i8 1
+  %value = load i8, i8* %byte
+  store i8 %value, i8* %first
+  ret void
+}
+
+declare i1 @tick(%state*)
+"
+        );
+    }
+
+    #[test]
+    fn verify_finds_unterminated_blocks() {
+        let mut module = Module::new("test.ch8");
+        let mut function = Function::new("run", Type::Void, &[]);
+        let entry = function.append_block("entry");
+        function.append_block("open");
+        function.position_at_end(entry);
+        function.ret(None);
+        module.add_function(function);
+        assert_eq!(module.verify(), Err("Block open in run has no terminator".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "already terminated")]
+    fn nothing_follows_a_terminator() {
+        let mut function = Function::new("run", Type::Void, &[]);
+        let entry = function.append_block("entry");
+        function.position_at_end(entry);
+        function.ret(None);
+        function.ret(None);
+    }
+}
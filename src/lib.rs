@@ -0,0 +1,25 @@
+/**
+ * lib.rs
+ *  chip8_retargeter_llvm statically recompiles chip8 programs to LLVM IR.
+ *  This file simply declares and exports the modules that make that up.
+ *  Everything up to writing textual IR, C or WebAssembly builds without LLVM;
+ *  compiling to native code needs the llvm feature, which is on by default.
+ */
+pub mod asm;
+pub mod c;
+pub mod cfg;
+pub mod classify;
+#[cfg(feature = "llvm")]
+pub mod codegen;
+pub mod constants;
+pub mod disasm;
+pub mod dot;
+pub mod instruction;
+pub mod ir;
+pub mod lift;
+pub mod liveness;
+pub mod quirks;
+pub mod rom;
+pub mod smc;
+pub mod trace;
+pub mod wasm;
@@ -0,0 +1,1328 @@
+/**
+ * lift.rs
+ *  this file statically recompiles a chip8 program into an LLVM IR module.
+ *  Every basic block the cfg module recovers becomes an LLVM basic block
+ *  inside chip8_run, with the same semantics as CPU::execute_next_opcode,
+ *  except blocks the classify module finds running into sprite data.
+ *  Modules are built with the ir module, so lifting needs no LLVM install.
+ *
+ *  Generated code works on the machine state owned by the runtime
+ *  (Chip8State in runtime/src/state.rs):
+ *      %chip8.state = { [16 x i8] V, i16 I, i16 PC, i16 SP, i8 DT, i8 ST,
+ *                       [16 x i16] stack, [4096 x i8] memory }
+ *  Anything that touches the host (display, keypad, timers, random numbers)
+ *  is a call into a chip8_rt_* function.
+ *
+ *  Stores the smc module finds may hit lifted code tell the runtime what
+ *  they wrote, and blocks they may hit check their own bytes on entry.
+ *  Either way, patched code is left to the interpreter.
+ *
+ *  Where the constants module knows what I holds, loads and stores it keeps
+ *  in memory are not bounds checked, and sprites the program never writes
+ *  are drawn straight from the program image.
+ *
+ *  Subroutines the cfg module finds well formed are lifted a second time
+ *  into functions of their own, and CALLs to them are native calls. The
+ *  machine's stack is still pushed and popped, with the same depth checks,
+ *  so when control leaves a subroutine any way other than RET, chip8_run
+ *  carries on from the state with the emulated stack as usual.
+ *
+ *  Inside a block V0-VF, I, DT and ST are kept in SSA values and only
+ *  written back to the state at safepoints: the end of the block and any
+ *  instruction that can fault or stop execution. chip8_rt_tick still runs
+ *  after every instruction, but is told whether the state is up to date.
+ *  Flags the liveness module finds are never read are not computed at all.
+ *
+ *  With debug info every instruction points at its address's line in the
+ *  disasm module's debug listing, and V0-VF, I, PC, SP and the stack are
+ *  variables kept in the state. Registers kept in SSA values only reach
+ *  the state at safepoints, so that is where debuggers see them change.
+ */
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::slice;
+
+use crate::cfg::BasicBlock as Block;
+use crate::classify::code_cfg;
+use crate::constants::Constants;
+use crate::disasm::debug_line;
+use crate::instruction::Instruction;
+use crate::ir::{quote, BlockId, Function, IntPredicate, Metadata, Module, Type, Value};
+use crate::liveness::dead_flags;
+use crate::rom::{Rom, LOAD_ADDRESS, MEMORY_SIZE};
+use crate::smc::SelfModification;
+
+// Field indexes into %chip8.state
+const STATE_V: u32 = 0;
+const STATE_I: u32 = 1;
+const STATE_PC: u32 = 2;
+const STATE_SP: u32 = 3;
+const STATE_DT: u32 = 4;
+const STATE_ST: u32 = 5;
+const STATE_STACK: u32 = 6;
+const STATE_MEMORY: u32 = 7;
+
+// Byte offsets of the fields of %chip8.state debuggers can show
+const OFFSET_I: u64 = 16;
+const OFFSET_PC: u64 = 18;
+const OFFSET_SP: u64 = 20;
+const OFFSET_STACK: u64 = 24;
+
+const STACK_SIZE: u64 = 16;
+
+/**
+ * A register generated code keeps in an SSA value within a block
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Register {
+    V(u8),
+    I,
+    Dt,
+    St,
+}
+
+/**
+ * Declarations of the runtime entry points generated code calls into
+ */
+struct Runtime {
+    init: Value,
+    run: Value,
+    exit: Value,
+    tick: Value,
+    cls: Value,
+    draw: Value,
+    draw_sprite: Value,
+    key_pressed: Value,
+    wait_key: Value,
+    random: Value,
+    bad_address: Value,
+    bad_stack: Value,
+    unresolved: Value,
+    code_written: Value,
+}
+
+impl Runtime {
+    fn declare(module: &mut Module, state_type: &Type) -> Self {
+        let state_ptr = state_type.ptr();
+        let void = Type::Void;
+        let bool_type = Type::Int(1);
+        let i8_type = Type::Int(8);
+        let i16_type = Type::Int(16);
+        let i32_type = Type::Int(32);
+        let rom_ptr = i8_type.ptr();
+        let run_ptr = void.function(slice::from_ref(&state_ptr)).ptr();
+        let blocks_ptr = i16_type.ptr();
+        let mut declare = |name: &str, ret: &Type, params: Vec<Type>| module.declare(name, ret.clone(), &params);
+
+        Runtime {
+            init: declare("chip8_rt_init", &state_ptr, vec![rom_ptr.clone(), i32_type.clone()]),
+            run: declare("chip8_rt_run", &void, vec![state_ptr.clone(), run_ptr, blocks_ptr, i32_type.clone()]),
+            exit: declare("chip8_rt_exit", &i32_type, vec![state_ptr.clone()]),
+            tick: declare("chip8_rt_tick", &bool_type, vec![state_ptr.clone(), bool_type.clone()]),
+            cls: declare("chip8_rt_cls", &void, vec![state_ptr.clone()]),
+            draw: declare(
+                "chip8_rt_draw",
+                &i8_type,
+                vec![state_ptr.clone(), i8_type.clone(), i8_type.clone(), i8_type.clone()],
+            ),
+            draw_sprite: declare(
+                "chip8_rt_draw_sprite",
+                &i8_type,
+                vec![state_ptr.clone(), i8_type.clone(), i8_type.clone(), rom_ptr.clone(), i8_type.clone()],
+            ),
+            key_pressed: declare("chip8_rt_key_pressed", &bool_type, vec![state_ptr.clone(), i8_type.clone()]),
+            wait_key: declare("chip8_rt_wait_key", &i8_type, vec![state_ptr.clone()]),
+            random: declare("chip8_rt_random", &i8_type, vec![state_ptr.clone()]),
+            bad_address: declare("chip8_rt_bad_address", &void, vec![state_ptr.clone(), i16_type.clone()]),
+            bad_stack: declare("chip8_rt_bad_stack", &void, vec![state_ptr.clone()]),
+            unresolved: declare("chip8_rt_unresolved", &void, vec![state_ptr.clone()]),
+            code_written: declare("chip8_rt_code_written", &bool_type, vec![state_ptr, i16_type.clone(), i16_type]),
+        }
+    }
+}
+
+/**
+ * lift recompiles a whole program into a new module. The module defines
+ *    chip8_run, which executes from the pc stored in the state until the
+ *    runtime asks it to stop or reaches an address with no recompiled code,
+ *    and a main that hands chip8_run and the table of lifted block ranges
+ *    to chip8_rt_run.
+ */
+pub fn lift(rom: &Rom, name: &str) -> Result<Module, String> {
+    let mut module = Module::new(name);
+    build_module(&mut module, rom, None);
+    module.verify().map_err(|e| format!("Generated invalid IR: {}", e))?;
+    Ok(module)
+}
+
+/**
+ * lift_with_debug_info recompiles a whole program like lift, with debug
+ *    info pointing at listing, where the debug listing for rom is kept.
+ *    listing should be absolute for debuggers to find it from anywhere.
+ */
+pub fn lift_with_debug_info(rom: &Rom, name: &str, listing: &Path) -> Result<Module, String> {
+    let mut module = Module::new(name);
+    build_module(&mut module, rom, Some(listing));
+    module.verify().map_err(|e| format!("Generated invalid IR: {}", e))?;
+    Ok(module)
+}
+
+/**
+ * lift_block compiles a single block on its own into a new module, for
+ *    JIT compilers. The module defines chip8_block, which runs the block
+ *    from its start and returns with pc stored in the state as soon as
+ *    control leaves the block or chip8_rt_tick asks it to stop at a
+ *    safepoint.
+ */
+pub fn lift_block(block: &Block, name: &str) -> Result<Module, String> {
+    let mut module = Module::new(name);
+    build_block_module(&mut module, block);
+    module.verify().map_err(|e| format!("Generated invalid IR: {}", e))?;
+    Ok(module)
+}
+
+/**
+ * declare_state adds %chip8.state to a module
+ */
+fn declare_state(module: &mut Module) -> Type {
+    let i8_type = Type::Int(8);
+    let i16_type = Type::Int(16);
+    module.add_struct(
+        "chip8.state",
+        &[
+            i8_type.array(16),
+            i16_type.clone(),
+            i16_type.clone(),
+            i16_type.clone(),
+            i8_type.clone(),
+            i8_type.clone(),
+            i16_type.array(STACK_SIZE),
+            i8_type.array(4096),
+        ],
+    )
+}
+
+fn build_block_module(module: &mut Module, block: &Block) {
+    let state_type = declare_state(module);
+    let runtime = Runtime::declare(module, &state_type);
+
+    // chip8_block(%chip8.state*)
+    let mut function = Function::new("chip8_block", Type::Void, &[(state_type.ptr(), "state")]);
+    let state = function.param(0);
+    let entry = function.append_block("entry");
+    let exit = function.append_block("exit");
+    let body = function.append_block(&format!("block_{:03X}", block.start));
+
+    // Every way out of the block returns, so there is nothing to dispatch to
+    let smc = SelfModification::default();
+    let constants = Constants::default();
+    let mut lifter = Lifter {
+        runtime: &runtime,
+        rom: None,
+        rom_data: None,
+        smc: &smc,
+        constants: &constants,
+        natives: &BTreeMap::new(),
+        function,
+        state,
+        blocks: BTreeMap::from([(block.start, body)]),
+        dispatch: exit,
+        unresolved: exit,
+        exit,
+        returned: None,
+        values: BTreeMap::new(),
+        dirty: BTreeSet::new(),
+        dead_flags: BTreeSet::new(),
+        debug: None,
+    };
+    lifter.function.position_at_end(entry);
+    lifter.function.br(body);
+    lifter.function.position_at_end(exit);
+    lifter.function.ret(None);
+    lifter.lift_block(block);
+    module.add_function(lifter.function);
+}
+
+/**
+ * Debug info every lifted function shares: the compile unit, which has
+ *    the listing as its source file, and the types of the registers
+ */
+struct DebugUnit {
+    file: Metadata,
+    unit: Metadata,
+    function_type: Metadata,
+    byte: Metadata,
+    word: Metadata,
+    stack: Metadata,
+    declare: Value,
+}
+
+impl DebugUnit {
+    /**
+     * describe adds the compile unit and register types to module, with
+     *    listing as the source file
+     */
+    fn describe(module: &mut Module, listing: &Path) -> DebugUnit {
+        let filename = listing.file_name().map_or(String::new(), |name| name.to_string_lossy().into_owned());
+        let directory = listing.parent().map_or(String::new(), |dir| dir.to_string_lossy().into_owned());
+        let file = module.add_metadata(&format!(
+            "!DIFile(filename: {}, directory: {})",
+            quote(filename.as_bytes()),
+            quote(directory.as_bytes())
+        ));
+        let unit = module.add_metadata(&format!(
+            "distinct !DICompileUnit(language: DW_LANG_C99, file: {}, producer: \"chip8_retargeter_llvm\", \
+             isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug)",
+            file
+        ));
+        module.add_named_metadata("llvm.dbg.cu", &[unit]);
+        let dwarf_version = module.add_metadata("!{i32 7, !\"Dwarf Version\", i32 4}");
+        let debug_info_version = module.add_metadata("!{i32 2, !\"Debug Info Version\", i32 3}");
+        module.add_named_metadata("llvm.module.flags", &[dwarf_version, debug_info_version]);
+
+        let no_types = module.add_metadata("!{null}");
+        let function_type = module.add_metadata(&format!("!DISubroutineType(types: {})", no_types));
+        let byte = module.add_metadata("!DIBasicType(name: \"uint8_t\", size: 8, encoding: DW_ATE_unsigned)");
+        let word = module.add_metadata("!DIBasicType(name: \"uint16_t\", size: 16, encoding: DW_ATE_unsigned)");
+        let range = module.add_metadata(&format!("!DISubrange(count: {})", STACK_SIZE));
+        let ranges = module.add_metadata(&format!("!{{{}}}", range));
+        let stack = module.add_metadata(&format!(
+            "!DICompositeType(tag: DW_TAG_array_type, baseType: {}, size: {}, elements: {})",
+            word,
+            STACK_SIZE * 16,
+            ranges
+        ));
+
+        let metadata = Type::Metadata;
+        let declare = module.declare("llvm.dbg.declare", Type::Void, &[metadata.clone(), metadata.clone(), metadata]);
+        DebugUnit { file, unit, function_type, byte, word, stack, declare }
+    }
+}
+
+/**
+ * Debug info for one lifted function, built before any of its code
+ */
+struct DebugInfo {
+    // DILocation for every lifted instruction, on the line for its address
+    locations: BTreeMap<u16, Metadata>,
+    // DILocation for code no one instruction owns, like the dispatcher
+    no_line: Metadata,
+    // DILocalVariable for each register and its byte offset in the state
+    variables: Vec<(Metadata, u64)>,
+    declare: Value,
+}
+
+impl DebugInfo {
+    /**
+     * describe adds a subprogram for function, its variables and a
+     *    location for each instruction in blocks to module
+     */
+    fn describe<'b>(
+        module: &mut Module,
+        unit: &DebugUnit,
+        function: &mut Function,
+        name: &str,
+        blocks: impl Iterator<Item = &'b Block>,
+    ) -> DebugInfo {
+        let subprogram = module.add_metadata(&format!(
+            "distinct !DISubprogram(name: \"{}\", scope: {}, file: {}, line: 1, type: {}, scopeLine: 1, \
+             spFlags: DISPFlagDefinition, unit: {})",
+            name, unit.file, unit.file, unit.function_type, unit.unit
+        ));
+        function.set_subprogram(subprogram);
+
+        let mut variable = |name: &str, ty: Metadata| {
+            module.add_metadata(&format!(
+                "!DILocalVariable(name: \"{}\", scope: {}, file: {}, line: 1, type: {})",
+                name, subprogram, unit.file, ty
+            ))
+        };
+        let mut variables: Vec<_> = (0..16).map(|x| (variable(&format!("V{:X}", x), unit.byte), x)).collect();
+        variables.push((variable("I", unit.word), OFFSET_I));
+        variables.push((variable("PC", unit.word), OFFSET_PC));
+        variables.push((variable("SP", unit.word), OFFSET_SP));
+        variables.push((variable("stack", unit.stack), OFFSET_STACK));
+
+        let mut locations = BTreeMap::new();
+        for &(pc, _) in blocks.flat_map(|block| block.instructions.iter()) {
+            let text = format!("!DILocation(line: {}, column: 1, scope: {})", debug_line(pc), subprogram);
+            locations.entry(pc).or_insert_with(|| module.add_metadata(&text));
+        }
+        let no_line = module.add_metadata(&format!("!DILocation(line: 0, scope: {})", subprogram));
+        DebugInfo { locations, no_line, variables, declare: unit.declare.clone() }
+    }
+}
+
+fn build_module(module: &mut Module, rom: &Rom, listing: Option<&Path>) {
+    let i8_type = Type::Int(8);
+    let i16_type = Type::Int(16);
+    let i32_type = Type::Int(32);
+    let state_type = declare_state(module);
+    let runtime = Runtime::declare(module, &state_type);
+
+    // chip8_run(%chip8.state*)
+    let mut run = Function::new("chip8_run", Type::Void, &[(state_type.ptr(), "state")]);
+    let state = run.param(0);
+    let entry = run.append_block("entry");
+    let dispatch = run.append_block("dispatch");
+    let unresolved = run.append_block("unresolved");
+    let exit = run.append_block("exit");
+
+    let cfg = code_cfg(rom);
+    let smc = SelfModification::analyze(&cfg);
+    let constants = Constants::analyze(&cfg);
+    let mut blocks = BTreeMap::new();
+    for start in cfg.blocks().map(|block| block.start) {
+        blocks.insert(start, run.append_block(&format!("block_{:03X}", start)));
+    }
+    let unit = listing.map(|listing| DebugUnit::describe(module, listing));
+    // The program image handed to the runtime at start up, and sprites are drawn from
+    let rom_global = module.add_constant("chip8_rom", Value::bytes(&rom.bytes));
+    let debug = unit.as_ref().map(|unit| DebugInfo::describe(module, unit, &mut run, "chip8_run", cfg.blocks()));
+
+    // Subroutines that always return normally are also lifted into functions
+    // of their own, which CALLs call straight into
+    let subroutines: Vec<_> = cfg.well_formed_functions().collect();
+    let mut natives = BTreeMap::new();
+    let mut functions = Vec::new();
+    for subroutine in &subroutines {
+        let name = format!("chip8_sub_{:03X}", subroutine.entry);
+        let mut function = Function::new(&name, Type::Int(1), &[(state_type.ptr(), "state")]);
+        function.set_internal();
+        natives.insert(subroutine.entry, function.value());
+        functions.push(function);
+    }
+
+    let mut lifter = Lifter {
+        runtime: &runtime,
+        rom: Some(rom),
+        rom_data: Some(rom_global.clone()),
+        smc: &smc,
+        constants: &constants,
+        natives: &natives,
+        function: run,
+        state,
+        blocks,
+        dispatch,
+        unresolved,
+        exit,
+        returned: None,
+        values: BTreeMap::new(),
+        dirty: BTreeSet::new(),
+        dead_flags: BTreeSet::new(),
+        debug,
+    };
+    lifter.build_prologue(entry);
+    for block in cfg.blocks() {
+        lifter.lift_block(block);
+    }
+    let run = lifter.function;
+
+    // Start and end address of every block, so the runtime knows when the
+    // interpreter can hand control back and which writes patch lifted code
+    let ranges: Vec<_> = cfg
+        .blocks()
+        .flat_map(|block| vec![block.start, block.end()])
+        .map(|addr| Value::int(16, addr as u64))
+        .collect();
+    let blocks_global = module.add_constant("chip8_blocks", Value::array(&i16_type, &ranges));
+
+    let mut main = Function::new("main", i32_type, &[]);
+    let entry = main.append_block("entry");
+    main.position_at_end(entry);
+    let rom_ptr = main.bitcast(&rom_global, &i8_type.ptr(), "rom");
+    let length = Value::int(32, rom.bytes.len() as u64);
+    let machine = main.call(&runtime.init, &[rom_ptr, length], "state").unwrap();
+    let blocks_ptr = main.bitcast(&blocks_global, &i16_type.ptr(), "blocks");
+    let count = Value::int(32, ranges.len() as u64 / 2);
+    main.call(&runtime.run, &[machine.clone(), run.value(), blocks_ptr, count], "");
+    let code = main.call(&runtime.exit, &[machine], "code").unwrap();
+    main.ret(Some(&code));
+
+    module.add_function(run);
+    for (subroutine, mut function) in subroutines.into_iter().zip(functions) {
+        let state = function.param(0);
+        let entry = function.append_block("entry");
+        let leave = function.append_block("leave");
+        let unresolved = function.append_block("unresolved");
+        let returned = function.append_block("returned");
+        let mut blocks = BTreeMap::new();
+        for &start in &subroutine.blocks {
+            blocks.insert(start, function.append_block(&format!("block_{:03X}", start)));
+        }
+        let lifted = || subroutine.blocks.iter().filter_map(|&start| cfg.block(start));
+        let name = format!("chip8_sub_{:03X}", subroutine.entry);
+        let debug = unit.as_ref().map(|unit| DebugInfo::describe(module, unit, &mut function, &name, lifted()));
+
+        // Every way out other than RET leaves it to chip8_run to carry on
+        let mut lifter = Lifter {
+            runtime: &runtime,
+            rom: Some(rom),
+            rom_data: Some(rom_global.clone()),
+            smc: &smc,
+            constants: &constants,
+            natives: &natives,
+            function,
+            state,
+            blocks,
+            dispatch: leave,
+            unresolved,
+            exit: leave,
+            returned: Some(returned),
+            values: BTreeMap::new(),
+            dirty: BTreeSet::new(),
+            dead_flags: BTreeSet::new(),
+            debug,
+        };
+        lifter.build_subroutine_prologue(entry, subroutine.entry);
+        for block in lifted() {
+            lifter.lift_block(block);
+        }
+        module.add_function(lifter.function);
+    }
+    module.add_function(main);
+}
+
+struct Lifter<'a> {
+    runtime: &'a Runtime,
+    // Program the blocks came from, none when lifting a lone block
+    rom: Option<&'a Rom>,
+    // Pointer to the program image in the module
+    rom_data: Option<Value>,
+    smc: &'a SelfModification,
+    constants: &'a Constants,
+    // Function lifted for each well formed subroutine, by entry address
+    natives: &'a BTreeMap<u16, Value>,
+    // Function the blocks are lifted into
+    function: Function,
+    // Pointer to the machine state passed into chip8_run
+    state: Value,
+    // LLVM basic block for every chip8 block start address
+    blocks: BTreeMap<u16, BlockId>,
+    // Switches on the pc stored in the state to find the block to run
+    dispatch: BlockId,
+    // Hands the pc stored in the state to the runtime's interpreter
+    unresolved: BlockId,
+    // Returns back to the runtime, or out of a subroutine to chip8_run
+    exit: BlockId,
+    // Returns to the caller after a RET, when lifting a subroutine
+    returned: Option<BlockId>,
+    // Value of each register as last loaded or set in the current block
+    values: BTreeMap<Register, Value>,
+    // Registers set since they were last written back to the state
+    dirty: BTreeSet<Register>,
+    // Instructions in the current block whose VF flag is never read
+    dead_flags: BTreeSet<u16>,
+    debug: Option<DebugInfo>,
+}
+
+impl<'a> Lifter<'a> {
+    /**
+     * build_prologue fills in the blocks every chip8_run has: the entry,
+     *    the pc dispatcher, the unresolved target handler and the exit.
+     */
+    fn build_prologue(&mut self, entry: BlockId) {
+        self.function.position_at_end(entry);
+        self.declare_variables();
+        self.function.br(self.dispatch);
+
+        self.function.position_at_end(self.dispatch);
+        let pc = self.load_field(STATE_PC, "pc");
+        let cases: Vec<_> = self
+            .blocks
+            .iter()
+            .map(|(&start, &block)| (self.i16(start as u64), block))
+            .collect();
+        self.function.switch(&pc, self.unresolved, &cases);
+
+        // Nothing was lifted at pc, let the runtime decide what to do
+        self.function.position_at_end(self.unresolved);
+        self.call(&self.runtime.unresolved, &[], "");
+        self.function.br(self.exit);
+
+        self.function.position_at_end(self.exit);
+        self.function.ret(None);
+    }
+
+    /**
+     * declare_variables tells debuggers where each register is kept, when
+     *    there is debug info. Call it first thing in the entry block.
+     */
+    fn declare_variables(&mut self) {
+        if let Some(debug) = &self.debug {
+            // Every register lives at its offset from the state pointer
+            self.function.set_location(Some(debug.no_line));
+            let state = self.state.as_metadata();
+            for (variable, offset) in &debug.variables {
+                let expression = Value::metadata(&format!("!DIExpression(DW_OP_plus_uconst, {})", offset));
+                self.function.call(&debug.declare, &[state.clone(), variable.value(), expression], "");
+            }
+        }
+    }
+
+    /**
+     * build_subroutine_prologue fills in the blocks every subroutine has:
+     *    the entry, which goes straight to the block at start, and the
+     *    ways out. Returning false tells the caller control left some way
+     *    other than RET, with every register and pc in the state.
+     */
+    fn build_subroutine_prologue(&mut self, entry: BlockId, start: u16) {
+        self.function.position_at_end(entry);
+        self.declare_variables();
+        self.function.br(self.blocks[&start]);
+
+        self.function.position_at_end(self.exit);
+        self.function.ret(Some(&Value::bool(false)));
+
+        self.function.position_at_end(self.unresolved);
+        self.call(&self.runtime.unresolved, &[], "");
+        self.function.br(self.exit);
+
+        if let Some(returned) = self.returned {
+            self.function.position_at_end(returned);
+            self.function.ret(Some(&Value::bool(true)));
+        }
+    }
+
+    fn lift_block(&mut self, block: &Block) {
+        // Values from other blocks are not available here
+        self.values.clear();
+        self.dirty.clear();
+        self.dead_flags = dead_flags(block);
+
+        self.function.position_at_end(self.blocks[&block.start]);
+        self.set_location(block.start);
+        if self.smc.may_be_written(block.start) {
+            self.check_unpatched(block);
+        }
+        let last = block.instructions.len() - 1;
+        for (index, &(pc, instruction)) in block.instructions.iter().enumerate() {
+            self.set_location(pc);
+            self.lift_instruction(pc, instruction, index == last);
+        }
+
+        // Block ran into the start of another block or off the end of the program
+        if block.falls_through() {
+            let next = self.target(block.end());
+            self.function.br(next);
+        }
+    }
+
+    /**
+     * check_unpatched compares the block's bytes in memory against the
+     *    instructions that were lifted, and leaves the block to the
+     *    interpreter if any of them changed.
+     */
+    fn check_unpatched(&mut self, block: &Block) {
+        let mut patched = Value::bool(false);
+        for addr in block.start..block.end() {
+            let ptr = self.memory_at(&Value::int(32, addr as u64), 0);
+            let byte = self.function.load(&ptr, "code");
+            let lifted = self.i8(self.rom.and_then(|rom| rom.read_byte(addr)).unwrap());
+            let changed = self.function.icmp(IntPredicate::NE, &byte, &lifted, "changed");
+            patched = self.function.or(&patched, &changed, "patched");
+        }
+
+        let body = self.function.append_block(&format!("body_{:03X}", block.start));
+        let bail = self.function.append_block(&format!("patched_{:03X}", block.start));
+        self.function.cond_br(&patched, bail, body);
+        self.function.position_at_end(bail);
+        self.set_pc(block.start);
+        self.function.br(self.unresolved);
+        self.function.position_at_end(body);
+    }
+
+    /**
+     * lift_instruction emits one instruction. last is set for the final
+     *    instruction in its block, which always ends on a safepoint.
+     */
+    fn lift_instruction(&mut self, pc: u16, instruction: Instruction, last: bool) {
+        // Set by stores that may have patched lifted code
+        let mut patched = None;
+        let flag_live = !self.dead_flags.contains(&pc);
+        match instruction {
+            Instruction::Cls => {
+                self.call(&self.runtime.cls, &[], "");
+            }
+            Instruction::Ret => {
+                // RET with an empty stack is a fault rather than an underflow
+                let sp = self.load_field(STATE_SP, "sp");
+                let empty = self.function.icmp(IntPredicate::EQ, &sp, &self.i16(0), "empty");
+                self.guard(pc, &empty, |lifter| {
+                    lifter.call(&lifter.runtime.bad_stack, &[], "");
+                });
+                let slot = self.stack_slot(&sp);
+                let ret_addr = self.function.load(&slot, "ret_addr");
+                let sp = self.function.sub(&sp, &self.i16(1), "sp");
+                self.store_field(STATE_SP, &sp);
+                let next = self.function.add(&ret_addr, &self.i16(2), "next");
+                self.store_field(STATE_PC, &next);
+                // The caller checks it is the one being returned to
+                self.tick_then(self.returned.unwrap_or(self.dispatch));
+                return;
+            }
+            Instruction::Sys(_)
+            | Instruction::ScrollDown(_)
+            | Instruction::ScrollRight
+            | Instruction::ScrollLeft
+            | Instruction::Exit
+            | Instruction::Low
+            | Instruction::High => {
+                // Ignored by the interpreter, it just moves on. Outside
+                // SUPER-CHIP mode that includes SUPER-CHIP's 00CN to 00FF.
+            }
+            Instruction::Unknown(_) | Instruction::LdHf(_) | Instruction::StoreRpl(_) | Instruction::LoadRpl(_) => {
+                // A fault, which the interpreter is left to report
+                self.set_pc(pc);
+                self.spill();
+                self.function.br(self.unresolved);
+                let next = self.function.append_block(&format!("unknown_{:03X}", pc));
+                self.function.position_at_end(next);
+            }
+            Instruction::Jp(addr) => {
+                self.set_pc(addr);
+                self.tick_then(self.target(addr));
+                return;
+            }
+            Instruction::Call(addr) => {
+                // Stack slot 0 is never used, the interpreter increments sp first
+                let sp = self.load_field(STATE_SP, "sp");
+                let sp = self.function.add(&sp, &self.i16(1), "sp");
+                let full = self.function.icmp(IntPredicate::UGE, &sp, &self.i16(STACK_SIZE), "full");
+                self.guard(pc, &full, |lifter| {
+                    lifter.call(&lifter.runtime.bad_stack, &[], "");
+                });
+                self.store_field(STATE_SP, &sp);
+                let slot = self.stack_slot(&sp);
+                self.function.store(&slot, &self.i16(pc as u64));
+                self.set_pc(addr);
+                match self.natives.get(&addr) {
+                    Some(native) => self.call_native(pc, native),
+                    None => self.tick_then(self.target(addr)),
+                }
+                return;
+            }
+            Instruction::SeByte(x, byte) => {
+                let vx = self.load_v(x);
+                let cond = self.function.icmp(IntPredicate::EQ, &vx, &self.i8(byte), "se");
+                return self.skip_if(pc, &cond);
+            }
+            Instruction::SneByte(x, byte) => {
+                let vx = self.load_v(x);
+                let cond = self.function.icmp(IntPredicate::NE, &vx, &self.i8(byte), "sne");
+                return self.skip_if(pc, &cond);
+            }
+            Instruction::SeReg(x, y) => {
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                let cond = self.function.icmp(IntPredicate::EQ, &vx, &vy, "se");
+                return self.skip_if(pc, &cond);
+            }
+            Instruction::SneReg(x, y) => {
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                let cond = self.function.icmp(IntPredicate::NE, &vx, &vy, "sne");
+                return self.skip_if(pc, &cond);
+            }
+            Instruction::LdByte(x, byte) => {
+                self.store_v(x, self.i8(byte));
+            }
+            Instruction::AddByte(x, byte) => {
+                let vx = self.load_v(x);
+                let sum = self.function.add(&vx, &self.i8(byte), "add");
+                self.store_v(x, sum);
+            }
+            Instruction::LdReg(x, y) => {
+                let vy = self.load_v(y);
+                self.store_v(x, vy);
+            }
+            Instruction::Or(x, y) => {
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                let result = self.function.or(&vx, &vy, "or");
+                self.store_v(x, result);
+            }
+            Instruction::And(x, y) => {
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                let result = self.function.and(&vx, &vy, "and");
+                self.store_v(x, result);
+            }
+            Instruction::Xor(x, y) => {
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                let result = self.function.xor(&vx, &vy, "xor");
+                self.store_v(x, result);
+            }
+            Instruction::AddReg(x, y) => {
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                let sum = self.function.add(&vx, &vy, "add");
+                if flag_live {
+                    let carry = self.function.icmp(IntPredicate::ULT, &sum, &vx, "carry");
+                    self.store_flag(&carry);
+                }
+                self.store_v(x, sum);
+            }
+            // The interpreter writes VF before computing Vx, so Vx and Vy are
+            // loaded again afterwards in case one of them is VF.
+            Instruction::Sub(x, y) => {
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                if flag_live {
+                    let no_borrow = self.function.icmp(IntPredicate::UGT, &vx, &vy, "no_borrow");
+                    self.store_flag(&no_borrow);
+                }
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                let diff = self.function.sub(&vx, &vy, "sub");
+                self.store_v(x, diff);
+            }
+            Instruction::Shr(x, _) => {
+                let vx = self.load_v(x);
+                if flag_live {
+                    let lsb = self.function.and(&vx, &self.i8(0x01), "lsb");
+                    self.store_v(0xF, lsb);
+                }
+                let vx = self.load_v(x);
+                let shifted = self.function.lshr(&vx, &self.i8(1), "shr");
+                self.store_v(x, shifted);
+            }
+            Instruction::Subn(x, y) => {
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                if flag_live {
+                    let no_borrow = self.function.icmp(IntPredicate::UGT, &vy, &vx, "no_borrow");
+                    self.store_flag(&no_borrow);
+                }
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                let diff = self.function.sub(&vy, &vx, "subn");
+                self.store_v(x, diff);
+            }
+            Instruction::Shl(x, _) => {
+                let vx = self.load_v(x);
+                if flag_live {
+                    let msb = self.function.lshr(&vx, &self.i8(7), "msb");
+                    self.store_v(0xF, msb);
+                }
+                let vx = self.load_v(x);
+                let shifted = self.function.shl(&vx, &self.i8(1), "shl");
+                self.store_v(x, shifted);
+            }
+            Instruction::LdI(addr) => {
+                self.store(Register::I, self.i16(addr as u64));
+            }
+            Instruction::JpV0(addr) => {
+                let v0 = self.load_v(0);
+                let v0 = self.function.zext(&v0, &Type::Int(16), "v0");
+                let target = self.function.add(&v0, &self.i16(addr as u64), "target");
+                self.store_field(STATE_PC, &target);
+                self.tick_then(self.dispatch);
+                return;
+            }
+            Instruction::Rnd(x, byte) => {
+                let random = self.call(&self.runtime.random, &[], "random").unwrap();
+                let masked = self.function.and(&random, &self.i8(byte), "rnd");
+                self.store_v(x, masked);
+            }
+            Instruction::Drw(x, y, n) => {
+                let (vx, vy) = (self.load_v(x), self.load_v(y));
+                self.set_pc(pc);
+                let collision = match self.constant_sprite(pc, n) {
+                    Some(sprite) => self.call(&self.runtime.draw_sprite, &[vx, vy, sprite, self.i8(n)], "collision"),
+                    None => {
+                        // A sprite running off the end of memory faults here, before
+                        // anything is drawn or counted, as in the interpreter. Even
+                        // DXY0 faults once I is past the end.
+                        self.checked_i(pc, n as u64);
+                        // The runtime reads the sprite from I
+                        self.write_back(Register::I);
+                        self.call(&self.runtime.draw, &[vx, vy, self.i8(n)], "collision")
+                    }
+                };
+                self.store_v(0xF, collision.unwrap());
+            }
+            Instruction::Skp(x) => {
+                let vx = self.load_v(x);
+                self.set_pc(pc);
+                let pressed = self.call(&self.runtime.key_pressed, &[vx], "pressed").unwrap();
+                return self.skip_if(pc, &pressed);
+            }
+            Instruction::Sknp(x) => {
+                let vx = self.load_v(x);
+                self.set_pc(pc);
+                let pressed = self.call(&self.runtime.key_pressed, &[vx], "pressed").unwrap();
+                let released = self.function.not(&pressed, "released");
+                return self.skip_if(pc, &released);
+            }
+            Instruction::LdVxDt(x) => {
+                let dt = self.load(Register::Dt, "dt");
+                self.store_v(x, dt);
+            }
+            Instruction::LdVxK(x) => {
+                // The runtime ticks while it waits, with the state up to date
+                self.set_pc(pc);
+                self.flush();
+                let key = self.call(&self.runtime.wait_key, &[], "key").unwrap();
+                self.forget_timers();
+                self.store_v(x, key);
+            }
+            Instruction::LdDtVx(x) => {
+                let vx = self.load_v(x);
+                self.store(Register::Dt, vx);
+            }
+            Instruction::LdStVx(x) => {
+                let vx = self.load_v(x);
+                self.store(Register::St, vx);
+            }
+            Instruction::AddI(x) => {
+                let i = self.load(Register::I, "i");
+                let vx = self.load_v(x);
+                let vx = self.function.zext(&vx, &Type::Int(16), "vx");
+                let sum = self.function.add(&i, &vx, "i");
+                self.store(Register::I, sum);
+            }
+            Instruction::LdF(x) => {
+                // All font sprites start at their digit times 5
+                let vx = self.load_v(x);
+                let vx = self.function.zext(&vx, &Type::Int(16), "vx");
+                let addr = self.function.mul(&vx, &self.i16(5), "font");
+                self.store(Register::I, addr);
+            }
+            Instruction::LdB(x) => {
+                let i = self.checked_i(pc, 3);
+                let vx = self.load_v(x);
+                let hundreds = self.function.udiv(&vx, &self.i8(100), "hundreds");
+                let tens = self.function.udiv(&vx, &self.i8(10), "tens");
+                let tens = self.function.urem(&tens, &self.i8(10), "tens");
+                let ones = self.function.urem(&vx, &self.i8(10), "ones");
+                for (offset, digit) in [hundreds, tens, ones].iter().enumerate() {
+                    let ptr = self.memory_at(&i, offset as u64);
+                    self.function.store(&ptr, digit);
+                }
+                patched = self.report_write(pc, &i, 3);
+            }
+            Instruction::Store(x) => {
+                let i = self.checked_i(pc, x as u64 + 1);
+                for offset in 0..=x {
+                    let value = self.load_v(offset);
+                    let ptr = self.memory_at(&i, offset as u64);
+                    self.function.store(&ptr, &value);
+                }
+                patched = self.report_write(pc, &i, x as u64 + 1);
+            }
+            Instruction::Load(x) => {
+                let i = self.checked_i(pc, x as u64 + 1);
+                for offset in 0..=x {
+                    let ptr = self.memory_at(&i, offset as u64);
+                    let value = self.function.load(&ptr, "mem");
+                    self.store_v(offset, value);
+                }
+            }
+        }
+
+        // Everything else moves on to the next instruction. Unless the block
+        // can be left here, the tick is not a safepoint and cannot stop it.
+        let leaves = matches!(instruction, Instruction::Drw(..) | Instruction::LdVxK(_));
+        if !(last || leaves || patched.is_some()) {
+            self.tick(false);
+            return;
+        }
+        let next = self.function.append_block(&format!("op_{:03X}", pc + 2));
+        self.set_pc(pc + 2);
+        match patched {
+            // Code after this store may have changed, so the interpreter runs it
+            Some(patched) => {
+                let check = self.function.append_block(&format!("patch_check_{:03X}", pc));
+                self.tick_then(check);
+                self.function.position_at_end(check);
+                self.function.cond_br(&patched, self.unresolved, next);
+            }
+            None => self.tick_then(next),
+        }
+        self.function.position_at_end(next);
+    }
+
+    /**
+     * report_write tells the runtime about a store the smc module says may
+     *    hit lifted code. Returns whether lifted code really was patched.
+     */
+    fn report_write(&mut self, pc: u16, i: &Value, length: u64) -> Option<Value> {
+        if !self.smc.writes_code(pc) {
+            return None;
+        }
+        let addr = self.function.trunc(i, &Type::Int(16), "addr");
+        self.call(&self.runtime.code_written, &[addr, self.i16(length)], "patched")
+    }
+
+    /**
+     * skip_if ends a block on a skip instruction, jumping over the next
+     *    instruction when cond holds.
+     */
+    fn skip_if(&mut self, pc: u16, cond: &Value) {
+        let skipped = self.i16(pc as u64 + 4);
+        let next = self.i16(pc as u64 + 2);
+        let next_pc = self.function.select(cond, &skipped, &next, "next");
+        self.store_field(STATE_PC, &next_pc);
+
+        let branch = self.function.append_block(&format!("skip_{:03X}", pc));
+        self.tick_then(branch);
+        self.function.position_at_end(branch);
+        self.function.cond_br(cond, self.target(pc + 4), self.target(pc + 2));
+    }
+
+    /**
+     * call_native ends the block on a CALL at pc by calling the function
+     *    lifted for its subroutine. When that returns to the instruction
+     *    after the CALL, execution carries on there directly; any other way
+     *    out of the subroutine goes through the dispatcher or back out.
+     */
+    fn call_native(&mut self, pc: u16, native: &Value) {
+        let call = self.function.append_block(&format!("call_{:03X}", pc));
+        self.tick_then(call);
+        self.function.position_at_end(call);
+        let returned = self.call(native, &[], "returned").unwrap();
+        let back = self.function.append_block(&format!("back_{:03X}", pc));
+        self.function.cond_br(&returned, back, self.exit);
+
+        self.function.position_at_end(back);
+        let next = self.load_field(STATE_PC, "next");
+        let expected = self.function.icmp(IntPredicate::EQ, &next, &self.i16(pc as u64 + 2), "expected");
+        self.function.cond_br(&expected, self.target(pc + 2), self.dispatch);
+    }
+
+    /**
+     * tick lets the runtime run its timers and input between instructions.
+     *    At a safepoint every register is written back first and the
+     *    result says whether to carry on, elsewhere only the timers are.
+     */
+    fn tick(&mut self, safepoint: bool) -> Value {
+        if safepoint {
+            self.flush();
+        } else {
+            self.write_back(Register::Dt);
+            self.write_back(Register::St);
+        }
+        let running = self.call(&self.runtime.tick, &[Value::bool(safepoint)], "running").unwrap();
+        self.forget_timers();
+        running
+    }
+
+    /**
+     * tick_then ticks at a safepoint, then continues at next unless asked
+     *    to stop.
+     */
+    fn tick_then(&mut self, next: BlockId) {
+        let running = self.tick(true);
+        self.function.cond_br(&running, next, self.exit);
+    }
+
+    /**
+     * guard calls fault and leaves chip8_run with pc on the faulting
+     *    instruction if cond holds, otherwise carries on.
+     */
+    fn guard<F>(&mut self, pc: u16, cond: &Value, fault: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let faulted = self.function.append_block(&format!("fault_{:03X}", pc));
+        let ok = self.function.append_block(&format!("ok_{:03X}", pc));
+        self.function.cond_br(cond, faulted, ok);
+        self.function.position_at_end(faulted);
+        self.set_pc(pc);
+        self.spill();
+        fault(self);
+        self.function.br(self.exit);
+        self.function.position_at_end(ok);
+    }
+
+    /**
+     * checked_i loads I and faults unless the length bytes from I are all
+     *    in memory, which goes without checking where the constants module
+     *    knows I is low enough
+     */
+    fn checked_i(&mut self, pc: u16, length: u64) -> Value {
+        let i = self.load(Register::I, "i");
+        let i = self.function.zext(&i, &Type::Int(32), "i");
+        if self.constants.i(pc).high as u64 + length <= MEMORY_SIZE as u64 {
+            return i;
+        }
+        let end = self.function.add(&i, &Value::int(32, length), "end");
+        let out_of_bounds =
+            self.function.icmp(IntPredicate::UGT, &end, &Value::int(32, MEMORY_SIZE as u64), "out_of_bounds");
+        self.guard(pc, &out_of_bounds, |lifter| {
+            let start = lifter.function.trunc(&i, &Type::Int(16), "start");
+            lifter.call(&lifter.runtime.bad_address, &[start], "");
+        });
+        i
+    }
+
+    /**
+     * constant_sprite returns a pointer to the n byte sprite the DXYN at pc
+     *    draws, in the program image, if I is known there and the program
+     *    never writes the sprite
+     */
+    fn constant_sprite(&mut self, pc: u16, n: u8) -> Option<Value> {
+        let addr = self.constants.i(pc).value()?;
+        let end = addr + n as u16;
+        let rom = self.rom?;
+        if !rom.contains(addr) || end > rom.end() || self.constants.may_write(addr, end) {
+            return None;
+        }
+        let rom_data = self.rom_data.clone()?;
+        let offset = Value::int(32, (addr - LOAD_ADDRESS) as u64);
+        Some(self.function.array_gep(&rom_data, &offset, "sprite"))
+    }
+
+    /**
+     * target returns the block for a known address, or the dispatcher which
+     *    hands unknown addresses to the runtime.
+     */
+    fn target(&self, addr: u16) -> BlockId {
+        *self.blocks.get(&addr).unwrap_or(&self.dispatch)
+    }
+
+    /**
+     * set_location attributes the code that follows to the instruction at
+     *    pc, when there is debug info
+     */
+    fn set_location(&mut self, pc: u16) {
+        let location = self.debug.as_ref().and_then(|debug| debug.locations.get(&pc).copied());
+        self.function.set_location(location);
+    }
+
+    /**
+     * call calls a runtime function with the state followed by args
+     */
+    fn call(&mut self, function: &Value, args: &[Value], name: &str) -> Option<Value> {
+        let mut all_args = vec![self.state.clone()];
+        all_args.extend(args.iter().cloned());
+        self.function.call(function, &all_args, name)
+    }
+
+    fn field(&mut self, index: u32) -> Value {
+        self.function.struct_gep(&self.state, index, "field")
+    }
+
+    fn load_field(&mut self, index: u32, name: &str) -> Value {
+        let ptr = self.field(index);
+        self.function.load(&ptr, name)
+    }
+
+    fn store_field(&mut self, index: u32, value: &Value) {
+        let ptr = self.field(index);
+        self.function.store(&ptr, value);
+    }
+
+    fn set_pc(&mut self, addr: u16) {
+        self.store_field(STATE_PC, &self.i16(addr as u64));
+    }
+
+    fn v_ptr(&mut self, x: u8) -> Value {
+        let registers = self.field(STATE_V);
+        self.function.array_gep(&registers, &Value::int(32, x as u64), &format!("v{:x}_ptr", x))
+    }
+
+    fn register_ptr(&mut self, register: Register) -> Value {
+        match register {
+            Register::V(x) => self.v_ptr(x),
+            Register::I => self.field(STATE_I),
+            Register::Dt => self.field(STATE_DT),
+            Register::St => self.field(STATE_ST),
+        }
+    }
+
+    /**
+     * load returns a register's value, only reading the state the first
+     *    time the block uses it
+     */
+    fn load(&mut self, register: Register, name: &str) -> Value {
+        if let Some(value) = self.values.get(&register) {
+            return value.clone();
+        }
+        let ptr = self.register_ptr(register);
+        let value = self.function.load(&ptr, name);
+        self.values.insert(register, value.clone());
+        value
+    }
+
+    /**
+     * store sets a register's value, leaving the state to be written at
+     *    the next safepoint
+     */
+    fn store(&mut self, register: Register, value: Value) {
+        self.values.insert(register, value);
+        self.dirty.insert(register);
+    }
+
+    /**
+     * write_back writes one register to the state if it has changed
+     */
+    fn write_back(&mut self, register: Register) {
+        if self.dirty.remove(&register) {
+            let ptr = self.register_ptr(register);
+            self.function.store(&ptr, &self.values[&register]);
+        }
+    }
+
+    /**
+     * flush writes every changed register to the state
+     */
+    fn flush(&mut self) {
+        let dirty: Vec<_> = self.dirty.iter().copied().collect();
+        for register in dirty {
+            self.write_back(register);
+        }
+    }
+
+    /**
+     * spill writes every changed register to the state on a path out of
+     *    the block, leaving them changed for the path that carries on
+     */
+    fn spill(&mut self) {
+        let dirty: Vec<_> = self.dirty.iter().copied().collect();
+        for register in dirty {
+            let ptr = self.register_ptr(register);
+            self.function.store(&ptr, &self.values[&register]);
+        }
+    }
+
+    /**
+     * forget_timers drops DT and ST after a call that counts them down, so
+     *    they are read again from the state. Both must be written back.
+     */
+    fn forget_timers(&mut self) {
+        self.values.remove(&Register::Dt);
+        self.values.remove(&Register::St);
+    }
+
+    fn load_v(&mut self, x: u8) -> Value {
+        self.load(Register::V(x), &format!("v{:x}", x))
+    }
+
+    fn store_v(&mut self, x: u8, value: Value) {
+        self.store(Register::V(x), value);
+    }
+
+    /**
+     * store_flag writes an i1 condition to VF as 0 or 1
+     */
+    fn store_flag(&mut self, flag: &Value) {
+        let flag = self.function.zext(flag, &Type::Int(8), "vf");
+        self.store_v(0xF, flag);
+    }
+
+    fn stack_slot(&mut self, sp: &Value) -> Value {
+        let stack = self.field(STATE_STACK);
+        self.function.array_gep(&stack, sp, "slot")
+    }
+
+    /**
+     * memory_at returns a pointer to memory[i + offset], i must be an i32
+     *    that has already been bounds checked
+     */
+    fn memory_at(&mut self, i: &Value, offset: u64) -> Value {
+        let memory = self.field(STATE_MEMORY);
+        let addr = self.function.add(i, &Value::int(32, offset), "addr");
+        self.function.array_gep(&memory, &addr, "mem_ptr")
+    }
+
+    fn i8(&self, value: u8) -> Value {
+        Value::int(8, value as u64)
+    }
+
+    fn i16(&self, value: u64) -> Value {
+        Value::int(16, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::Cfg;
+
+    fn lift_bytes(bytes: &[u8]) -> String {
+        lift(&Rom::from_bytes(bytes.to_vec()).unwrap(), "test.ch8").unwrap().to_string()
+    }
+
+    /**
+     * block_text returns the instructions of the block with a label, up to
+     *    the blank line after it
+     */
+    fn block_text<'a>(ir: &'a str, label: &str) -> &'a str {
+        let start = ir.find(&format!("\n{}:\n", label)).unwrap_or_else(|| panic!("no block {}", label));
+        let block = &ir[start + label.len() + 3..];
+        &block[..block.find("\n\n").unwrap_or(block.len())]
+    }
+
+    // LD VF, 5; LD I, 0x000; DRW VF, V1, 1; 0xF0FF; CALL 0x20C; JP 0x20A; LD V0, 1; RET
+    const PROGRAM: &[u8] = &[
+        0x6F, 0x05, 0xA0, 0x00, 0xDF, 0x11, 0xF0, 0xFF, 0x22, 0x0C, 0x12, 0x0A, 0x60, 0x01, 0x00, 0xEE,
+    ];
+
+    #[test]
+    fn every_block_and_subroutine_is_lifted() {
+        let ir = lift_bytes(PROGRAM);
+        assert!(ir.starts_with("; ModuleID = 'test.ch8'\n"));
+        assert!(ir.contains("\ndefine void @chip8_run(%chip8.state* %state) {\n"));
+        assert!(ir.contains("\ndefine internal i1 @chip8_sub_20C(%chip8.state* %state) {\n"));
+        assert!(ir.contains("\ndefine i32 @main() {\n"));
+        for label in ["block_200", "block_20A", "block_20C"] {
+            block_text(&ir, label);
+        }
+    }
+
+    #[test]
+    fn lifting_is_deterministic() {
+        assert_eq!(lift_bytes(PROGRAM), lift_bytes(PROGRAM));
+    }
+
+    #[test]
+    fn drw_reads_vf_before_writing_the_collision() {
+        let ir = lift_bytes(PROGRAM);
+        let block = block_text(&ir, "block_200");
+        assert!(block.contains("@chip8_rt_draw(%chip8.state* %state, i8 5, "), "{}", block);
+        assert!(!block.contains("store i8 0, i8* %vf_ptr"), "{}", block);
+    }
+
+    #[test]
+    fn sprites_off_the_end_of_memory_fault_at_the_drw() {
+        // LD V0, 1; LD I, 0xFFF; LD VF, 5; DRW V0, V0, 2; JP 0x208
+        let ir = lift_bytes(&[0x60, 0x01, 0xAF, 0xFF, 0x6F, 0x05, 0xD0, 0x02, 0x12, 0x08]);
+        let block = block_text(&ir, "fault_206");
+        assert!(block.contains("store i16 518, "), "{}", block);
+        assert!(block.contains("store i8 5, i8* %vf_ptr"), "{}", block);
+        assert!(block.contains("@chip8_rt_bad_address("), "{}", block);
+        assert!(block_text(&ir, "ok_206").contains("@chip8_rt_draw("));
+    }
+
+    #[test]
+    fn empty_sprites_past_the_end_of_memory_fault_at_the_drw() {
+        // LD I, 0xFFF; LD V0, 2; ADD I, V0; DRW V0, V0, 0; JP 0x208
+        let ir = lift_bytes(&[0xAF, 0xFF, 0x60, 0x02, 0xF0, 0x1E, 0xD0, 0x00, 0x12, 0x08]);
+        let block = block_text(&ir, "fault_206");
+        assert!(block.contains("store i16 518, "), "{}", block);
+        assert!(block.contains("@chip8_rt_bad_address("), "{}", block);
+        assert!(block_text(&ir, "ok_206").contains("@chip8_rt_draw("));
+    }
+
+    #[test]
+    fn unknown_opcodes_leave_for_the_interpreter() {
+        let ir = lift_bytes(PROGRAM);
+        let block = block_text(&ir, "op_206");
+        assert!(block.contains("store i16 518, "), "{}", block);
+        assert!(block.ends_with("br label %unresolved"), "{}", block);
+    }
+
+    #[test]
+    fn single_blocks_lift_on_their_own() {
+        let rom = Rom::from_bytes(PROGRAM.to_vec()).unwrap();
+        let cfg = Cfg::build(&rom);
+        let block = cfg.block_containing(0x20C).unwrap();
+        let ir = lift_block(block, "block.ch8").unwrap().to_string();
+        assert!(ir.contains("\ndefine void @chip8_block(%chip8.state* %state) {\n"));
+        assert!(!ir.contains("@chip8_run"));
+    }
+
+    #[test]
+    fn stores_that_patch_code_go_back_through_the_dispatcher() {
+        // LD I, 0x208; LD [I], V1; JP 0x206; JP 0x208; JP 0x200
+        let ir = lift_bytes(&[0xA2, 0x08, 0xF1, 0x55, 0x12, 0x06, 0x12, 0x08, 0x12, 0x00]);
+        assert!(block_text(&ir, "block_200").contains("call i1 @chip8_rt_code_written("));
+        assert_eq!(block_text(&ir, "patch_check_202").trim(), "br i1 %patched, label %unresolved, label %op_204");
+        assert!(block_text(&ir, "block_208").ends_with("label %patched_208, label %body_208"));
+        assert!(block_text(&ir, "patched_208").contains("br label %unresolved"));
+        assert!(!ir.contains("patched_200") && !ir.contains("patched_206"));
+    }
+}
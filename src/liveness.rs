@@ -0,0 +1,167 @@
+/**
+ * liveness.rs
+ *  this file works out which VF flag writes nothing ever reads. 8XY4, 8XY5,
+ *  8XY6, 8XY7 and 8XYE all set VF, and programs mostly use them for the
+ *  result in Vx and overwrite VF before looking at it. The lifter skips
+ *  computing the flag for those instructions.
+ *
+ *  The analysis runs backwards over a single block. VF is taken to be live
+ *  wherever generated code may hand the machine to the runtime with every
+ *  register written back: at the end of the block and on any instruction
+ *  that can fault or stop execution.
+ */
+use std::collections::BTreeSet;
+
+use crate::cfg::BasicBlock;
+use crate::instruction::Instruction;
+
+const VF: u8 = 0xF;
+
+/**
+ * reads_vf returns true if an instruction reads VF as an operand
+ */
+fn reads_vf(instruction: &Instruction) -> bool {
+    match *instruction {
+        Instruction::SeByte(x, _)
+        | Instruction::SneByte(x, _)
+        | Instruction::AddByte(x, _)
+        | Instruction::Shr(x, _)
+        | Instruction::Shl(x, _)
+        | Instruction::Skp(x)
+        | Instruction::Sknp(x)
+        | Instruction::LdDtVx(x)
+        | Instruction::LdStVx(x)
+        | Instruction::AddI(x)
+        | Instruction::LdF(x)
+        | Instruction::LdB(x) => x == VF,
+        Instruction::SeReg(x, y)
+        | Instruction::SneReg(x, y)
+        | Instruction::Or(x, y)
+        | Instruction::And(x, y)
+        | Instruction::Xor(x, y)
+        | Instruction::AddReg(x, y)
+        | Instruction::Sub(x, y)
+        | Instruction::Subn(x, y)
+        | Instruction::Drw(x, y, _) => x == VF || y == VF,
+        Instruction::LdReg(_, y) => y == VF,
+        Instruction::Store(x) => x == VF,
+        _ => false,
+    }
+}
+
+/**
+ * writes_vf returns true if an instruction always leaves a new value in VF
+ */
+fn writes_vf(instruction: &Instruction) -> bool {
+    match *instruction {
+        Instruction::AddReg(..)
+        | Instruction::Sub(..)
+        | Instruction::Shr(..)
+        | Instruction::Subn(..)
+        | Instruction::Shl(..)
+        | Instruction::Drw(..) => true,
+        Instruction::LdByte(x, _)
+        | Instruction::AddByte(x, _)
+        | Instruction::LdReg(x, _)
+        | Instruction::Or(x, _)
+        | Instruction::And(x, _)
+        | Instruction::Xor(x, _)
+        | Instruction::Rnd(x, _)
+        | Instruction::LdVxDt(x)
+        | Instruction::LdVxK(x)
+        | Instruction::Load(x) => x == VF,
+        _ => false,
+    }
+}
+
+/**
+ * may_leave returns true if execution can leave the block before an
+ *    instruction finishes, through a fault or the runtime stopping it
+ */
+fn may_leave(instruction: &Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::Ret
+            | Instruction::Call(_)
+            | Instruction::Drw(..)
+            | Instruction::LdVxK(_)
+            | Instruction::LdB(_)
+            | Instruction::Store(_)
+            | Instruction::Load(_)
+            | Instruction::Unknown(_)
+            | Instruction::LdHf(_)
+            | Instruction::StoreRpl(_)
+            | Instruction::LoadRpl(_)
+    )
+}
+
+/**
+ * flag_read_in_place returns true for flag setting instructions that go on
+ *    to use the flag themselves, because Vx or Vy is VF. The interpreter
+ *    sets VF before computing Vx for all but 8XY4.
+ */
+fn flag_read_in_place(instruction: &Instruction) -> bool {
+    match *instruction {
+        Instruction::Sub(x, y) | Instruction::Subn(x, y) => x == VF || y == VF,
+        Instruction::Shr(x, _) | Instruction::Shl(x, _) => x == VF,
+        _ => false,
+    }
+}
+
+/**
+ * dead_flags returns the address of every 8XY4, 8XY5, 8XY6, 8XY7 and 8XYE
+ *    in a block whose flag is overwritten before anything reads it
+ */
+pub fn dead_flags(block: &BasicBlock) -> BTreeSet<u16> {
+    let mut dead = BTreeSet::new();
+    // Whether the VF written so far, going backwards, is read later on
+    let mut live = true;
+    for &(pc, instruction) in block.instructions.iter().rev() {
+        let sets_flag = matches!(
+            instruction,
+            Instruction::AddReg(..)
+                | Instruction::Sub(..)
+                | Instruction::Shr(..)
+                | Instruction::Subn(..)
+                | Instruction::Shl(..)
+        );
+        if sets_flag && !flag_read_in_place(&instruction) {
+            // 8XYF overwrites its own flag with the sum
+            let overwritten = matches!(instruction, Instruction::AddReg(VF, _));
+            if overwritten || !live {
+                dead.insert(pc);
+            }
+        }
+        if writes_vf(&instruction) {
+            live = false;
+        }
+        if reads_vf(&instruction) || may_leave(&instruction) {
+            live = true;
+        }
+    }
+    dead
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(instructions: &[Instruction]) -> BasicBlock {
+        let instructions = instructions.iter().enumerate().map(|(n, &instruction)| (0x200 + 2 * n as u16, instruction));
+        BasicBlock { start: 0x200, instructions: instructions.collect() }
+    }
+
+    #[test]
+    fn flags_overwritten_before_use_are_dead() {
+        let flags = dead_flags(&block(&[Instruction::AddReg(1, 2), Instruction::LdByte(VF, 0)]));
+        assert_eq!(flags.into_iter().collect::<Vec<_>>(), vec![0x200]);
+    }
+
+    #[test]
+    fn flags_are_live_where_the_block_may_be_left() {
+        for leave in [Instruction::Unknown(0x8008), Instruction::Store(0), Instruction::Drw(0, 1, 1)] {
+            let flags = dead_flags(&block(&[Instruction::AddReg(1, 2), leave, Instruction::LdByte(VF, 0)]));
+            assert!(flags.is_empty(), "{}", leave);
+        }
+    }
+}
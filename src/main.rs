@@ -1,3 +1,510 @@
-fn main() {
-    println!("Hello, world!");
+/**
+ * main.rs
+ *  command line front end for the retargeter.
+ *
+ *  Usage: chip8_retargeter_llvm <command> <input> [-o <output>] [--load-address <addr>] [--quirks <preset>]
+ *                               [build flags]
+ *
+ *  Commands: asm        assemble <input> into a ROM, needs -o
+ *            disasm     write an assembler listing of a ROM
+ *            cfg        list the blocks, edges and subroutines of a ROM, or
+ *                       with --format dot write them as a Graphviz graph.
+ *                       With --trace <trace.txt>, from difftest's trace
+ *                       tool, recovery also follows what the trace saw.
+ *            analyze    report what every byte of a ROM is for, which
+ *                       subroutines become native functions and what I
+ *                       holds wherever memory is used. With --trace it
+ *                       also reports the code coverage of the trace.
+ *            emit-ir    write the lifted IR
+ *            emit-c     write the ROM translated into C
+ *            emit-wasm  write the ROM translated into WebAssembly, needs -o
+ *            build      compile the ROM to a native executable, needs -o
+ *            run        build the ROM into a temporary executable, or -o,
+ *                       and run it
+ *
+ *  Build flags, for build and run only: -O0|-O1|-O2|-O3, --target <triple>,
+ *  --runtime <libchip8_rt.a>, --no-sdl and -g. With -g, debug info is added
+ *  and the listing it refers to is written next to the output as
+ *  <output>.lst. build and run need the llvm feature, everything else works
+ *  without LLVM.
+ *
+ *  Programs are always loaded at 0x200 and run with the modern quirks.
+ *  --load-address refuses any other address, and --quirks takes the
+ *  emulator's preset names but refuses all but modern, so that scripts
+ *  fail loudly instead of getting something they did not ask for. The
+ *  emulator runs programs that need the other presets.
+ *  Usage errors exit with 2, anything else that goes wrong, such as a
+ *  malformed ROM, with 1. run exits with the program's own exit code.
+ */
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+#[cfg(feature = "llvm")]
+use inkwell::context::Context;
+#[cfg(feature = "llvm")]
+use inkwell::OptimizationLevel;
+
+use chip8_retargeter_llvm::asm::assemble_file;
+use chip8_retargeter_llvm::c;
+use chip8_retargeter_llvm::cfg::Cfg;
+use chip8_retargeter_llvm::classify::{code_cfg, Classification};
+#[cfg(feature = "llvm")]
+use chip8_retargeter_llvm::codegen::{default_runtime, link, optimize, to_llvm, write_object};
+use chip8_retargeter_llvm::constants::Constants;
+#[cfg(feature = "llvm")]
+use chip8_retargeter_llvm::disasm::debug_listing;
+use chip8_retargeter_llvm::disasm::{disassemble, listing};
+use chip8_retargeter_llvm::dot::to_dot;
+use chip8_retargeter_llvm::instruction::Instruction;
+use chip8_retargeter_llvm::lift::lift;
+#[cfg(feature = "llvm")]
+use chip8_retargeter_llvm::lift::lift_with_debug_info;
+use chip8_retargeter_llvm::quirks;
+use chip8_retargeter_llvm::rom::{Rom, LOAD_ADDRESS};
+use chip8_retargeter_llvm::smc::SelfModification;
+use chip8_retargeter_llvm::trace::{coverage, Trace};
+use chip8_retargeter_llvm::wasm;
+
+const USAGE: &str = "Usage: chip8_retargeter_llvm <command> <input> [-o <output>] [--load-address <addr>] \
+[--quirks <preset>] [build flags]
+
+Commands:
+    asm        assemble <input> into a ROM, needs -o
+    disasm     write an assembler listing of a ROM
+    cfg        list the blocks, edges and subroutines of a ROM, --format text|dot, --trace <trace.txt>
+    analyze    report how a ROM uses its bytes, registers and subroutines, --trace <trace.txt>
+    emit-ir    write the lifted IR
+    emit-c     write the ROM translated into C
+    emit-wasm  write the ROM translated into WebAssembly, needs -o
+    build      compile the ROM to a native executable, needs -o
+    run        build the ROM and run it
+
+Build flags, for build and run only:
+    -O0|-O1|-O2|-O3  optimization level, -O2 by default
+    --target <triple>
+    --runtime <libchip8_rt.a>
+    --no-sdl         link against a runtime built without its sdl feature
+    -g               add debug info, and write the listing it refers to as <output>.lst
+
+Output goes to stdout when -o is not given and the command allows it.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Asm,
+    Disasm,
+    Cfg,
+    Analyze,
+    EmitIr,
+    EmitC,
+    EmitWasm,
+    Build,
+    Run,
+}
+
+/**
+ * Everything given on the command line after the command
+ */
+struct Options {
+    input: String,
+    output: Option<String>,
+    // 0 to 3, as in -O0 to -O3
+    optimization: u8,
+    triple: Option<String>,
+    runtime: Option<PathBuf>,
+    sdl: bool,
+    debug: bool,
+    // cfg writes a Graphviz graph instead of text
+    dot: bool,
+    // Trace of the program running, for cfg and analyze
+    trace: Option<String>,
+}
+
+impl Command {
+    fn parse(name: &str) -> Option<Command> {
+        let command = match name {
+            "asm" => Command::Asm,
+            "disasm" => Command::Disasm,
+            "cfg" => Command::Cfg,
+            "analyze" => Command::Analyze,
+            "emit-ir" => Command::EmitIr,
+            "emit-c" => Command::EmitC,
+            "emit-wasm" => Command::EmitWasm,
+            "build" => Command::Build,
+            "run" => Command::Run,
+            _ => return None,
+        };
+        Some(command)
+    }
+
+    /**
+     * needs_output returns true for commands that cannot write to stdout
+     */
+    fn needs_output(self) -> bool {
+        matches!(self, Command::Asm | Command::EmitWasm | Command::Build)
+    }
+
+    /**
+     * compiles returns true for commands that take the build flags
+     */
+    fn compiles(self) -> bool {
+        matches!(self, Command::Build | Command::Run)
+    }
+}
+
+/**
+ * parse_address reads an address written in hex with a 0x prefix, or in
+ *    decimal
+ */
+fn parse_address(text: &str) -> Option<u16> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/**
+ * parse_options reads the arguments after the command. Errors are usage
+ *    errors, described well enough to fix the command line.
+ */
+fn parse_options(command: Command, args: &[String]) -> Result<Options, String> {
+    let mut input = None;
+    let mut options = Options {
+        input: String::new(),
+        output: None,
+        optimization: 2,
+        triple: None,
+        runtime: None,
+        sdl: true,
+        debug: false,
+        dot: false,
+        trace: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "-o" | "--output" => options.output = Some(value()?),
+            "--load-address" => {
+                let text = value()?;
+                let address = parse_address(&text).ok_or_else(|| format!("{} is not an address", text))?;
+                if address != LOAD_ADDRESS {
+                    return Err(format!("Programs can only be loaded at 0x{:03X}, not {}", LOAD_ADDRESS, text));
+                }
+            }
+            "--quirks" => {
+                let preset = value()?;
+                if !quirks::PRESETS.contains(&preset.as_str()) {
+                    let presets = quirks::PRESETS.join(", ");
+                    return Err(format!("Unknown quirks preset {}, expected one of: {}", preset, presets));
+                }
+                if preset != quirks::RECOMPILED {
+                    return Err(format!(
+                        "The recompilers only support the {} quirks, run {} programs with the emulator",
+                        quirks::RECOMPILED, preset
+                    ));
+                }
+            }
+            "--format" if command == Command::Cfg => match value()?.as_str() {
+                "text" => options.dot = false,
+                "dot" => options.dot = true,
+                format => return Err(format!("Unknown format {}, expected text or dot", format)),
+            },
+            "--trace" if command == Command::Cfg || command == Command::Analyze => options.trace = Some(value()?),
+            flag if !command.compiles() && flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("Unknown flag {} for this command", flag));
+            }
+            "-O0" | "-O1" | "-O2" | "-O3" => options.optimization = arg.as_bytes()[2] - b'0',
+            "--target" => options.triple = Some(value()?),
+            "--runtime" => options.runtime = Some(PathBuf::from(value()?)),
+            "--no-sdl" => options.sdl = false,
+            "-g" => options.debug = true,
+            flag if flag.starts_with('-') && flag.len() > 1 => return Err(format!("Unknown flag {}", flag)),
+            _ if input.is_some() => return Err(format!("Unexpected argument {}", arg)),
+            _ => input = Some(arg.clone()),
+        }
+    }
+
+    options.input = input.ok_or("Missing the input file")?;
+    if command.needs_output() && options.output.is_none() {
+        return Err("This command needs -o <output>".to_string());
+    }
+    Ok(options)
+}
+
+/**
+ * write_output writes text to the output file, or stdout if there is none
+ */
+fn write_output(text: &str, output: Option<&str>) -> Result<(), String> {
+    match output {
+        Some(path) => fs::write(path, text).map_err(|e| format!("Cannot write {}: {}", path, e)),
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+/**
+ * asm assembles a source file into a ROM
+ */
+fn asm(options: &Options) -> Result<(), String> {
+    let output = options.output.as_deref().unwrap();
+    let program = assemble_file(&options.input).map_err(|e| e.to_string())?;
+    fs::write(output, program).map_err(|e| format!("Cannot write {}: {}", output, e))
+}
+
+/**
+ * disasm writes an assembler listing of a ROM to output, or stdout
+ */
+fn disasm(options: &Options) -> Result<(), String> {
+    let rom = Rom::from_file(&options.input)?;
+    write_output(&listing(&disassemble(&rom)), options.output.as_deref())
+}
+
+/**
+ * emit_ir lifts a ROM and writes the textual IR to output, or stdout
+ */
+fn emit_ir(options: &Options) -> Result<(), String> {
+    let rom = Rom::from_file(&options.input)?;
+    let module = lift(&rom, &options.input)?;
+    write_output(&module.to_string(), options.output.as_deref())
+}
+
+/**
+ * emit_c translates a ROM into a C file written to output, or stdout
+ */
+fn emit_c(options: &Options) -> Result<(), String> {
+    let rom = Rom::from_file(&options.input)?;
+    write_output(&c::translate(&rom, &options.input), options.output.as_deref())
+}
+
+/**
+ * emit_wasm translates a ROM into a WebAssembly module written to output
+ */
+fn emit_wasm(options: &Options) -> Result<(), String> {
+    let output = options.output.as_deref().unwrap();
+    let rom = Rom::from_file(&options.input)?;
+    fs::write(output, wasm::translate(&rom)).map_err(|e| format!("Cannot write {}: {}", output, e))
+}
+
+/**
+ * print_cfg lists the blocks, edges and subroutines the recompilers work
+ *    from, or those found with a trace, as text or a Graphviz graph
+ */
+fn print_cfg(options: &Options) -> Result<(), String> {
+    let rom = Rom::from_file(&options.input)?;
+    let cfg = match &options.trace {
+        Some(trace) => Cfg::build_traced(&rom, &Trace::from_file(trace)?),
+        None => code_cfg(&rom),
+    };
+    if options.dot {
+        return write_output(&to_dot(&rom, &cfg), options.output.as_deref());
+    }
+    let mut out = String::new();
+    for block in cfg.blocks() {
+        writeln!(out, "block 0x{:03X}..0x{:03X}", block.start, block.end()).unwrap();
+        for edge in cfg.successors(block.start) {
+            writeln!(out, "    {:?} -> 0x{:03X}", edge.kind, edge.to).unwrap();
+        }
+    }
+    for function in cfg.functions() {
+        let blocks: Vec<String> = function.blocks.iter().map(|b| format!("0x{:03X}", b)).collect();
+        writeln!(out, "function 0x{:03X}: {}", function.entry, blocks.join(" ")).unwrap();
+    }
+    for jump in cfg.indirect_jumps() {
+        writeln!(out, "unresolved JP V0 at 0x{:03X}", jump).unwrap();
+    }
+    write_output(&out, options.output.as_deref())
+}
+
+/**
+ * analyze reports what the recompiler makes of a ROM: what each byte is
+ *    used for and which code is also drawn, which subroutines become
+ *    native functions, what I holds at every instruction that uses memory
+ *    and which stores may patch code
+ */
+fn analyze(options: &Options) -> Result<(), String> {
+    let rom = Rom::from_file(&options.input)?;
+    let cfg = code_cfg(&rom);
+    let classification = Classification::analyze(&rom, &cfg);
+    let constants = Constants::analyze(&cfg);
+    let mut out = String::new();
+
+    writeln!(out, "bytes:").unwrap();
+    for range in classification.ranges() {
+        writeln!(out, "    0x{:03X}..0x{:03X}  {}", range.start, range.end, range.kind).unwrap();
+    }
+
+    writeln!(out, "code drawn as sprites:").unwrap();
+    for (start, end) in classification.overlaps() {
+        writeln!(out, "    0x{:03X}..0x{:03X}", start, end).unwrap();
+    }
+
+    writeln!(out, "subroutines:").unwrap();
+    for function in cfg.functions().filter(|function| function.entry != LOAD_ADDRESS) {
+        let native = cfg.well_formed_functions().any(|native| native.entry == function.entry);
+        let lifted = if native { "native" } else { "inlined" };
+        let callers: Vec<String> = function.callers.iter().map(|pc| format!("0x{:03X}", pc)).collect();
+        writeln!(out, "    0x{:03X}  {:<8} called from {}", function.entry, lifted, callers.join(" ")).unwrap();
+    }
+
+    writeln!(out, "memory uses:").unwrap();
+    for &(pc, instruction) in cfg.blocks().flat_map(|block| block.instructions.iter()) {
+        if let Instruction::Drw(..) | Instruction::LdB(_) | Instruction::Store(_) | Instruction::Load(_) = instruction {
+            writeln!(out, "    0x{:03X}  {:<16} I = {}", pc, instruction.to_string(), constants.i(pc)).unwrap();
+        }
+    }
+
+    writeln!(out, "unresolved jumps:").unwrap();
+    for pc in cfg.indirect_jumps() {
+        let jump = rom.get_opcode(pc).unwrap() & 0x0FFF;
+        writeln!(out, "    0x{:03X}  JP V0, 0x{:03X}  V0 = {}", pc, jump, constants.v(pc, 0)).unwrap();
+    }
+
+    writeln!(out, "stores that may patch code:").unwrap();
+    for write in SelfModification::analyze(&cfg).writes() {
+        writeln!(out, "    0x{:03X}  writes 0x{:03X}..0x{:03X}", write.pc, write.start, write.end).unwrap();
+    }
+
+    if let Some(trace) = &options.trace {
+        writeln!(out, "coverage:").unwrap();
+        for line in coverage(&rom, &Trace::from_file(trace)?).to_string().lines() {
+            writeln!(out, "    {}", line).unwrap();
+        }
+    }
+    write_output(&out, options.output.as_deref())
+}
+
+/**
+ * compile compiles a ROM to a native executable at output, with the
+ *    optimization level, target, runtime library and debug info the
+ *    options ask for
+ */
+#[cfg(feature = "llvm")]
+fn compile(options: &Options, output: &Path) -> Result<(), String> {
+    let level = match options.optimization {
+        0 => OptimizationLevel::None,
+        1 => OptimizationLevel::Less,
+        2 => OptimizationLevel::Default,
+        _ => OptimizationLevel::Aggressive,
+    };
+    let runtime = match &options.runtime {
+        Some(runtime) => runtime.clone(),
+        None => default_runtime()?,
+    };
+
+    let rom = Rom::from_file(&options.input)?;
+    let context = Context::create();
+    let lifted = if options.debug {
+        // Debug info points at the listing by its absolute path
+        let listing = env::current_dir().map_err(|e| e.to_string())?.join(with_suffix(output, ".lst"));
+        fs::write(&listing, debug_listing(&rom)).map_err(|e| format!("Cannot write {}: {}", listing.display(), e))?;
+        lift_with_debug_info(&rom, &options.input, &listing)?
+    } else {
+        lift(&rom, &options.input)?
+    };
+    let module = to_llvm(&context, &lifted)?;
+    optimize(&module, level);
+    let object = with_suffix(output, ".o");
+    write_object(&module, options.triple.as_deref(), level, &object)?;
+    let linked = link(&object, &runtime, output, options.sdl);
+    let _ = fs::remove_file(&object);
+    linked
+}
+
+#[cfg(not(feature = "llvm"))]
+fn compile(_options: &Options, _output: &Path) -> Result<(), String> {
+    Err("build and run need the llvm feature, rebuild without --no-default-features".to_string())
+}
+
+/**
+ * with_suffix returns path with suffix added to the end of its file name
+ */
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/**
+ * build compiles a ROM to a native executable
+ */
+fn build(options: &Options) -> Result<(), String> {
+    compile(options, Path::new(options.output.as_deref().unwrap()))
+}
+
+/**
+ * run compiles a ROM and runs it, returning its exit code. Without -o the
+ *    executable is built in the temporary directory and removed afterwards.
+ */
+fn run(options: &Options) -> Result<i32, String> {
+    let output = match &options.output {
+        Some(output) => PathBuf::from(output),
+        None => {
+            let name = Path::new(&options.input).file_stem().and_then(|stem| stem.to_str()).unwrap_or("rom");
+            env::temp_dir().join(format!("{}-{}", name, process::id()))
+        }
+    };
+    compile(options, &output)?;
+
+    // A bare file name would be looked up on PATH
+    let program = if output.is_relative() { Path::new(".").join(&output) } else { output.clone() };
+    let status =
+        process::Command::new(&program).status().map_err(|e| format!("Cannot run {}: {}", program.display(), e));
+    if options.output.is_none() {
+        let _ = fs::remove_file(&output);
+        let _ = fs::remove_file(with_suffix(&output, ".lst"));
+    }
+    // Killed by a signal
+    Ok(status?.code().unwrap_or(1))
+}
+
+/**
+ * execute runs a command and returns the exit code to leave with
+ */
+fn execute(command: Command, options: &Options) -> Result<i32, String> {
+    match command {
+        Command::Asm => asm(options),
+        Command::Disasm => disasm(options),
+        Command::Cfg => print_cfg(options),
+        Command::Analyze => analyze(options),
+        Command::EmitIr => emit_ir(options),
+        Command::EmitC => emit_c(options),
+        Command::EmitWasm => emit_wasm(options),
+        Command::Build => build(options),
+        Command::Run => return run(options),
+    }
+    .map(|_| 0)
+}
+
+pub fn main() {
+    let args: Vec<String> = env::args().collect();
+    let command = match args.get(1).map(|s| s.as_str()) {
+        Some("help") | Some("-h") | Some("--help") => {
+            println!("{}", USAGE);
+            return;
+        }
+        Some(name) => Command::parse(name).ok_or_else(|| format!("Unknown command {}", name)),
+        None => Err("Missing a command".to_string()),
+    };
+    let options = command.and_then(|command| Ok((command, parse_options(command, &args[2..])?)));
+    let (command, options) = match options {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match execute(command, &options) {
+        Ok(code) => process::exit(code),
+        Err(message) => {
+            eprintln!("error: {}", message);
+            process::exit(1);
+        }
+    }
 }
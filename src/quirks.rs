@@ -0,0 +1,12 @@
+/**
+ * quirks.rs
+ *  the names of the emulator's quirk presets, for the command line. The
+ *  emulator's JIT depends on this crate, so this crate cannot use
+ *  emulator::quirks::Quirks::PRESETS itself. difftest, which depends on
+ *  both, has a test that the two lists stay the same.
+ */
+// Every preset name, in the order of Quirks::PRESETS
+pub const PRESETS: &[&str] = &["vip", "chip48", "schip", "modern"];
+
+// The one preset the recompilers implement
+pub const RECOMPILED: &str = "modern";
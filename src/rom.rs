@@ -0,0 +1,78 @@
+/**
+ * rom.rs
+ *  this file loads a chip8 program off disk and gives the rest of the
+ *  retargeter a read only view of it as it would sit in chip8 memory.
+ */
+use std::fs;
+
+// Chip8 programs are loaded directly after the interpreter reserved area
+pub const LOAD_ADDRESS: u16 = 0x200;
+pub const MEMORY_SIZE: usize = 4096;
+
+pub struct Rom {
+    // Raw program bytes as read from the file
+    pub bytes: Vec<u8>,
+}
+
+impl Rom {
+    /**
+     * from_file reads in a .ch8 file and checks that it fits in memory.
+     *    Errors name the file.
+     */
+    pub fn from_file(filename: &str) -> Result<Rom, String> {
+        let bytes = fs::read(filename).map_err(|e| format!("Cannot open {}: {}", filename, e))?;
+        Rom::from_bytes(bytes).map_err(|e| format!("Cannot load {}: {}", filename, e))
+    }
+
+    /**
+     * from_bytes wraps an in memory program, checking that it holds at least
+     *    one instruction and fits in memory
+     */
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Rom, String> {
+        if bytes.is_empty() {
+            return Err("ROM is empty".to_string());
+        }
+        if bytes.len() < 2 {
+            return Err("ROM is 1 byte long, too short to hold an instruction".to_string());
+        }
+        let max = MEMORY_SIZE - LOAD_ADDRESS as usize;
+        if bytes.len() > max {
+            return Err(format!("ROM is {} bytes long, at most {} fit in memory", bytes.len(), max));
+        }
+        Ok(Rom { bytes })
+    }
+
+    /**
+     * end returns the first address after the loaded program
+     */
+    pub fn end(&self) -> u16 {
+        LOAD_ADDRESS + self.bytes.len() as u16
+    }
+
+    /**
+     * contains returns true if an address falls inside the loaded program
+     */
+    pub fn contains(&self, location: u16) -> bool {
+        location >= LOAD_ADDRESS && location < self.end()
+    }
+
+    /**
+     * read_byte returns the program byte at location, if it is in the program
+     */
+    pub fn read_byte(&self, location: u16) -> Option<u8> {
+        if !self.contains(location) {
+            return None;
+        }
+        Some(self.bytes[(location - LOAD_ADDRESS) as usize])
+    }
+
+    /**
+     * get_opcode reads the two bytes at location as a big endian opcode.
+     *    Returns None if the opcode is not fully inside the program.
+     */
+    pub fn get_opcode(&self, location: u16) -> Option<u16> {
+        let high = self.read_byte(location)?;
+        let low = self.read_byte(location + 1)?;
+        Some((high as u16) << 8 | low as u16)
+    }
+}
@@ -0,0 +1,159 @@
+/**
+ * smc.rs
+ *  this file finds self modifying code: FX33 and FX55 stores that may
+ *  land on instructions the recompiler lifted. Generated code guards
+ *  those stores, and the blocks they can reach, so patched instructions
+ *  run through the interpreter instead of stale native code. What I can
+ *  hold at each store comes from the constants module.
+ */
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::cfg::Cfg;
+use crate::constants::Constants;
+use crate::instruction::Instruction;
+use crate::rom::MEMORY_SIZE;
+
+/**
+ * A store and every address it might write
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeWrite {
+    // Address of the FX33 or FX55
+    pub pc: u16,
+    // First and one past the last address that might be written
+    pub start: u16,
+    pub end: u16,
+}
+
+#[derive(Default)]
+pub struct SelfModification {
+    // Stores that might write lifted code, by address
+    writes: BTreeMap<u16, CodeWrite>,
+    // Start addresses of blocks that one of those stores might write
+    written_blocks: BTreeSet<u16>,
+}
+
+/**
+ * store_length returns how many bytes a store instruction writes from I
+ */
+fn store_length(instruction: &Instruction) -> Option<u16> {
+    match *instruction {
+        Instruction::LdB(_) => Some(3),
+        Instruction::Store(x) => Some(x as u16 + 1),
+        _ => None,
+    }
+}
+
+impl SelfModification {
+    /**
+     * analyze finds every store that might write into a lifted block.
+     *    Before any code is patched I holds what the constants module
+     *    works out for unpatched code, so if no store can write code with
+     *    those values none ever does. Once one might, the interpreter may
+     *    hand back to any block with any I, so only what each block sets
+     *    I to itself is known.
+     */
+    pub fn analyze(cfg: &Cfg) -> SelfModification {
+        let found = SelfModification::find(cfg, &Constants::unpatched(cfg));
+        if found.writes.is_empty() {
+            return found;
+        }
+        SelfModification::find(cfg, &Constants::interpreted(cfg))
+    }
+
+    /**
+     * find checks every store against the lifted blocks, with I in the
+     *    range constants gives before it
+     */
+    fn find(cfg: &Cfg, constants: &Constants) -> SelfModification {
+        let code: Vec<(u16, u16)> = cfg.blocks().map(|block| (block.start, block.end())).collect();
+        let mut writes = BTreeMap::new();
+        let mut written_blocks = BTreeSet::new();
+
+        for &(pc, instruction) in cfg.blocks().flat_map(|block| block.instructions.iter()) {
+            let length = match store_length(&instruction) {
+                Some(length) => length,
+                None => continue,
+            };
+            // Only instructions the analysis reached can ever run
+            let i = match constants.before(pc) {
+                Some(registers) => registers.i,
+                None => continue,
+            };
+
+            // Anything past the end of memory faults instead of being written
+            let start = i.low.min(MEMORY_SIZE as u16);
+            let end = i.high.saturating_add(length).min(MEMORY_SIZE as u16);
+            let mut hits_code = false;
+            for &(code_start, code_end) in &code {
+                if start < code_end && code_start < end {
+                    written_blocks.insert(code_start);
+                    hits_code = true;
+                }
+            }
+            if hits_code {
+                writes.insert(pc, CodeWrite { pc, start, end });
+            }
+        }
+
+        SelfModification { writes, written_blocks }
+    }
+
+    /**
+     * writes returns every store that might write lifted code
+     */
+    pub fn writes(&self) -> impl Iterator<Item = &CodeWrite> {
+        self.writes.values()
+    }
+
+    /**
+     * writes_code returns true if the store at pc might write lifted code
+     */
+    pub fn writes_code(&self, pc: u16) -> bool {
+        self.writes.contains_key(&pc)
+    }
+
+    /**
+     * may_be_written returns true if some store might change the block
+     *    starting at an address
+     */
+    pub fn may_be_written(&self, start: u16) -> bool {
+        self.written_blocks.contains(&start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rom::Rom;
+
+    fn analyze(bytes: &[u8]) -> SelfModification {
+        SelfModification::analyze(&Cfg::build(&Rom::from_bytes(bytes.to_vec()).unwrap()))
+    }
+
+    #[test]
+    fn stores_with_i_set_in_another_block_can_miss_code() {
+        // LD I, 0x300; JP 0x204; LD [I], V1; JP 0x200
+        let smc = analyze(&[0xA3, 0x00, 0x12, 0x04, 0xF1, 0x55, 0x12, 0x00]);
+        assert_eq!(smc.writes().count(), 0);
+        assert!(!smc.writes_code(0x204));
+        assert!(!smc.may_be_written(0x200) && !smc.may_be_written(0x204));
+    }
+
+    #[test]
+    fn stores_into_a_lifted_block_are_found() {
+        // LD I, 0x208; LD [I], V1; JP 0x206; JP 0x208; JP 0x200
+        let smc = analyze(&[0xA2, 0x08, 0xF1, 0x55, 0x12, 0x06, 0x12, 0x08, 0x12, 0x00]);
+        assert_eq!(smc.writes().collect::<Vec<_>>(), [&CodeWrite { pc: 0x202, start: 0x208, end: 0x20A }]);
+        assert!(smc.may_be_written(0x208));
+        assert!(!smc.may_be_written(0x200) && !smc.may_be_written(0x206));
+    }
+
+    #[test]
+    fn i_from_other_blocks_is_not_trusted_once_code_may_be_patched() {
+        // LD I, 0x20A; LD [I], V0; JP 0x206; LD I, 0x300; JP 0x20A; LD [I], V1; JP 0x200
+        let smc = analyze(&[0xA2, 0x0A, 0xF0, 0x55, 0x12, 0x06, 0xA3, 0x00, 0x12, 0x0A, 0xF1, 0x55, 0x12, 0x00]);
+        assert!(smc.writes_code(0x202));
+        assert_eq!(smc.writes().last(), Some(&CodeWrite { pc: 0x20A, start: 0, end: MEMORY_SIZE as u16 }));
+    }
+}
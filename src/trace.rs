@@ -0,0 +1,227 @@
+/**
+ * trace.rs
+ *  this file holds what a run of a program showed about its control flow:
+ *  every address an instruction ran at and every target a BNNN jumped to.
+ *  Cfg::build_traced takes those as extra entry points and edges, finding
+ *  code that static reachability misses, and coverage compares the two.
+ *  Traces are written and read as text, one address or jump per line:
+ *
+ *  ```text
+ *      ; comment
+ *      pc 0x200
+ *      jump 0x20C 0x210        a BNNN at 0x20C went to 0x210
+ *  ```
+ */
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+
+use crate::cfg::Cfg;
+use crate::classify::code_cfg;
+use crate::rom::Rom;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    // Address of every instruction that ran
+    executed: BTreeSet<u16>,
+    // Address of each BNNN that ran and where it went
+    jumps: BTreeSet<(u16, u16)>,
+}
+
+/**
+ * What static recovery and a trace each found of a program's code
+ */
+pub struct Coverage {
+    // Instructions the recompilers find without a trace
+    pub found: BTreeSet<u16>,
+    // Instructions found with the trace fed back in
+    pub traced: BTreeSet<u16>,
+    // Instructions that ran, inside the program
+    pub executed: BTreeSet<u16>,
+    // Instructions that ran outside the program, in memory it wrote
+    pub outside: usize,
+    // BNNN targets seen
+    pub jumps: usize,
+}
+
+/**
+ * parse_address reads a 0x prefixed hex address
+ */
+fn parse_address(word: Option<&str>) -> Option<u16> {
+    let hex = word?.strip_prefix("0x")?;
+    u16::from_str_radix(hex, 16).ok()
+}
+
+impl Trace {
+    pub fn new() -> Trace {
+        Trace::default()
+    }
+
+    /**
+     * record notes that an instruction ran at pc
+     */
+    pub fn record(&mut self, pc: u16) {
+        self.executed.insert(pc);
+    }
+
+    /**
+     * record_jump notes that the BNNN at pc jumped to target
+     */
+    pub fn record_jump(&mut self, pc: u16, target: u16) {
+        self.jumps.insert((pc, target));
+    }
+
+    /**
+     * executed returns every address an instruction ran at, in order
+     */
+    pub fn executed(&self) -> impl Iterator<Item = u16> + '_ {
+        self.executed.iter().copied()
+    }
+
+    pub fn ran(&self, pc: u16) -> bool {
+        self.executed.contains(&pc)
+    }
+
+    /**
+     * jumps returns every BNNN address and target seen, in order
+     */
+    pub fn jumps(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.jumps.iter().copied()
+    }
+
+    pub fn from_file(filename: &str) -> Result<Trace, String> {
+        let text = fs::read_to_string(filename).map_err(|e| format!("Cannot open {}: {}", filename, e))?;
+        Trace::parse(&text).map_err(|e| format!("{}:{}", filename, e))
+    }
+
+    /**
+     * parse reads a trace, reporting the line of the first mistake
+     */
+    pub fn parse(text: &str) -> Result<Trace, String> {
+        let mut trace = Trace::new();
+        for (index, line) in text.lines().enumerate() {
+            let code = line.split(';').next().unwrap();
+            let mut words = code.split_whitespace();
+            let invalid = || format!("{}: Invalid trace line {}", index + 1, line.trim());
+            match words.next() {
+                Some("pc") => trace.record(parse_address(words.next()).ok_or_else(invalid)?),
+                Some("jump") => {
+                    let pc = parse_address(words.next()).ok_or_else(invalid)?;
+                    let target = parse_address(words.next()).ok_or_else(invalid)?;
+                    trace.record_jump(pc, target);
+                }
+                Some(_) => return Err(invalid()),
+                None => continue,
+            }
+            if words.next().is_some() {
+                return Err(invalid());
+            }
+        }
+        Ok(trace)
+    }
+}
+
+/**
+ * instructions returns the address of every instruction in cfg
+ */
+fn instructions(cfg: &Cfg) -> BTreeSet<u16> {
+    cfg.blocks().flat_map(|block| block.instructions.iter().map(|&(pc, _)| pc)).collect()
+}
+
+/**
+ * coverage compares the code found in a program with and without a trace
+ */
+pub fn coverage(rom: &Rom, trace: &Trace) -> Coverage {
+    let executed: BTreeSet<u16> = trace.executed().filter(|&pc| rom.get_opcode(pc).is_some()).collect();
+    Coverage {
+        found: instructions(&code_cfg(rom)),
+        traced: instructions(&Cfg::build_traced(rom, trace)),
+        outside: trace.executed.len() - executed.len(),
+        executed,
+        jumps: trace.jumps.len(),
+    }
+}
+
+/**
+ * runs writes addresses as ranges of consecutive instructions
+ */
+fn runs(addresses: &BTreeSet<u16>) -> Vec<String> {
+    let mut runs: Vec<(u16, u16)> = Vec::new();
+    for &pc in addresses {
+        match runs.last_mut() {
+            Some(run) if run.1 + 2 == pc => run.1 = pc,
+            _ => runs.push((pc, pc)),
+        }
+    }
+    runs.iter().map(|&(first, last)| format!("0x{:03X}..0x{:03X}", first, last + 2)).collect()
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for pc in &self.executed {
+            writeln!(f, "pc 0x{:03X}", pc)?;
+        }
+        for (pc, target) in &self.jumps {
+            writeln!(f, "jump 0x{:03X} 0x{:03X}", pc, target)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let missed: BTreeSet<u16> = self.executed.difference(&self.found).copied().collect();
+        let unseen = self.found.difference(&self.executed).count();
+        writeln!(f, "instructions found statically:  {}", self.found.len())?;
+        writeln!(f, "instructions found with trace:  {}", self.traced.len())?;
+        writeln!(f, "instructions executed:          {}", self.executed.len())?;
+        writeln!(f, "    also found statically:      {}", self.executed.len() - missed.len())?;
+        writeln!(f, "    only found by tracing:      {}", missed.len())?;
+        writeln!(f, "    outside the program:        {}", self.outside)?;
+        writeln!(f, "found statically, never run:    {}", unseen)?;
+        writeln!(f, "BNNN targets seen:              {}", self.jumps)?;
+        for run in runs(&missed) {
+            writeln!(f, "only found by tracing: {}", run)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::EdgeKind;
+
+    // LD V0, 2; JP V0, 0x206; JP 0x204; 0x0000; LD V1, 1; JP 0x20A
+    const PROGRAM: &[u8] = &[0x60, 0x02, 0xB2, 0x06, 0x12, 0x04, 0x00, 0x00, 0x61, 0x01, 0x12, 0x0A];
+
+    // The BNNN going to 0x208, and a jump into memory the program wrote
+    const TRACE: &str = "pc 0x200\npc 0x202\npc 0x208\npc 0x20A\npc 0x300 ; outside\njump 0x202 0x208\n";
+
+    #[test]
+    fn traced_bnnn_targets_are_entries() {
+        let rom = Rom::from_bytes(PROGRAM.to_vec()).unwrap();
+        assert!(Cfg::build(&rom).block(0x208).is_none());
+        let cfg = Cfg::build_traced(&rom, &Trace::parse(TRACE).unwrap());
+        let starts: Vec<u16> = cfg.blocks().map(|block| block.start).collect();
+        assert_eq!(starts, [0x200, 0x208, 0x20A]);
+        let edges: Vec<(u16, EdgeKind)> = cfg.predecessors(0x208).map(|edge| (edge.from, edge.kind)).collect();
+        assert_eq!(edges, [(0x200, EdgeKind::Indirect)]);
+        assert_eq!(cfg.indirect_jumps().collect::<Vec<_>>(), [0x202]);
+    }
+
+    #[test]
+    fn coverage_counts_what_static_recovery_and_the_trace_found() {
+        let rom = Rom::from_bytes(PROGRAM.to_vec()).unwrap();
+        let coverage = coverage(&rom, &Trace::parse(TRACE).unwrap());
+        assert_eq!(coverage.found.iter().copied().collect::<Vec<_>>(), [0x200, 0x202]);
+        assert_eq!(coverage.traced.iter().copied().collect::<Vec<_>>(), [0x200, 0x202, 0x208, 0x20A]);
+        assert_eq!(coverage.executed.len(), 4);
+        assert_eq!((coverage.outside, coverage.jumps), (1, 1));
+        let report = coverage.to_string();
+        assert!(report.contains("    also found statically:      2\n"), "{}", report);
+        assert!(report.contains("    only found by tracing:      2\n"), "{}", report);
+        assert!(report.contains("found statically, never run:    0\n"), "{}", report);
+        assert!(report.ends_with("only found by tracing: 0x208..0x20C\n"), "{}", report);
+    }
+}
@@ -0,0 +1,1110 @@
+/**
+ * wasm.rs
+ *  this file translates a chip8 program straight into a WebAssembly
+ *  module, without going through LLVM. Every block the cfg module recovers
+ *  becomes a wasm block inside run_frame, reached through a br_table on pc,
+ *  with the same semantics as CPU::execute_next_opcode.
+ *
+ *  The module exports its memory, init and run_frame. init loads the font
+ *  and program and resets the registers, run_frame runs from pc until the
+ *  host's tick hook ends the frame at the end of a block, and returns 1 if
+ *  the program can carry on or 0 once it has stopped. Everything touching
+ *  the host is imported from "chip8":
+ *      cls()                                   clear the screen
+ *      draw(x, y, addr, n) -> collision        draw n sprite bytes at addr
+ *      key_pressed(key) -> pressed             key query
+ *      random() -> byte                        RND
+ *      tick(sound) -> running                  after every instruction, once
+ *                                              the timers have been updated
+ *      bad_address(start), bad_stack()         faults, with pc in memory
+ *      unresolved(pc)                          nothing was translated at pc
+ *  There is no interpreter to fall back on, so a fault, a BNNN to code
+ *  that was not found or a store over translated code stops the program.
+ *
+ *  Chip8 memory sits at address 0 of the wasm memory, followed by the
+ *  registers at the offsets below. Numbers are little endian.
+ */
+use std::collections::BTreeMap;
+
+use crate::cfg::{BasicBlock as Block, Cfg};
+use crate::classify::code_cfg;
+use crate::instruction::Instruction;
+use crate::rom::{Rom, LOAD_ADDRESS, MEMORY_SIZE};
+use crate::smc::SelfModification;
+
+// Where the registers live in the wasm memory
+pub const STATE_V: u32 = 0x1000;
+pub const STATE_I: u32 = 0x1010;
+pub const STATE_PC: u32 = 0x1012;
+pub const STATE_SP: u32 = 0x1014;
+pub const STATE_DT: u32 = 0x1016;
+pub const STATE_ST: u32 = 0x1017;
+pub const STATE_STACK: u32 = 0x1018;
+// Instructions run so far times TIMER_HZ, modulo INSTRUCTIONS_PER_SECOND
+const STATE_PHASE: u32 = 0x1038;
+const STATE_END: u32 = 0x103A;
+// Pristine copy of memory init starts from
+const IMAGE: u32 = 0x2000;
+
+// Timers count down TIMER_HZ times every INSTRUCTIONS_PER_SECOND
+// instructions, on the same instructions as the emulator's timers_due
+const INSTRUCTIONS_PER_SECOND: i32 = 500;
+const TIMER_HZ: i32 = 60;
+
+const STACK_SIZE: i32 = 16;
+const LAST_ADDRESS: i32 = 0xFFF;
+
+const FONT: [u8; 80] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+    0x20, 0x60, 0x20, 0x20, 0x70, // 1
+    0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+    0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+    0x90, 0x90, 0xF0, 0x10, 0x10, // 4
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+    0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+    0xF0, 0x10, 0x20, 0x40, 0x40, // 7
+    0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+    0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+    0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+    0xE0, 0x90, 0xE0, 0x90, 0xE0, // B
+    0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+    0xE0, 0x90, 0x90, 0x90, 0xE0, // D
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+    0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+];
+
+// Section ids
+const SECTION_TYPE: u8 = 1;
+const SECTION_IMPORT: u8 = 2;
+const SECTION_FUNCTION: u8 = 3;
+const SECTION_MEMORY: u8 = 5;
+const SECTION_EXPORT: u8 = 7;
+const SECTION_CODE: u8 = 10;
+const SECTION_DATA: u8 = 11;
+
+const TYPE_I32: u8 = 0x7F;
+const TYPE_FUNCTION: u8 = 0x60;
+const BLOCK_EMPTY: u8 = 0x40;
+const EXPORT_FUNCTION: u8 = 0x00;
+const EXPORT_MEMORY: u8 = 0x02;
+
+// Opcodes
+const UNREACHABLE: u8 = 0x00;
+const BLOCK: u8 = 0x02;
+const LOOP: u8 = 0x03;
+const IF: u8 = 0x04;
+const END: u8 = 0x0B;
+const BR: u8 = 0x0C;
+const BR_IF: u8 = 0x0D;
+const BR_TABLE: u8 = 0x0E;
+const RETURN: u8 = 0x0F;
+const CALL: u8 = 0x10;
+const DROP: u8 = 0x1A;
+const SELECT: u8 = 0x1B;
+const LOCAL_GET: u8 = 0x20;
+const LOCAL_SET: u8 = 0x21;
+const LOCAL_TEE: u8 = 0x22;
+const I32_LOAD8_U: u8 = 0x2D;
+const I32_LOAD16_U: u8 = 0x2F;
+const I32_STORE8: u8 = 0x3A;
+const I32_STORE16: u8 = 0x3B;
+const I32_CONST: u8 = 0x41;
+const I32_EQZ: u8 = 0x45;
+const I32_EQ: u8 = 0x46;
+const I32_NE: u8 = 0x47;
+const I32_LT_U: u8 = 0x49;
+const I32_GT_U: u8 = 0x4B;
+const I32_GE_U: u8 = 0x4F;
+const I32_ADD: u8 = 0x6A;
+const I32_SUB: u8 = 0x6B;
+const I32_MUL: u8 = 0x6C;
+const I32_DIV_U: u8 = 0x6E;
+const I32_REM_U: u8 = 0x70;
+const I32_AND: u8 = 0x71;
+const I32_OR: u8 = 0x72;
+const I32_XOR: u8 = 0x73;
+const I32_SHL: u8 = 0x74;
+const I32_SHR_U: u8 = 0x76;
+const BULK: u8 = 0xFC;
+const MEMORY_COPY: u32 = 10;
+const MEMORY_FILL: u32 = 11;
+
+// Function indexes, imports first
+const CLS: u32 = 0;
+const DRAW: u32 = 1;
+const KEY_PRESSED: u32 = 2;
+const RANDOM: u32 = 3;
+const TICK: u32 = 4;
+const BAD_ADDRESS: u32 = 5;
+const BAD_STACK: u32 = 6;
+const UNRESOLVED: u32 = 7;
+const TIMERS: u32 = 8;
+const INIT: u32 = 9;
+const RUN_FRAME: u32 = 10;
+
+// Locals of run_frame
+const LOCAL_A: u32 = 0;
+const LOCAL_KEY: u32 = 1;
+
+/**
+ * write_u32 writes an unsigned LEB128 number
+ */
+fn write_u32(out: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/**
+ * write_i32 writes a signed LEB128 number
+ */
+fn write_i32(out: &mut Vec<u8>, mut value: i32) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn write_name(out: &mut Vec<u8>, name: &str) {
+    write_u32(out, name.len() as u32);
+    out.extend_from_slice(name.as_bytes());
+}
+
+/**
+ * write_vec writes a count followed by already encoded items
+ */
+fn write_vec(out: &mut Vec<u8>, items: &[Vec<u8>]) {
+    write_u32(out, items.len() as u32);
+    for item in items {
+        out.extend_from_slice(item);
+    }
+}
+
+fn write_section(out: &mut Vec<u8>, id: u8, contents: &[u8]) {
+    out.push(id);
+    write_u32(out, contents.len() as u32);
+    out.extend_from_slice(contents);
+}
+
+/**
+ * A label a branch can target, valid until the block that made it ends
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Label(usize);
+
+/**
+ * A function body, built one instruction at a time. Keeps track of the
+ * blocks it is inside so branches can name their target.
+ */
+struct Body {
+    locals: u32,
+    code: Vec<u8>,
+    // Label of every block, loop and if the code is inside, innermost last
+    labels: Vec<Label>,
+    next_label: usize,
+}
+
+impl Body {
+    fn new(locals: u32) -> Body {
+        Body { locals, code: Vec::new(), labels: Vec::new(), next_label: 0 }
+    }
+
+    fn op(&mut self, opcode: u8) {
+        self.code.push(opcode);
+    }
+
+    fn open(&mut self, opcode: u8) -> Label {
+        self.code.push(opcode);
+        self.code.push(BLOCK_EMPTY);
+        let label = Label(self.next_label);
+        self.next_label += 1;
+        self.labels.push(label);
+        label
+    }
+
+    fn block(&mut self) -> Label {
+        self.open(BLOCK)
+    }
+
+    fn loop_(&mut self) -> Label {
+        self.open(LOOP)
+    }
+
+    /**
+     * if_ runs the code up to the matching end if the top of the stack is
+     *    not zero
+     */
+    fn if_(&mut self) -> Label {
+        self.open(IF)
+    }
+
+    fn end(&mut self) {
+        self.labels.pop().expect("end outside of a block");
+        self.code.push(END);
+    }
+
+    /**
+     * depth returns how many blocks out a branch to label has to go
+     */
+    fn depth(&self, label: Label) -> u32 {
+        let index = self.labels.iter().rposition(|&l| l == label).expect("branch to a block that has ended");
+        (self.labels.len() - 1 - index) as u32
+    }
+
+    fn br(&mut self, label: Label) {
+        let depth = self.depth(label);
+        self.code.push(BR);
+        write_u32(&mut self.code, depth);
+    }
+
+    fn br_if(&mut self, label: Label) {
+        let depth = self.depth(label);
+        self.code.push(BR_IF);
+        write_u32(&mut self.code, depth);
+    }
+
+    fn br_table(&mut self, labels: &[Label], default: Label) {
+        let depths: Vec<u32> = labels.iter().map(|&label| self.depth(label)).collect();
+        let default = self.depth(default);
+        self.code.push(BR_TABLE);
+        write_u32(&mut self.code, depths.len() as u32);
+        for depth in depths {
+            write_u32(&mut self.code, depth);
+        }
+        write_u32(&mut self.code, default);
+    }
+
+    fn i32_const(&mut self, value: i32) {
+        self.code.push(I32_CONST);
+        write_i32(&mut self.code, value);
+    }
+
+    fn local(&mut self, opcode: u8, index: u32) {
+        self.code.push(opcode);
+        write_u32(&mut self.code, index);
+    }
+
+    fn call(&mut self, function: u32) {
+        self.code.push(CALL);
+        write_u32(&mut self.code, function);
+    }
+
+    /**
+     * memory is a load or store at the address on the stack plus offset
+     */
+    fn memory(&mut self, opcode: u8, offset: u32) {
+        let align = if opcode == I32_LOAD16_U || opcode == I32_STORE16 { 1 } else { 0 };
+        self.code.push(opcode);
+        write_u32(&mut self.code, align);
+        write_u32(&mut self.code, offset);
+    }
+
+    fn bulk(&mut self, opcode: u32) {
+        self.code.push(BULK);
+        write_u32(&mut self.code, opcode);
+        // Memory 0, and for memory.copy memory 0 again
+        self.code.push(0);
+        if opcode == MEMORY_COPY {
+            self.code.push(0);
+        }
+    }
+
+    /**
+     * encode finishes the body as it goes in the code section
+     */
+    fn encode(mut self) -> Vec<u8> {
+        assert!(self.labels.is_empty(), "function ends inside a block");
+        self.code.push(END);
+        let mut body = Vec::new();
+        if self.locals > 0 {
+            write_u32(&mut body, 1);
+            write_u32(&mut body, self.locals);
+            body.push(TYPE_I32);
+        } else {
+            write_u32(&mut body, 0);
+        }
+        body.extend_from_slice(&self.code);
+        let mut out = Vec::new();
+        write_u32(&mut out, body.len() as u32);
+        out.extend_from_slice(&body);
+        out
+    }
+}
+
+/**
+ * translate compiles a whole program into a WebAssembly module
+ */
+pub fn translate(rom: &Rom) -> Vec<u8> {
+    let cfg = code_cfg(rom);
+    let smc = SelfModification::analyze(&cfg);
+
+    let mut types = Vec::new();
+    let imports = [
+        ("cls", type_index(&mut types, 0, false)),
+        ("draw", type_index(&mut types, 4, true)),
+        ("key_pressed", type_index(&mut types, 1, true)),
+        ("random", type_index(&mut types, 0, true)),
+        ("tick", type_index(&mut types, 1, true)),
+        ("bad_address", type_index(&mut types, 1, false)),
+        ("bad_stack", type_index(&mut types, 0, false)),
+        ("unresolved", type_index(&mut types, 1, false)),
+    ];
+    // timers, init and run_frame
+    let functions = [
+        type_index(&mut types, 0, true),
+        type_index(&mut types, 0, false),
+        type_index(&mut types, 0, true),
+    ];
+
+    let mut out = b"\0asm".to_vec();
+    out.extend_from_slice(&[1, 0, 0, 0]);
+
+    let mut section = Vec::new();
+    write_vec(&mut section, &types);
+    write_section(&mut out, SECTION_TYPE, &section);
+
+    let imports: Vec<Vec<u8>> = imports
+        .iter()
+        .map(|&(name, ty)| {
+            let mut import = Vec::new();
+            write_name(&mut import, "chip8");
+            write_name(&mut import, name);
+            import.push(EXPORT_FUNCTION);
+            write_u32(&mut import, ty);
+            import
+        })
+        .collect();
+    let mut section = Vec::new();
+    write_vec(&mut section, &imports);
+    write_section(&mut out, SECTION_IMPORT, &section);
+
+    let mut section = Vec::new();
+    write_u32(&mut section, functions.len() as u32);
+    for &ty in functions.iter() {
+        write_u32(&mut section, ty);
+    }
+    write_section(&mut out, SECTION_FUNCTION, &section);
+
+    // One page with no maximum
+    write_section(&mut out, SECTION_MEMORY, &[1, 0, 1]);
+
+    let exports = [
+        ("memory", EXPORT_MEMORY, 0),
+        ("init", EXPORT_FUNCTION, INIT),
+        ("run_frame", EXPORT_FUNCTION, RUN_FRAME),
+    ];
+    let exports: Vec<Vec<u8>> = exports
+        .iter()
+        .map(|&(name, kind, index)| {
+            let mut export = Vec::new();
+            write_name(&mut export, name);
+            export.push(kind);
+            write_u32(&mut export, index);
+            export
+        })
+        .collect();
+    let mut section = Vec::new();
+    write_vec(&mut section, &exports);
+    write_section(&mut out, SECTION_EXPORT, &section);
+
+    let bodies = [build_timers(), build_init(), build_run_frame(rom, &cfg, &smc)];
+    let mut section = Vec::new();
+    write_vec(&mut section, &bodies);
+    write_section(&mut out, SECTION_CODE, &section);
+
+    let segments = [(IMAGE, &FONT[..]), (IMAGE + LOAD_ADDRESS as u32, &rom.bytes[..])];
+    let segments: Vec<Vec<u8>> = segments
+        .iter()
+        .map(|&(addr, bytes)| {
+            // Active segment in memory 0, placed by a constant expression
+            let mut segment = vec![0, I32_CONST];
+            write_i32(&mut segment, addr as i32);
+            segment.push(END);
+            write_u32(&mut segment, bytes.len() as u32);
+            segment.extend_from_slice(bytes);
+            segment
+        })
+        .collect();
+    let mut section = Vec::new();
+    write_vec(&mut section, &segments);
+    write_section(&mut out, SECTION_DATA, &section);
+    out
+}
+
+/**
+ * type_index returns the index of a function type taking params i32s and
+ *    maybe returning one, adding it to types the first time
+ */
+fn type_index(types: &mut Vec<Vec<u8>>, params: u32, result: bool) -> u32 {
+    let mut ty = vec![TYPE_FUNCTION];
+    write_u32(&mut ty, params);
+    ty.extend((0..params).map(|_| TYPE_I32));
+    write_u32(&mut ty, result as u32);
+    if result {
+        ty.push(TYPE_I32);
+    }
+    match types.iter().position(|t| *t == ty) {
+        Some(index) => index as u32,
+        None => {
+            types.push(ty);
+            types.len() as u32 - 1
+        }
+    }
+}
+
+/**
+ * build_timers builds the function run after every instruction: it counts
+ *    down DT and ST when CPU::update_timers would, then hands the host
+ *    whether the sound timer counted down and returns what tick says
+ */
+fn build_timers() -> Vec<u8> {
+    // Locals: the last timer looked at, then the phase
+    let mut body = Body::new(2);
+    // Each instruction is TIMER_HZ further through a second, and the
+    // timers count down each time a whole second has passed
+    body.i32_const(0);
+    body.memory(I32_LOAD16_U, STATE_PHASE);
+    body.i32_const(TIMER_HZ);
+    body.op(I32_ADD);
+    body.local(LOCAL_TEE, 1);
+    body.i32_const(INSTRUCTIONS_PER_SECOND);
+    body.op(I32_GE_U);
+    body.if_();
+    body.local(LOCAL_GET, 1);
+    body.i32_const(INSTRUCTIONS_PER_SECOND);
+    body.op(I32_SUB);
+    body.local(LOCAL_SET, 1);
+    for timer in [STATE_DT, STATE_ST] {
+        body.i32_const(0);
+        body.memory(I32_LOAD8_U, timer);
+        body.local(LOCAL_TEE, 0);
+        body.if_();
+        body.i32_const(0);
+        body.local(LOCAL_GET, 0);
+        body.i32_const(1);
+        body.op(I32_SUB);
+        body.memory(I32_STORE8, timer);
+        body.end();
+    }
+    body.end();
+    body.i32_const(0);
+    body.local(LOCAL_GET, 1);
+    body.memory(I32_STORE16, STATE_PHASE);
+    body.local(LOCAL_GET, 0);
+    body.i32_const(0);
+    body.op(I32_NE);
+    body.call(TICK);
+    body.encode()
+}
+
+/**
+ * build_init builds init, which copies the pristine memory image over
+ *    memory and resets the registers with pc at the load address
+ */
+fn build_init() -> Vec<u8> {
+    let mut body = Body::new(0);
+    body.i32_const(0);
+    body.i32_const(IMAGE as i32);
+    body.i32_const(MEMORY_SIZE as i32);
+    body.bulk(MEMORY_COPY);
+    body.i32_const(STATE_V as i32);
+    body.i32_const(0);
+    body.i32_const((STATE_END - STATE_V) as i32);
+    body.bulk(MEMORY_FILL);
+    body.i32_const(0);
+    body.i32_const(LOAD_ADDRESS as i32);
+    body.memory(I32_STORE16, STATE_PC);
+    body.encode()
+}
+
+/**
+ * split_blocks splits the recovered blocks so every FX0A starts a block
+ *    of its own, since waiting for a key may end the frame there. Each
+ *    piece keeps the start of the block it came from.
+ */
+fn split_blocks(cfg: &Cfg) -> Vec<(u16, Block)> {
+    let mut pieces = Vec::new();
+    for block in cfg.blocks() {
+        let mut piece = Block { start: block.start, instructions: Vec::new() };
+        for &(pc, instruction) in block.instructions.iter() {
+            if matches!(instruction, Instruction::LdVxK(_)) && !piece.instructions.is_empty() {
+                pieces.push((block.start, piece));
+                piece = Block { start: pc, instructions: Vec::new() };
+            }
+            piece.instructions.push((pc, instruction));
+        }
+        pieces.push((block.start, piece));
+    }
+    pieces
+}
+
+/**
+ * build_run_frame builds run_frame: a loop around a br_table on pc that
+ *    picks the block to run, with one wasm block per chip8 block
+ */
+fn build_run_frame(rom: &Rom, cfg: &Cfg, smc: &SelfModification) -> Vec<u8> {
+    let pieces = split_blocks(cfg);
+    let mut translator = Translator {
+        rom,
+        smc,
+        body: Body::new(2),
+        dispatch: Label(0),
+        blocks: BTreeMap::new(),
+    };
+    let body = &mut translator.body;
+    translator.dispatch = body.loop_();
+    let unresolved = body.block();
+    // The first block ends innermost, so it is opened last
+    let labels: Vec<Label> = pieces.iter().rev().map(|_| body.block()).collect();
+    for (label, (_, piece)) in labels.iter().rev().zip(pieces.iter()) {
+        translator.blocks.insert(piece.start, *label);
+    }
+
+    let last = *translator.blocks.keys().next_back().unwrap_or(&0);
+    let table: Vec<Label> =
+        (0..=last).map(|pc| *translator.blocks.get(&pc).unwrap_or(&unresolved)).collect();
+    let body = &mut translator.body;
+    body.i32_const(0);
+    body.memory(I32_LOAD16_U, STATE_PC);
+    body.br_table(&table, unresolved);
+
+    for (start, piece) in pieces.iter() {
+        translator.body.end();
+        translator.translate_block(*start, piece);
+    }
+
+    // Nothing was translated at pc, let the host decide what to do
+    let body = &mut translator.body;
+    body.end();
+    body.i32_const(0);
+    body.memory(I32_LOAD16_U, STATE_PC);
+    body.call(UNRESOLVED);
+    body.i32_const(0);
+    body.op(RETURN);
+    body.end();
+    body.op(UNREACHABLE);
+    translator.body.encode()
+}
+
+struct Translator<'a> {
+    rom: &'a Rom,
+    smc: &'a SelfModification,
+    // Body of run_frame
+    body: Body,
+    // Loop around the br_table on pc
+    dispatch: Label,
+    // wasm block for every chip8 block start address
+    blocks: BTreeMap<u16, Label>,
+}
+
+impl<'a> Translator<'a> {
+    /**
+     * translate_block emits one block. original is the start of the block
+     *    the cfg module recovered, which the smc module knows it by.
+     */
+    fn translate_block(&mut self, original: u16, block: &Block) {
+        let patchable = self.smc.may_be_written(original);
+        if patchable {
+            self.check_unpatched(block.start, block.end(), 0);
+        }
+        let last = block.instructions.len() - 1;
+        for (index, &(pc, instruction)) in block.instructions.iter().enumerate() {
+            let ended = self.translate_instruction(pc, instruction);
+            if !ended {
+                if patchable && self.smc.writes_code(pc) && index < last {
+                    self.check_unpatched(pc + 2, block.end(), 1);
+                }
+                if index < last {
+                    self.body.call(TIMERS);
+                    self.body.op(DROP);
+                }
+            }
+        }
+        if block.falls_through() {
+            self.set_pc(block.end());
+            self.end_block();
+        }
+    }
+
+    /**
+     * check_unpatched compares memory from start to end against the
+     *    program, and stops with pc at start if any of it changed. ticks
+     *    is how many instructions have run that have not been ticked.
+     */
+    fn check_unpatched(&mut self, start: u16, end: u16, ticks: u32) {
+        let body = &mut self.body;
+        body.i32_const(0);
+        for addr in start..end {
+            body.i32_const(0);
+            body.memory(I32_LOAD8_U, addr as u32);
+            body.i32_const(self.rom.read_byte(addr).unwrap() as i32);
+            body.op(I32_NE);
+            body.op(I32_OR);
+        }
+        body.if_();
+        for _ in 0..ticks {
+            body.call(TIMERS);
+            body.op(DROP);
+        }
+        self.set_pc(start);
+        let body = &mut self.body;
+        body.i32_const(start as i32);
+        body.call(UNRESOLVED);
+        body.i32_const(0);
+        body.op(RETURN);
+        body.end();
+    }
+
+    /**
+     * translate_instruction emits one instruction. Returns true if it
+     *    ended the block itself.
+     */
+    fn translate_instruction(&mut self, pc: u16, instruction: Instruction) -> bool {
+        match instruction {
+            Instruction::Cls => self.body.call(CLS),
+            Instruction::Ret => {
+                // RET with an empty stack is a fault rather than an underflow
+                self.body.i32_const(0);
+                self.body.memory(I32_LOAD16_U, STATE_SP);
+                self.body.local(LOCAL_TEE, LOCAL_A);
+                self.body.op(I32_EQZ);
+                self.fault_if(pc, BAD_STACK, false);
+                let body = &mut self.body;
+                body.i32_const(0);
+                body.local(LOCAL_GET, LOCAL_A);
+                body.i32_const(1);
+                body.op(I32_SHL);
+                body.memory(I32_LOAD16_U, STATE_STACK);
+                body.i32_const(2);
+                body.op(I32_ADD);
+                body.memory(I32_STORE16, STATE_PC);
+                body.i32_const(0);
+                body.local(LOCAL_GET, LOCAL_A);
+                body.i32_const(1);
+                body.op(I32_SUB);
+                body.memory(I32_STORE16, STATE_SP);
+                self.end_block();
+                return true;
+            }
+            Instruction::Sys(_)
+            | Instruction::ScrollDown(_)
+            | Instruction::ScrollRight
+            | Instruction::ScrollLeft
+            | Instruction::Exit
+            | Instruction::Low
+            | Instruction::High => {
+                // Ignored by the interpreter, it just moves on. Outside
+                // SUPER-CHIP mode that includes SUPER-CHIP's 00CN to 00FF.
+            }
+            Instruction::Unknown(_) | Instruction::LdHf(_) | Instruction::StoreRpl(_) | Instruction::LoadRpl(_) => {
+                // A fault in the interpreter, with no interpreter to report it
+                self.set_pc(pc);
+                let body = &mut self.body;
+                body.i32_const(pc as i32);
+                body.call(UNRESOLVED);
+                body.i32_const(0);
+                body.op(RETURN);
+                return true;
+            }
+            Instruction::Jp(addr) => {
+                self.set_pc(addr);
+                self.end_block();
+                return true;
+            }
+            Instruction::Call(addr) => {
+                // Stack slot 0 is never used, the interpreter increments sp first
+                self.body.i32_const(0);
+                self.body.memory(I32_LOAD16_U, STATE_SP);
+                self.body.i32_const(1);
+                self.body.op(I32_ADD);
+                self.body.local(LOCAL_TEE, LOCAL_A);
+                self.body.i32_const(STACK_SIZE);
+                self.body.op(I32_GE_U);
+                self.fault_if(pc, BAD_STACK, false);
+                let body = &mut self.body;
+                body.i32_const(0);
+                body.local(LOCAL_GET, LOCAL_A);
+                body.memory(I32_STORE16, STATE_SP);
+                body.local(LOCAL_GET, LOCAL_A);
+                body.i32_const(1);
+                body.op(I32_SHL);
+                body.i32_const(pc as i32);
+                body.memory(I32_STORE16, STATE_STACK);
+                self.set_pc(addr);
+                self.end_block();
+                return true;
+            }
+            Instruction::SeByte(x, byte) => {
+                self.load_v(x);
+                self.body.i32_const(byte as i32);
+                self.body.op(I32_EQ);
+                self.skip_if(pc);
+                return true;
+            }
+            Instruction::SneByte(x, byte) => {
+                self.load_v(x);
+                self.body.i32_const(byte as i32);
+                self.body.op(I32_NE);
+                self.skip_if(pc);
+                return true;
+            }
+            Instruction::SeReg(x, y) => {
+                self.load_v(x);
+                self.load_v(y);
+                self.body.op(I32_EQ);
+                self.skip_if(pc);
+                return true;
+            }
+            Instruction::SneReg(x, y) => {
+                self.load_v(x);
+                self.load_v(y);
+                self.body.op(I32_NE);
+                self.skip_if(pc);
+                return true;
+            }
+            Instruction::LdByte(x, byte) => {
+                self.body.i32_const(0);
+                self.body.i32_const(byte as i32);
+                self.store_v(x);
+            }
+            Instruction::AddByte(x, byte) => {
+                self.body.i32_const(0);
+                self.load_v(x);
+                self.body.i32_const(byte as i32);
+                self.body.op(I32_ADD);
+                self.store_v(x);
+            }
+            Instruction::LdReg(x, y) => {
+                self.body.i32_const(0);
+                self.load_v(y);
+                self.store_v(x);
+            }
+            Instruction::Or(x, y) => self.binary(x, y, I32_OR),
+            Instruction::And(x, y) => self.binary(x, y, I32_AND),
+            Instruction::Xor(x, y) => self.binary(x, y, I32_XOR),
+            Instruction::AddReg(x, y) => {
+                self.load_v(x);
+                self.load_v(y);
+                self.body.op(I32_ADD);
+                self.body.local(LOCAL_SET, LOCAL_A);
+                self.body.i32_const(0);
+                self.body.local(LOCAL_GET, LOCAL_A);
+                self.body.i32_const(0xFF);
+                self.body.op(I32_GT_U);
+                self.store_v(0xF);
+                self.body.i32_const(0);
+                self.body.local(LOCAL_GET, LOCAL_A);
+                self.store_v(x);
+            }
+            // The interpreter writes VF before computing Vx, so Vx and Vy are
+            // loaded again afterwards in case one of them is VF.
+            Instruction::Sub(x, y) => {
+                self.body.i32_const(0);
+                self.load_v(x);
+                self.load_v(y);
+                self.body.op(I32_GT_U);
+                self.store_v(0xF);
+                self.binary(x, y, I32_SUB);
+            }
+            Instruction::Shr(x, _) => {
+                self.body.i32_const(0);
+                self.load_v(x);
+                self.body.i32_const(1);
+                self.body.op(I32_AND);
+                self.store_v(0xF);
+                self.shift(x, I32_SHR_U);
+            }
+            Instruction::Subn(x, y) => {
+                self.body.i32_const(0);
+                self.load_v(y);
+                self.load_v(x);
+                self.body.op(I32_GT_U);
+                self.store_v(0xF);
+                self.body.i32_const(0);
+                self.load_v(y);
+                self.load_v(x);
+                self.body.op(I32_SUB);
+                self.store_v(x);
+            }
+            Instruction::Shl(x, _) => {
+                self.body.i32_const(0);
+                self.load_v(x);
+                self.body.i32_const(7);
+                self.body.op(I32_SHR_U);
+                self.store_v(0xF);
+                self.shift(x, I32_SHL);
+            }
+            Instruction::LdI(addr) => {
+                self.body.i32_const(0);
+                self.body.i32_const(addr as i32);
+                self.body.memory(I32_STORE16, STATE_I);
+            }
+            Instruction::JpV0(addr) => {
+                self.body.i32_const(0);
+                self.load_v(0);
+                self.body.i32_const(addr as i32);
+                self.body.op(I32_ADD);
+                self.body.memory(I32_STORE16, STATE_PC);
+                self.end_block();
+                return true;
+            }
+            Instruction::Rnd(x, byte) => {
+                self.body.i32_const(0);
+                self.body.call(RANDOM);
+                self.body.i32_const(byte as i32);
+                self.body.op(I32_AND);
+                self.store_v(x);
+            }
+            Instruction::Drw(x, y, n) => {
+                // The interpreter faults on a sprite running off the end of memory
+                self.load_i();
+                self.body.local(LOCAL_TEE, LOCAL_A);
+                self.body.i32_const(n as i32);
+                self.body.op(I32_ADD);
+                self.body.i32_const(MEMORY_SIZE as i32);
+                self.body.op(I32_GT_U);
+                self.fault_if(pc, BAD_ADDRESS, true);
+                self.body.i32_const(0);
+                self.load_v(x);
+                self.load_v(y);
+                self.load_i();
+                self.body.i32_const(n as i32);
+                self.body.call(DRAW);
+                self.store_v(0xF);
+            }
+            Instruction::Skp(x) => {
+                self.load_v(x);
+                self.body.call(KEY_PRESSED);
+                self.skip_if(pc);
+                return true;
+            }
+            Instruction::Sknp(x) => {
+                self.load_v(x);
+                self.body.call(KEY_PRESSED);
+                self.body.op(I32_EQZ);
+                self.skip_if(pc);
+                return true;
+            }
+            Instruction::LdVxDt(x) => {
+                self.body.i32_const(0);
+                self.body.i32_const(0);
+                self.body.memory(I32_LOAD8_U, STATE_DT);
+                self.store_v(x);
+            }
+            Instruction::LdVxK(x) => self.wait_key(pc, x),
+            Instruction::LdDtVx(x) => {
+                self.body.i32_const(0);
+                self.load_v(x);
+                self.body.memory(I32_STORE8, STATE_DT);
+            }
+            Instruction::LdStVx(x) => {
+                self.body.i32_const(0);
+                self.load_v(x);
+                self.body.memory(I32_STORE8, STATE_ST);
+            }
+            Instruction::AddI(x) => {
+                self.body.i32_const(0);
+                self.load_i();
+                self.load_v(x);
+                self.body.op(I32_ADD);
+                self.body.memory(I32_STORE16, STATE_I);
+            }
+            Instruction::LdF(x) => {
+                // All font sprites start at their digit times 5
+                self.body.i32_const(0);
+                self.load_v(x);
+                self.body.i32_const(5);
+                self.body.op(I32_MUL);
+                self.body.memory(I32_STORE16, STATE_I);
+            }
+            Instruction::LdB(x) => {
+                self.checked_i(pc, 2);
+                for (offset, divisor, modulus) in [(0, 100, 0), (1, 10, 10), (2, 1, 10)] {
+                    self.body.local(LOCAL_GET, LOCAL_A);
+                    self.load_v(x);
+                    self.body.i32_const(divisor);
+                    self.body.op(I32_DIV_U);
+                    if modulus > 0 {
+                        self.body.i32_const(modulus);
+                        self.body.op(I32_REM_U);
+                    }
+                    self.body.memory(I32_STORE8, offset);
+                }
+            }
+            Instruction::Store(x) => {
+                self.checked_i(pc, x as i32);
+                for offset in 0..=x {
+                    self.body.local(LOCAL_GET, LOCAL_A);
+                    self.load_v(offset);
+                    self.body.memory(I32_STORE8, offset as u32);
+                }
+            }
+            Instruction::Load(x) => {
+                self.checked_i(pc, x as i32);
+                for offset in 0..=x {
+                    self.body.i32_const(0);
+                    self.body.local(LOCAL_GET, LOCAL_A);
+                    self.body.memory(I32_LOAD8_U, offset as u32);
+                    self.store_v(offset);
+                }
+            }
+        }
+        false
+    }
+
+    /**
+     * wait_key stores the first key held down in Vx. With none held down
+     *    the interpreter leaves pc on FX0A and tries again next instruction,
+     *    so this ends the block there.
+     */
+    fn wait_key(&mut self, pc: u16, x: u8) {
+        let body = &mut self.body;
+        let found = body.block();
+        body.i32_const(0);
+        body.local(LOCAL_SET, LOCAL_KEY);
+        let scan = body.loop_();
+        body.local(LOCAL_GET, LOCAL_KEY);
+        body.call(KEY_PRESSED);
+        body.if_();
+        body.i32_const(0);
+        body.local(LOCAL_GET, LOCAL_KEY);
+        self.store_v(x);
+        self.body.br(found);
+        self.body.end();
+        let body = &mut self.body;
+        body.local(LOCAL_GET, LOCAL_KEY);
+        body.i32_const(1);
+        body.op(I32_ADD);
+        body.local(LOCAL_TEE, LOCAL_KEY);
+        body.i32_const(16);
+        body.op(I32_LT_U);
+        body.br_if(scan);
+        body.end();
+        self.set_pc(pc);
+        self.end_block();
+        self.body.end();
+    }
+
+    /**
+     * skip_if ends a block on a skip instruction, jumping over the next
+     *    instruction when the condition on the stack holds
+     */
+    fn skip_if(&mut self, pc: u16) {
+        let body = &mut self.body;
+        body.local(LOCAL_SET, LOCAL_A);
+        body.i32_const(0);
+        body.i32_const(pc as i32 + 4);
+        body.i32_const(pc as i32 + 2);
+        body.local(LOCAL_GET, LOCAL_A);
+        body.op(SELECT);
+        body.memory(I32_STORE16, STATE_PC);
+        self.end_block();
+    }
+
+    /**
+     * end_block ticks for the block's last instruction, then goes back to
+     *    the br_table with the pc stored in memory unless the host ended
+     *    the frame
+     */
+    fn end_block(&mut self) {
+        let body = &mut self.body;
+        body.call(TIMERS);
+        body.op(I32_EQZ);
+        body.if_();
+        body.i32_const(1);
+        body.op(RETURN);
+        body.end();
+        let dispatch = self.dispatch;
+        self.body.br(dispatch);
+    }
+
+    /**
+     * fault_if calls fault and stops with pc on the faulting instruction if
+     *    the condition on the stack holds. With_start, LOCAL_A is passed to
+     *    fault as the first address used.
+     */
+    fn fault_if(&mut self, pc: u16, fault: u32, with_start: bool) {
+        self.body.if_();
+        self.set_pc(pc);
+        let body = &mut self.body;
+        if with_start {
+            body.local(LOCAL_GET, LOCAL_A);
+        }
+        body.call(fault);
+        body.i32_const(0);
+        body.op(RETURN);
+        body.end();
+    }
+
+    /**
+     * checked_i leaves I in LOCAL_A and faults unless I through I + last
+     *    are all in memory
+     */
+    fn checked_i(&mut self, pc: u16, last: i32) {
+        self.load_i();
+        self.body.local(LOCAL_TEE, LOCAL_A);
+        self.body.i32_const(last);
+        self.body.op(I32_ADD);
+        self.body.i32_const(LAST_ADDRESS);
+        self.body.op(I32_GT_U);
+        self.fault_if(pc, BAD_ADDRESS, true);
+    }
+
+    /**
+     * binary sets Vx to Vx op Vy
+     */
+    fn binary(&mut self, x: u8, y: u8, op: u8) {
+        self.body.i32_const(0);
+        self.load_v(x);
+        self.load_v(y);
+        self.body.op(op);
+        self.store_v(x);
+    }
+
+    /**
+     * shift sets Vx to Vx shifted one bit by op
+     */
+    fn shift(&mut self, x: u8, op: u8) {
+        self.body.i32_const(0);
+        self.load_v(x);
+        self.body.i32_const(1);
+        self.body.op(op);
+        self.store_v(x);
+    }
+
+    fn set_pc(&mut self, addr: u16) {
+        self.body.i32_const(0);
+        self.body.i32_const(addr as i32);
+        self.body.memory(I32_STORE16, STATE_PC);
+    }
+
+    fn load_i(&mut self) {
+        self.body.i32_const(0);
+        self.body.memory(I32_LOAD16_U, STATE_I);
+    }
+
+    fn load_v(&mut self, x: u8) {
+        self.body.i32_const(0);
+        self.body.memory(I32_LOAD8_U, STATE_V + x as u32);
+    }
+
+    /**
+     * store_v stores the value on the stack in Vx. The address, always 0,
+     *    has to be pushed before the value.
+     */
+    fn store_v(&mut self, x: u8) {
+        self.body.memory(I32_STORE8, STATE_V + x as u32);
+    }
+}
@@ -0,0 +1,67 @@
+/**
+ * golden.rs
+ *  lifts the emulator's bundled programs and compares the textual IR with
+ *  the golden files in tests/golden, so any change to what the lifter
+ *  emits shows up as a diff. After a deliberate change, regenerate them:
+ *
+ *  ```text
+ *      UPDATE_GOLDEN=1 cargo test --test golden
+ *  ```
+ */
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use chip8_retargeter_llvm::lift::lift;
+use chip8_retargeter_llvm::rom::Rom;
+
+const ASSETS: &[&str] = &["chp8_IBM_logo", "maze8", "pong", "test_opcode"];
+
+fn root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/**
+ * lifted returns the IR for a bundled program, named after its file so
+ *    the text does not depend on where the tree is
+ */
+fn lifted(asset: &str) -> String {
+    let path = root().join("emulator/assets").join(format!("{}.ch8", asset));
+    let rom = Rom::from_file(path.to_str().unwrap()).unwrap();
+    lift(&rom, &format!("{}.ch8", asset)).unwrap().to_string()
+}
+
+#[test]
+fn lifted_ir_matches_golden_files() {
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+    let mut stale = Vec::new();
+    for asset in ASSETS {
+        let ir = lifted(asset);
+        let golden = root().join("tests/golden").join(format!("{}.ll", asset));
+        if update {
+            fs::write(&golden, &ir).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&golden).unwrap_or_default();
+        if ir != expected {
+            let line = ir.lines().zip(expected.lines()).take_while(|(a, b)| a == b).count() + 1;
+            stale.push(format!("{} first differs at line {}", golden.display(), line));
+        }
+    }
+    assert!(stale.is_empty(), "IR no longer matches:\n{}\nRerun with UPDATE_GOLDEN=1 if intended", stale.join("\n"));
+}
+
+#[cfg(feature = "llvm")]
+#[test]
+fn golden_files_are_valid_llvm() {
+    use inkwell::context::Context;
+    use inkwell::memory_buffer::MemoryBuffer;
+
+    for asset in ASSETS {
+        let text = fs::read_to_string(root().join("tests/golden").join(format!("{}.ll", asset))).unwrap();
+        let context = Context::create();
+        let buffer = MemoryBuffer::create_from_memory_range_copy(text.as_bytes(), asset);
+        let module = context.create_module_from_ir(buffer).unwrap_or_else(|e| panic!("{}: {}", asset, e));
+        module.verify().unwrap_or_else(|e| panic!("{}: {}", asset, e));
+    }
+}
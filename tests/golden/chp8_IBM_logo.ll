@@ -0,0 +1,192 @@
+; ModuleID = 'chp8_IBM_logo.ch8'
+source_filename = "chp8_IBM_logo.ch8"
+
+%chip8.state = type { [16 x i8], i16, i16, i16, i8, i8, [16 x i16], [4096 x i8] }
+
+@chip8_rom = private constant [132 x i8] c"\00\E0\A2*`\0Ca\08\D0\1Fp\09\A29\D0\1F\A2Hp\08\D0\1Fp\04\A2W\D0\1Fp\08\A2f\D0\1Fp\08\A2u\D0\1F\12(\FF\00\FF\00<\00<\00<\00<\00\FF\00\FF\FF\00\FF\008\00?\00?\008\00\FF\00\FF\80\00\E0\00\E0\00\80\00\80\00\E0\00\E0\00\80\F8\00\FC\00>\00?\00;\009\00\F8\00\F8\03\00\07\00\0F\00\BF\00\FB\00\F3\00\E3\00C\E0\00\E0\00\80\00\80\00\80\00\80\00\E0\00\E0"
+@chip8_blocks = private constant [4 x i16] [i16 512, i16 552, i16 552, i16 554]
+
+define void @chip8_run(%chip8.state* %state) {
+entry:
+  br label %dispatch
+
+dispatch:
+  %field = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  %pc = load i16, i16* %field
+  switch i16 %pc, label %unresolved [
+    i16 512, label %block_200
+    i16 552, label %block_228
+  ]
+
+unresolved:
+  call void @chip8_rt_unresolved(%chip8.state* %state)
+  br label %exit
+
+exit:
+  ret void
+
+block_200:
+  call void @chip8_rt_cls(%chip8.state* %state)
+  %running = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running1 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running2 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running3 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field1 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 520, i16* %field1
+  %sprite = getelementptr inbounds [132 x i8], [132 x i8]* @chip8_rom, i32 0, i32 42
+  %collision = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 12, i8 8, i8* %sprite, i8 15)
+  %field2 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 522, i16* %field2
+  %field3 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field3, i32 0, i32 0
+  store i8 12, i8* %v0_ptr
+  %field4 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field4, i32 0, i32 1
+  store i8 8, i8* %v1_ptr
+  %field5 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field5, i32 0, i32 15
+  store i8 %collision, i8* %vf_ptr
+  %field6 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 554, i16* %field6
+  %running4 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running4, label %op_20A, label %exit
+
+block_228:
+  %field32 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 552, i16* %field32
+  %running20 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running20, label %block_228, label %exit
+
+op_20A:
+  %add = add i8 12, 9
+  %running5 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running6 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field7 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 526, i16* %field7
+  %sprite1 = getelementptr inbounds [132 x i8], [132 x i8]* @chip8_rom, i32 0, i32 57
+  %collision1 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %add, i8 8, i8* %sprite1, i8 15)
+  %field8 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 528, i16* %field8
+  %field9 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field9, i32 0, i32 0
+  store i8 %add, i8* %v0_ptr1
+  %field10 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field10, i32 0, i32 15
+  store i8 %collision1, i8* %vf_ptr1
+  %field11 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 569, i16* %field11
+  %running7 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running7, label %op_210, label %exit
+
+op_210:
+  %running8 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %add1 = add i8 %add, 8
+  %running9 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field12 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 532, i16* %field12
+  %sprite2 = getelementptr inbounds [132 x i8], [132 x i8]* @chip8_rom, i32 0, i32 72
+  %collision2 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %add1, i8 8, i8* %sprite2, i8 15)
+  %field13 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 534, i16* %field13
+  %field14 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field14, i32 0, i32 0
+  store i8 %add1, i8* %v0_ptr2
+  %field15 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field15, i32 0, i32 15
+  store i8 %collision2, i8* %vf_ptr2
+  %field16 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 584, i16* %field16
+  %running10 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running10, label %op_216, label %exit
+
+op_216:
+  %add2 = add i8 %add1, 4
+  %running11 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running12 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field17 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 538, i16* %field17
+  %sprite3 = getelementptr inbounds [132 x i8], [132 x i8]* @chip8_rom, i32 0, i32 87
+  %collision3 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %add2, i8 8, i8* %sprite3, i8 15)
+  %field18 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 540, i16* %field18
+  %field19 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field19, i32 0, i32 0
+  store i8 %add2, i8* %v0_ptr3
+  %field20 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field20, i32 0, i32 15
+  store i8 %collision3, i8* %vf_ptr3
+  %field21 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 599, i16* %field21
+  %running13 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running13, label %op_21C, label %exit
+
+op_21C:
+  %add3 = add i8 %add2, 8
+  %running14 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running15 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field22 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 544, i16* %field22
+  %sprite4 = getelementptr inbounds [132 x i8], [132 x i8]* @chip8_rom, i32 0, i32 102
+  %collision4 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %add3, i8 8, i8* %sprite4, i8 15)
+  %field23 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 546, i16* %field23
+  %field24 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field24, i32 0, i32 0
+  store i8 %add3, i8* %v0_ptr4
+  %field25 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field25, i32 0, i32 15
+  store i8 %collision4, i8* %vf_ptr4
+  %field26 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 614, i16* %field26
+  %running16 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running16, label %op_222, label %exit
+
+op_222:
+  %add4 = add i8 %add3, 8
+  %running17 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running18 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field27 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 550, i16* %field27
+  %sprite5 = getelementptr inbounds [132 x i8], [132 x i8]* @chip8_rom, i32 0, i32 117
+  %collision5 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %add4, i8 8, i8* %sprite5, i8 15)
+  %field28 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 552, i16* %field28
+  %field29 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field29, i32 0, i32 0
+  store i8 %add4, i8* %v0_ptr5
+  %field30 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field30, i32 0, i32 15
+  store i8 %collision5, i8* %vf_ptr5
+  %field31 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 629, i16* %field31
+  %running19 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running19, label %op_228, label %exit
+
+op_228:
+  br label %block_228
+}
+
+define i32 @main() {
+entry:
+  %rom = bitcast [132 x i8]* @chip8_rom to i8*
+  %state = call %chip8.state* @chip8_rt_init(i8* %rom, i32 132)
+  %blocks = bitcast [4 x i16]* @chip8_blocks to i16*
+  call void @chip8_rt_run(%chip8.state* %state, void (%chip8.state*)* @chip8_run, i16* %blocks, i32 2)
+  %code = call i32 @chip8_rt_exit(%chip8.state* %state)
+  ret i32 %code
+}
+
+declare %chip8.state* @chip8_rt_init(i8*, i32)
+declare void @chip8_rt_run(%chip8.state*, void (%chip8.state*)*, i16*, i32)
+declare i32 @chip8_rt_exit(%chip8.state*)
+declare i1 @chip8_rt_tick(%chip8.state*, i1)
+declare void @chip8_rt_cls(%chip8.state*)
+declare i8 @chip8_rt_draw(%chip8.state*, i8, i8, i8)
+declare i8 @chip8_rt_draw_sprite(%chip8.state*, i8, i8, i8*, i8)
+declare i1 @chip8_rt_key_pressed(%chip8.state*, i8)
+declare i8 @chip8_rt_wait_key(%chip8.state*)
+declare i8 @chip8_rt_random(%chip8.state*)
+declare void @chip8_rt_bad_address(%chip8.state*, i16)
+declare void @chip8_rt_bad_stack(%chip8.state*)
+declare void @chip8_rt_unresolved(%chip8.state*)
+declare i1 @chip8_rt_code_written(%chip8.state*, i16, i16)
@@ -0,0 +1,166 @@
+; ModuleID = 'maze8.ch8'
+source_filename = "maze8.ch8"
+
+%chip8.state = type { [16 x i8], i16, i16, i16, i8, i8, [16 x i16], [4096 x i8] }
+
+@chip8_rom = private constant [34 x i8] c"\A2\1E\C2\012\01\A2\1A\D0\14p\040@\12\00`\00q\041 \12\00\12\18\80@ \10 @\80\10"
+@chip8_blocks = private constant [14 x i16] [i16 512, i16 518, i16 518, i16 520, i16 520, i16 526, i16 526, i16 528, i16 528, i16 534, i16 534, i16 536, i16 536, i16 538]
+
+define void @chip8_run(%chip8.state* %state) {
+entry:
+  br label %dispatch
+
+dispatch:
+  %field = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  %pc = load i16, i16* %field
+  switch i16 %pc, label %unresolved [
+    i16 512, label %block_200
+    i16 518, label %block_206
+    i16 520, label %block_208
+    i16 526, label %block_20E
+    i16 528, label %block_210
+    i16 534, label %block_216
+    i16 536, label %block_218
+  ]
+
+unresolved:
+  call void @chip8_rt_unresolved(%chip8.state* %state)
+  br label %exit
+
+exit:
+  ret void
+
+block_200:
+  %running = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %random = call i8 @chip8_rt_random(%chip8.state* %state)
+  %rnd = and i8 %random, 1
+  %running1 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %se = icmp eq i8 %rnd, 1
+  %next = select i1 %se, i16 520, i16 518
+  %field1 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next, i16* %field1
+  %field2 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v2_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field2, i32 0, i32 2
+  store i8 %rnd, i8* %v2_ptr
+  %field3 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 542, i16* %field3
+  %running2 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running2, label %skip_204, label %exit
+
+block_206:
+  %field4 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 520, i16* %field4
+  %field5 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 538, i16* %field5
+  %running3 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running3, label %op_208, label %exit
+
+block_208:
+  %field6 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field6, i32 0, i32 0
+  %v0 = load i8, i8* %v0_ptr
+  %field7 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field7, i32 0, i32 1
+  %v1 = load i8, i8* %v1_ptr
+  %field8 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 520, i16* %field8
+  %field9 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i = load i16, i16* %field9
+  %i1 = zext i16 %i to i32
+  %collision = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %v0, i8 %v1, i8 4)
+  %field10 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 522, i16* %field10
+  %field11 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field11, i32 0, i32 15
+  store i8 %collision, i8* %vf_ptr
+  %running4 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running4, label %op_20A, label %exit
+
+block_20E:
+  %field14 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 512, i16* %field14
+  %running7 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running7, label %block_200, label %exit
+
+block_210:
+  %running8 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field15 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field15, i32 0, i32 1
+  %v11 = load i8, i8* %v1_ptr1
+  %add1 = add i8 %v11, 4
+  %running9 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %se2 = icmp eq i8 %add1, 32
+  %next2 = select i1 %se2, i16 536, i16 534
+  %field16 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next2, i16* %field16
+  %field17 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field17, i32 0, i32 0
+  store i8 0, i8* %v0_ptr2
+  %field18 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field18, i32 0, i32 1
+  store i8 %add1, i8* %v1_ptr2
+  %running10 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running10, label %skip_214, label %exit
+
+block_216:
+  %field19 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 512, i16* %field19
+  %running11 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running11, label %block_200, label %exit
+
+block_218:
+  %field20 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 536, i16* %field20
+  %running12 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running12, label %block_218, label %exit
+
+skip_204:
+  br i1 %se, label %block_208, label %block_206
+
+op_208:
+  br label %block_208
+
+op_20A:
+  %add = add i8 %v0, 4
+  %running5 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %se1 = icmp eq i8 %add, 64
+  %next1 = select i1 %se1, i16 528, i16 526
+  %field12 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next1, i16* %field12
+  %field13 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field13, i32 0, i32 0
+  store i8 %add, i8* %v0_ptr1
+  %running6 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running6, label %skip_20C, label %exit
+
+skip_20C:
+  br i1 %se1, label %block_210, label %block_20E
+
+skip_214:
+  br i1 %se2, label %block_218, label %block_216
+}
+
+define i32 @main() {
+entry:
+  %rom = bitcast [34 x i8]* @chip8_rom to i8*
+  %state = call %chip8.state* @chip8_rt_init(i8* %rom, i32 34)
+  %blocks = bitcast [14 x i16]* @chip8_blocks to i16*
+  call void @chip8_rt_run(%chip8.state* %state, void (%chip8.state*)* @chip8_run, i16* %blocks, i32 7)
+  %code = call i32 @chip8_rt_exit(%chip8.state* %state)
+  ret i32 %code
+}
+
+declare %chip8.state* @chip8_rt_init(i8*, i32)
+declare void @chip8_rt_run(%chip8.state*, void (%chip8.state*)*, i16*, i32)
+declare i32 @chip8_rt_exit(%chip8.state*)
+declare i1 @chip8_rt_tick(%chip8.state*, i1)
+declare void @chip8_rt_cls(%chip8.state*)
+declare i8 @chip8_rt_draw(%chip8.state*, i8, i8, i8)
+declare i8 @chip8_rt_draw_sprite(%chip8.state*, i8, i8, i8*, i8)
+declare i1 @chip8_rt_key_pressed(%chip8.state*, i8)
+declare i8 @chip8_rt_wait_key(%chip8.state*)
+declare i8 @chip8_rt_random(%chip8.state*)
+declare void @chip8_rt_bad_address(%chip8.state*, i16)
+declare void @chip8_rt_bad_stack(%chip8.state*)
+declare void @chip8_rt_unresolved(%chip8.state*)
+declare i1 @chip8_rt_code_written(%chip8.state*, i16, i16)
@@ -0,0 +1,1394 @@
+; ModuleID = 'pong.ch8'
+source_filename = "pong.ch8"
+
+%chip8.state = type { [16 x i8], i16, i16, i16, i8, i8, [16 x i16], [4096 x i8] }
+
+@chip8_rom = private constant [246 x i8] c"j\02k\0Cl?m\0C\A2\EA\DA\B6\DC\D6n\00\22\D4f\03h\02``\F0\15\F0\070\00\12\1A\C7\17w\08i\FF\A2\F0\D6q\A2\EA\DA\B6\DC\D6`\01\E0\A1{\FE`\04\E0\A1{\02`\1F\8B\02\DA\B6`\0C\E0\A1}\FE`\0D\E0\A1}\02`\1F\8D\02\DC\D6\A2\F0\D6q\86\84\87\94`?\86\02a\1F\87\12F\02\12xF?\12\82G\1Fi\FFG\00i\01\D6q\12*h\02c\01\80p\80\B5\12\8Ah\FEc\0A\80p\80\D5?\01\12\A2a\02\80\15?\01\12\BA\80\15?\01\12\C8\80\15?\01\12\C2` \F0\18\22\D4\8E4\22\D4f>3\01f\03h\FE3\01h\02\12\16y\FFI\FEi\FF\12\C8y\01I\02i\01`\04\F0\18v\01F@v\FE\12l\A2\F2\FE3\F2e\F1)d\14e\00\D4Ut\15\F2)\D4U\00\EE\80\80\80\80\80\80\80\00\00\00\00\00"
+@chip8_blocks = private constant [98 x i16] [i16 512, i16 530, i16 530, i16 534, i16 534, i16 538, i16 538, i16 542, i16 542, i16 544, i16 544, i16 554, i16 554, i16 564, i16 564, i16 566, i16 566, i16 570, i16 570, i16 572, i16 572, i16 582, i16 582, i16 584, i16 584, i16 588, i16 588, i16 590, i16 590, i16 614, i16 614, i16 616, i16 616, i16 618, i16 618, i16 620, i16 620, i16 622, i16 622, i16 624, i16 624, i16 626, i16 626, i16 628, i16 628, i16 632, i16 632, i16 642, i16 642, i16 650, i16 650, i16 652, i16 652, i16 654, i16 654, i16 660, i16 660, i16 662, i16 662, i16 666, i16 666, i16 668, i16 668, i16 672, i16 672, i16 674, i16 674, i16 680, i16 680, i16 684, i16 684, i16 688, i16 688, i16 690, i16 690, i16 694, i16 694, i16 696, i16 696, i16 698, i16 698, i16 702, i16 702, i16 704, i16 704, i16 706, i16 706, i16 710, i16 710, i16 712, i16 712, i16 720, i16 720, i16 722, i16 722, i16 724, i16 724, i16 746]
+
+define void @chip8_run(%chip8.state* %state) {
+entry:
+  br label %dispatch
+
+dispatch:
+  %field = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  %pc = load i16, i16* %field
+  switch i16 %pc, label %unresolved [
+    i16 512, label %block_200
+    i16 530, label %block_212
+    i16 534, label %block_216
+    i16 538, label %block_21A
+    i16 542, label %block_21E
+    i16 544, label %block_220
+    i16 554, label %block_22A
+    i16 564, label %block_234
+    i16 566, label %block_236
+    i16 570, label %block_23A
+    i16 572, label %block_23C
+    i16 582, label %block_246
+    i16 584, label %block_248
+    i16 588, label %block_24C
+    i16 590, label %block_24E
+    i16 614, label %block_266
+    i16 616, label %block_268
+    i16 618, label %block_26A
+    i16 620, label %block_26C
+    i16 622, label %block_26E
+    i16 624, label %block_270
+    i16 626, label %block_272
+    i16 628, label %block_274
+    i16 632, label %block_278
+    i16 642, label %block_282
+    i16 650, label %block_28A
+    i16 652, label %block_28C
+    i16 654, label %block_28E
+    i16 660, label %block_294
+    i16 662, label %block_296
+    i16 666, label %block_29A
+    i16 668, label %block_29C
+    i16 672, label %block_2A0
+    i16 674, label %block_2A2
+    i16 680, label %block_2A8
+    i16 684, label %block_2AC
+    i16 688, label %block_2B0
+    i16 690, label %block_2B2
+    i16 694, label %block_2B6
+    i16 696, label %block_2B8
+    i16 698, label %block_2BA
+    i16 702, label %block_2BE
+    i16 704, label %block_2C0
+    i16 706, label %block_2C2
+    i16 710, label %block_2C6
+    i16 712, label %block_2C8
+    i16 720, label %block_2D0
+    i16 722, label %block_2D2
+    i16 724, label %block_2D4
+  ]
+
+unresolved:
+  call void @chip8_rt_unresolved(%chip8.state* %state)
+  br label %exit
+
+exit:
+  ret void
+
+block_200:
+  %running = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running1 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running2 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running3 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running4 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field1 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 522, i16* %field1
+  %sprite = getelementptr inbounds [246 x i8], [246 x i8]* @chip8_rom, i32 0, i32 234
+  %collision = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 2, i8 12, i8* %sprite, i8 6)
+  %field2 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 524, i16* %field2
+  %field3 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field3, i32 0, i32 10
+  store i8 2, i8* %va_ptr
+  %field4 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field4, i32 0, i32 11
+  store i8 12, i8* %vb_ptr
+  %field5 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vc_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field5, i32 0, i32 12
+  store i8 63, i8* %vc_ptr
+  %field6 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vd_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field6, i32 0, i32 13
+  store i8 12, i8* %vd_ptr
+  %field7 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field7, i32 0, i32 15
+  store i8 %collision, i8* %vf_ptr
+  %field8 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 746, i16* %field8
+  %running5 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running5, label %op_20C, label %exit
+
+block_212:
+  %running9 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field20 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 534, i16* %field20
+  %field21 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field21, i32 0, i32 6
+  store i8 3, i8* %v6_ptr
+  %field22 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field22, i32 0, i32 8
+  store i8 2, i8* %v8_ptr
+  %running10 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running10, label %op_216, label %exit
+
+block_216:
+  %running11 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field23 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 538, i16* %field23
+  %field24 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field24, i32 0, i32 0
+  store i8 96, i8* %v0_ptr
+  %field25 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 4
+  store i8 96, i8* %field25
+  %running12 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running12, label %op_21A, label %exit
+
+block_21A:
+  %field26 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 4
+  %dt = load i8, i8* %field26
+  %running13 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %se = icmp eq i8 %dt, 0
+  %next1 = select i1 %se, i16 544, i16 542
+  %field27 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next1, i16* %field27
+  %field28 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field28, i32 0, i32 0
+  store i8 %dt, i8* %v0_ptr1
+  %running14 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running14, label %skip_21C, label %exit
+
+block_21E:
+  %field29 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 538, i16* %field29
+  %running15 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running15, label %block_21A, label %exit
+
+block_220:
+  %random = call i8 @chip8_rt_random(%chip8.state* %state)
+  %rnd = and i8 %random, 23
+  %running16 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %add = add i8 %rnd, 8
+  %running17 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running18 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running19 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field30 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field30, i32 0, i32 6
+  %v6 = load i8, i8* %v6_ptr1
+  %field31 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 552, i16* %field31
+  %sprite2 = getelementptr inbounds [246 x i8], [246 x i8]* @chip8_rom, i32 0, i32 240
+  %collision2 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v6, i8 %add, i8* %sprite2, i8 1)
+  %field32 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 554, i16* %field32
+  %field33 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field33, i32 0, i32 7
+  store i8 %add, i8* %v7_ptr
+  %field34 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field34, i32 0, i32 9
+  store i8 -1, i8* %v9_ptr
+  %field35 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field35, i32 0, i32 15
+  store i8 %collision2, i8* %vf_ptr2
+  %field36 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 752, i16* %field36
+  %running20 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running20, label %op_22A, label %exit
+
+block_22A:
+  %running21 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field37 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field37, i32 0, i32 10
+  %va = load i8, i8* %va_ptr1
+  %field38 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field38, i32 0, i32 11
+  %vb = load i8, i8* %vb_ptr1
+  %field39 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 556, i16* %field39
+  %sprite3 = getelementptr inbounds [246 x i8], [246 x i8]* @chip8_rom, i32 0, i32 234
+  %collision3 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %va, i8 %vb, i8* %sprite3, i8 6)
+  %field40 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 558, i16* %field40
+  %field41 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field41, i32 0, i32 15
+  store i8 %collision3, i8* %vf_ptr3
+  %field42 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 746, i16* %field42
+  %running22 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running22, label %op_22E, label %exit
+
+block_234:
+  %field51 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field51, i32 0, i32 11
+  %vb1 = load i8, i8* %vb_ptr2
+  %add1 = add i8 %vb1, -2
+  %field52 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 566, i16* %field52
+  %field53 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field53, i32 0, i32 11
+  store i8 %add1, i8* %vb_ptr3
+  %running26 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running26, label %op_236, label %exit
+
+block_236:
+  %running27 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field54 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 568, i16* %field54
+  %pressed1 = call i1 @chip8_rt_key_pressed(%chip8.state* %state, i8 4)
+  %released1 = xor i1 %pressed1, true
+  %next3 = select i1 %released1, i16 572, i16 570
+  %field55 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next3, i16* %field55
+  %field56 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field56, i32 0, i32 0
+  store i8 4, i8* %v0_ptr3
+  %running28 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running28, label %skip_238, label %exit
+
+block_23A:
+  %field57 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field57, i32 0, i32 11
+  %vb2 = load i8, i8* %vb_ptr4
+  %add2 = add i8 %vb2, 2
+  %field58 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 572, i16* %field58
+  %field59 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field59, i32 0, i32 11
+  store i8 %add2, i8* %vb_ptr5
+  %running29 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running29, label %op_23C, label %exit
+
+block_23C:
+  %running30 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field60 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field60, i32 0, i32 11
+  %vb3 = load i8, i8* %vb_ptr6
+  %and = and i8 %vb3, 31
+  %running31 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field61 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field61, i32 0, i32 10
+  %va1 = load i8, i8* %va_ptr2
+  %field62 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 576, i16* %field62
+  %sprite5 = getelementptr inbounds [246 x i8], [246 x i8]* @chip8_rom, i32 0, i32 234
+  %collision5 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %va1, i8 %and, i8* %sprite5, i8 6)
+  %field63 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 578, i16* %field63
+  %field64 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field64, i32 0, i32 0
+  store i8 31, i8* %v0_ptr4
+  %field65 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field65, i32 0, i32 11
+  store i8 %and, i8* %vb_ptr7
+  %field66 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field66, i32 0, i32 15
+  store i8 %collision5, i8* %vf_ptr5
+  %running32 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running32, label %op_242, label %exit
+
+block_246:
+  %field70 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vd_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field70, i32 0, i32 13
+  %vd1 = load i8, i8* %vd_ptr2
+  %add3 = add i8 %vd1, -2
+  %field71 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 584, i16* %field71
+  %field72 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vd_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field72, i32 0, i32 13
+  store i8 %add3, i8* %vd_ptr3
+  %running35 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running35, label %op_248, label %exit
+
+block_248:
+  %running36 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field73 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 586, i16* %field73
+  %pressed3 = call i1 @chip8_rt_key_pressed(%chip8.state* %state, i8 13)
+  %released3 = xor i1 %pressed3, true
+  %next5 = select i1 %released3, i16 590, i16 588
+  %field74 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next5, i16* %field74
+  %field75 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field75, i32 0, i32 0
+  store i8 13, i8* %v0_ptr6
+  %running37 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running37, label %skip_24A, label %exit
+
+block_24C:
+  %field76 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vd_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field76, i32 0, i32 13
+  %vd2 = load i8, i8* %vd_ptr4
+  %add4 = add i8 %vd2, 2
+  %field77 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 590, i16* %field77
+  %field78 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vd_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field78, i32 0, i32 13
+  store i8 %add4, i8* %vd_ptr5
+  %running38 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running38, label %op_24E, label %exit
+
+block_24E:
+  %running39 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field79 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vd_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field79, i32 0, i32 13
+  %vd3 = load i8, i8* %vd_ptr6
+  %and1 = and i8 %vd3, 31
+  %running40 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field80 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vc_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field80, i32 0, i32 12
+  %vc1 = load i8, i8* %vc_ptr2
+  %field81 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 594, i16* %field81
+  %sprite6 = getelementptr inbounds [246 x i8], [246 x i8]* @chip8_rom, i32 0, i32 234
+  %collision6 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %vc1, i8 %and1, i8* %sprite6, i8 6)
+  %field82 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 596, i16* %field82
+  %field83 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field83, i32 0, i32 0
+  store i8 31, i8* %v0_ptr7
+  %field84 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vd_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field84, i32 0, i32 13
+  store i8 %and1, i8* %vd_ptr7
+  %field85 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field85, i32 0, i32 15
+  store i8 %collision6, i8* %vf_ptr6
+  %running41 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running41, label %op_254, label %exit
+
+block_266:
+  %field100 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 632, i16* %field100
+  %running51 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running51, label %block_278, label %exit
+
+block_268:
+  %field101 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field101, i32 0, i32 6
+  %v62 = load i8, i8* %v6_ptr4
+  %sne1 = icmp ne i8 %v62, 63
+  %next7 = select i1 %sne1, i16 620, i16 618
+  %field102 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next7, i16* %field102
+  %running52 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running52, label %skip_268, label %exit
+
+block_26A:
+  %field103 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 642, i16* %field103
+  %running53 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running53, label %block_282, label %exit
+
+block_26C:
+  %field104 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field104, i32 0, i32 7
+  %v71 = load i8, i8* %v7_ptr3
+  %sne2 = icmp ne i8 %v71, 31
+  %next8 = select i1 %sne2, i16 624, i16 622
+  %field105 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next8, i16* %field105
+  %running54 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running54, label %skip_26C, label %exit
+
+block_26E:
+  %field106 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 624, i16* %field106
+  %field107 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field107, i32 0, i32 9
+  store i8 -1, i8* %v9_ptr2
+  %running55 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running55, label %op_270, label %exit
+
+block_270:
+  %field108 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field108, i32 0, i32 7
+  %v72 = load i8, i8* %v7_ptr4
+  %sne3 = icmp ne i8 %v72, 0
+  %next9 = select i1 %sne3, i16 628, i16 626
+  %field109 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next9, i16* %field109
+  %running56 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running56, label %skip_270, label %exit
+
+block_272:
+  %field110 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 628, i16* %field110
+  %field111 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field111, i32 0, i32 9
+  store i8 1, i8* %v9_ptr3
+  %running57 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running57, label %op_274, label %exit
+
+block_274:
+  %field112 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field112, i32 0, i32 6
+  %v63 = load i8, i8* %v6_ptr5
+  %field113 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field113, i32 0, i32 7
+  %v73 = load i8, i8* %v7_ptr5
+  %field114 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 628, i16* %field114
+  %sprite8 = getelementptr inbounds [246 x i8], [246 x i8]* @chip8_rom, i32 0, i32 240
+  %collision8 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v63, i8 %v73, i8* %sprite8, i8 1)
+  %field115 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 630, i16* %field115
+  %field116 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr9 = getelementptr inbounds [16 x i8], [16 x i8]* %field116, i32 0, i32 15
+  store i8 %collision8, i8* %vf_ptr9
+  %running58 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running58, label %op_276, label %exit
+
+block_278:
+  %running60 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running61 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field118 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field118, i32 0, i32 7
+  %v74 = load i8, i8* %v7_ptr6
+  %running62 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field119 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field119, i32 0, i32 11
+  %vb4 = load i8, i8* %vb_ptr8
+  %no_borrow = icmp ugt i8 %v74, %vb4
+  %vf1 = zext i1 %no_borrow to i8
+  %sub = sub i8 %v74, %vb4
+  %running63 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field120 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 650, i16* %field120
+  %field121 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr9 = getelementptr inbounds [16 x i8], [16 x i8]* %field121, i32 0, i32 0
+  store i8 %sub, i8* %v0_ptr9
+  %field122 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v3_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field122, i32 0, i32 3
+  store i8 1, i8* %v3_ptr
+  %field123 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field123, i32 0, i32 8
+  store i8 2, i8* %v8_ptr2
+  %field124 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr10 = getelementptr inbounds [16 x i8], [16 x i8]* %field124, i32 0, i32 15
+  store i8 %vf1, i8* %vf_ptr10
+  %running64 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running64, label %block_28A, label %exit
+
+block_282:
+  %running65 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running66 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field125 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field125, i32 0, i32 7
+  %v75 = load i8, i8* %v7_ptr7
+  %running67 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field126 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vd_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field126, i32 0, i32 13
+  %vd4 = load i8, i8* %vd_ptr8
+  %no_borrow1 = icmp ugt i8 %v75, %vd4
+  %vf2 = zext i1 %no_borrow1 to i8
+  %sub1 = sub i8 %v75, %vd4
+  %field127 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 650, i16* %field127
+  %field128 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr10 = getelementptr inbounds [16 x i8], [16 x i8]* %field128, i32 0, i32 0
+  store i8 %sub1, i8* %v0_ptr10
+  %field129 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v3_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field129, i32 0, i32 3
+  store i8 10, i8* %v3_ptr1
+  %field130 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field130, i32 0, i32 8
+  store i8 -2, i8* %v8_ptr3
+  %field131 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr11 = getelementptr inbounds [16 x i8], [16 x i8]* %field131, i32 0, i32 15
+  store i8 %vf2, i8* %vf_ptr11
+  %running68 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running68, label %op_28A, label %exit
+
+block_28A:
+  %field132 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr12 = getelementptr inbounds [16 x i8], [16 x i8]* %field132, i32 0, i32 15
+  %vf3 = load i8, i8* %vf_ptr12
+  %se1 = icmp eq i8 %vf3, 1
+  %next10 = select i1 %se1, i16 654, i16 652
+  %field133 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next10, i16* %field133
+  %running69 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running69, label %skip_28A, label %exit
+
+block_28C:
+  %field134 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 674, i16* %field134
+  %running70 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running70, label %block_2A2, label %exit
+
+block_28E:
+  %running71 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field135 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr11 = getelementptr inbounds [16 x i8], [16 x i8]* %field135, i32 0, i32 0
+  %v0 = load i8, i8* %v0_ptr11
+  %no_borrow2 = icmp ugt i8 %v0, 2
+  %vf4 = zext i1 %no_borrow2 to i8
+  %sub2 = sub i8 %v0, 2
+  %running72 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %se2 = icmp eq i8 %vf4, 1
+  %next11 = select i1 %se2, i16 662, i16 660
+  %field136 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next11, i16* %field136
+  %field137 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr12 = getelementptr inbounds [16 x i8], [16 x i8]* %field137, i32 0, i32 0
+  store i8 %sub2, i8* %v0_ptr12
+  %field138 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field138, i32 0, i32 1
+  store i8 2, i8* %v1_ptr1
+  %field139 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr13 = getelementptr inbounds [16 x i8], [16 x i8]* %field139, i32 0, i32 15
+  store i8 %vf4, i8* %vf_ptr13
+  %running73 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running73, label %skip_292, label %exit
+
+block_294:
+  %field140 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 698, i16* %field140
+  %running74 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running74, label %block_2BA, label %exit
+
+block_296:
+  %field141 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr13 = getelementptr inbounds [16 x i8], [16 x i8]* %field141, i32 0, i32 0
+  %v01 = load i8, i8* %v0_ptr13
+  %field142 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field142, i32 0, i32 1
+  %v1 = load i8, i8* %v1_ptr2
+  %no_borrow3 = icmp ugt i8 %v01, %v1
+  %vf5 = zext i1 %no_borrow3 to i8
+  %sub3 = sub i8 %v01, %v1
+  %running75 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %se3 = icmp eq i8 %vf5, 1
+  %next12 = select i1 %se3, i16 668, i16 666
+  %field143 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next12, i16* %field143
+  %field144 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr14 = getelementptr inbounds [16 x i8], [16 x i8]* %field144, i32 0, i32 0
+  store i8 %sub3, i8* %v0_ptr14
+  %field145 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr14 = getelementptr inbounds [16 x i8], [16 x i8]* %field145, i32 0, i32 15
+  store i8 %vf5, i8* %vf_ptr14
+  %running76 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running76, label %skip_298, label %exit
+
+block_29A:
+  %field146 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 712, i16* %field146
+  %running77 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running77, label %block_2C8, label %exit
+
+block_29C:
+  %field147 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr15 = getelementptr inbounds [16 x i8], [16 x i8]* %field147, i32 0, i32 0
+  %v02 = load i8, i8* %v0_ptr15
+  %field148 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field148, i32 0, i32 1
+  %v11 = load i8, i8* %v1_ptr3
+  %no_borrow4 = icmp ugt i8 %v02, %v11
+  %vf6 = zext i1 %no_borrow4 to i8
+  %sub4 = sub i8 %v02, %v11
+  %running78 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %se4 = icmp eq i8 %vf6, 1
+  %next13 = select i1 %se4, i16 674, i16 672
+  %field149 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next13, i16* %field149
+  %field150 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr16 = getelementptr inbounds [16 x i8], [16 x i8]* %field150, i32 0, i32 0
+  store i8 %sub4, i8* %v0_ptr16
+  %field151 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr15 = getelementptr inbounds [16 x i8], [16 x i8]* %field151, i32 0, i32 15
+  store i8 %vf6, i8* %vf_ptr15
+  %running79 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running79, label %skip_29E, label %exit
+
+block_2A0:
+  %field152 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 706, i16* %field152
+  %running80 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running80, label %block_2C2, label %exit
+
+block_2A2:
+  %running81 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field153 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 5
+  store i8 32, i8* %field153
+  %running82 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field154 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  %sp2 = load i16, i16* %field154
+  %sp3 = add i16 %sp2, 1
+  %full1 = icmp uge i16 %sp3, 16
+  br i1 %full1, label %fault_2A6, label %ok_2A6
+
+block_2A8:
+  %field162 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %ve_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field162, i32 0, i32 14
+  %ve = load i8, i8* %ve_ptr2
+  %field163 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v3_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field163, i32 0, i32 3
+  %v3 = load i8, i8* %v3_ptr2
+  %add7 = add i8 %ve, %v3
+  %carry1 = icmp ult i8 %add7, %ve
+  %vf7 = zext i1 %carry1 to i8
+  %running84 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field164 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  %sp4 = load i16, i16* %field164
+  %sp5 = add i16 %sp4, 1
+  %full2 = icmp uge i16 %sp5, 16
+  br i1 %full2, label %fault_2AA, label %ok_2AA
+
+block_2AC:
+  %running86 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field174 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v3_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field174, i32 0, i32 3
+  %v31 = load i8, i8* %v3_ptr3
+  %se5 = icmp eq i8 %v31, 1
+  %next16 = select i1 %se5, i16 690, i16 688
+  %field175 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next16, i16* %field175
+  %field176 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field176, i32 0, i32 6
+  store i8 62, i8* %v6_ptr6
+  %running87 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running87, label %skip_2AE, label %exit
+
+block_2B0:
+  %field177 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 690, i16* %field177
+  %field178 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field178, i32 0, i32 6
+  store i8 3, i8* %v6_ptr7
+  %running88 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running88, label %op_2B2, label %exit
+
+block_2B2:
+  %running89 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field179 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v3_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field179, i32 0, i32 3
+  %v32 = load i8, i8* %v3_ptr4
+  %se6 = icmp eq i8 %v32, 1
+  %next17 = select i1 %se6, i16 696, i16 694
+  %field180 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next17, i16* %field180
+  %field181 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field181, i32 0, i32 8
+  store i8 -2, i8* %v8_ptr4
+  %running90 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running90, label %skip_2B4, label %exit
+
+block_2B6:
+  %field182 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 696, i16* %field182
+  %field183 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field183, i32 0, i32 8
+  store i8 2, i8* %v8_ptr5
+  %running91 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running91, label %op_2B8, label %exit
+
+block_2B8:
+  %field184 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 534, i16* %field184
+  %running92 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running92, label %block_216, label %exit
+
+block_2BA:
+  %field185 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field185, i32 0, i32 9
+  %v91 = load i8, i8* %v9_ptr4
+  %add8 = add i8 %v91, -1
+  %running93 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne4 = icmp ne i8 %add8, -2
+  %next18 = select i1 %sne4, i16 704, i16 702
+  %field186 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next18, i16* %field186
+  %field187 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field187, i32 0, i32 9
+  store i8 %add8, i8* %v9_ptr5
+  %running94 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running94, label %skip_2BC, label %exit
+
+block_2BE:
+  %field188 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 704, i16* %field188
+  %field189 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field189, i32 0, i32 9
+  store i8 -1, i8* %v9_ptr6
+  %running95 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running95, label %op_2C0, label %exit
+
+block_2C0:
+  %field190 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 712, i16* %field190
+  %running96 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running96, label %block_2C8, label %exit
+
+block_2C2:
+  %field191 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field191, i32 0, i32 9
+  %v92 = load i8, i8* %v9_ptr7
+  %add9 = add i8 %v92, 1
+  %running97 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne5 = icmp ne i8 %add9, 2
+  %next19 = select i1 %sne5, i16 712, i16 710
+  %field192 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next19, i16* %field192
+  %field193 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field193, i32 0, i32 9
+  store i8 %add9, i8* %v9_ptr8
+  %running98 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running98, label %skip_2C4, label %exit
+
+block_2C6:
+  %field194 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 712, i16* %field194
+  %field195 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr9 = getelementptr inbounds [16 x i8], [16 x i8]* %field195, i32 0, i32 9
+  store i8 1, i8* %v9_ptr9
+  %running99 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running99, label %op_2C8, label %exit
+
+block_2C8:
+  %running100 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field196 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 5
+  store i8 4, i8* %field196
+  %running101 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field197 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field197, i32 0, i32 6
+  %v64 = load i8, i8* %v6_ptr8
+  %add10 = add i8 %v64, 1
+  %running102 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne6 = icmp ne i8 %add10, 64
+  %next20 = select i1 %sne6, i16 722, i16 720
+  %field198 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next20, i16* %field198
+  %field199 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr19 = getelementptr inbounds [16 x i8], [16 x i8]* %field199, i32 0, i32 0
+  store i8 4, i8* %v0_ptr19
+  %field200 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr9 = getelementptr inbounds [16 x i8], [16 x i8]* %field200, i32 0, i32 6
+  store i8 %add10, i8* %v6_ptr9
+  %running103 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running103, label %skip_2CE, label %exit
+
+block_2D0:
+  %field201 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr10 = getelementptr inbounds [16 x i8], [16 x i8]* %field201, i32 0, i32 6
+  %v65 = load i8, i8* %v6_ptr10
+  %add11 = add i8 %v65, -2
+  %field202 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 722, i16* %field202
+  %field203 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr11 = getelementptr inbounds [16 x i8], [16 x i8]* %field203, i32 0, i32 6
+  store i8 %add11, i8* %v6_ptr11
+  %running104 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running104, label %op_2D2, label %exit
+
+block_2D2:
+  %field204 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 620, i16* %field204
+  %running105 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running105, label %block_26C, label %exit
+
+block_2D4:
+  %running106 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %i = zext i16 754 to i32
+  %field205 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %ve_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field205, i32 0, i32 14
+  %ve1 = load i8, i8* %ve_ptr5
+  %hundreds = udiv i8 %ve1, 100
+  %tens = udiv i8 %ve1, 10
+  %tens1 = urem i8 %tens, 10
+  %ones = urem i8 %ve1, 10
+  %field206 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr = add i32 %i, 0
+  %mem_ptr = getelementptr inbounds [4096 x i8], [4096 x i8]* %field206, i32 0, i32 %addr
+  store i8 %hundreds, i8* %mem_ptr
+  %field207 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr1 = add i32 %i, 1
+  %mem_ptr1 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field207, i32 0, i32 %addr1
+  store i8 %tens1, i8* %mem_ptr1
+  %field208 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr2 = add i32 %i, 2
+  %mem_ptr2 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field208, i32 0, i32 %addr2
+  store i8 %ones, i8* %mem_ptr2
+  %running107 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %i1 = zext i16 754 to i32
+  %field209 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr3 = add i32 %i1, 0
+  %mem_ptr3 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field209, i32 0, i32 %addr3
+  %mem = load i8, i8* %mem_ptr3
+  %field210 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr4 = add i32 %i1, 1
+  %mem_ptr4 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field210, i32 0, i32 %addr4
+  %mem1 = load i8, i8* %mem_ptr4
+  %field211 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr5 = add i32 %i1, 2
+  %mem_ptr5 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field211, i32 0, i32 %addr5
+  %mem2 = load i8, i8* %mem_ptr5
+  %running108 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %vx = zext i8 %mem1 to i16
+  %font = mul i16 %vx, 5
+  %running109 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running110 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running111 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field212 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 736, i16* %field212
+  %i2 = zext i16 %font to i32
+  %field213 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 %font, i16* %field213
+  %collision9 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 20, i8 0, i8 5)
+  %field214 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 738, i16* %field214
+  %field215 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr20 = getelementptr inbounds [16 x i8], [16 x i8]* %field215, i32 0, i32 0
+  store i8 %mem, i8* %v0_ptr20
+  %field216 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field216, i32 0, i32 1
+  store i8 %mem1, i8* %v1_ptr4
+  %field217 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v2_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field217, i32 0, i32 2
+  store i8 %mem2, i8* %v2_ptr
+  %field218 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v4_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field218, i32 0, i32 4
+  store i8 20, i8* %v4_ptr
+  %field219 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v5_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field219, i32 0, i32 5
+  store i8 0, i8* %v5_ptr
+  %field220 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr18 = getelementptr inbounds [16 x i8], [16 x i8]* %field220, i32 0, i32 15
+  store i8 %collision9, i8* %vf_ptr18
+  %running112 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running112, label %op_2E2, label %exit
+
+op_20C:
+  %field9 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 524, i16* %field9
+  %sprite1 = getelementptr inbounds [246 x i8], [246 x i8]* @chip8_rom, i32 0, i32 234
+  %collision1 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 63, i8 12, i8* %sprite1, i8 6)
+  %field10 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 526, i16* %field10
+  %field11 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field11, i32 0, i32 15
+  store i8 %collision1, i8* %vf_ptr1
+  %running6 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running6, label %op_20E, label %exit
+
+op_20E:
+  %running7 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field12 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  %sp = load i16, i16* %field12
+  %sp1 = add i16 %sp, 1
+  %full = icmp uge i16 %sp1, 16
+  br i1 %full, label %fault_210, label %ok_210
+
+fault_210:
+  %field13 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 528, i16* %field13
+  %field14 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %ve_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field14, i32 0, i32 14
+  store i8 0, i8* %ve_ptr
+  call void @chip8_rt_bad_stack(%chip8.state* %state)
+  br label %exit
+
+ok_210:
+  %field15 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  store i16 %sp1, i16* %field15
+  %field16 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 6
+  %slot = getelementptr inbounds [16 x i16], [16 x i16]* %field16, i32 0, i16 %sp1
+  store i16 528, i16* %slot
+  %field17 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 724, i16* %field17
+  %field18 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %ve_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field18, i32 0, i32 14
+  store i8 0, i8* %ve_ptr1
+  %running8 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running8, label %call_210, label %exit
+
+call_210:
+  %returned = call i1 @chip8_sub_2D4(%chip8.state* %state)
+  br i1 %returned, label %back_210, label %exit
+
+back_210:
+  %field19 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  %next = load i16, i16* %field19
+  %expected = icmp eq i16 %next, 530
+  br i1 %expected, label %block_212, label %dispatch
+
+op_216:
+  br label %block_216
+
+op_21A:
+  br label %block_21A
+
+skip_21C:
+  br i1 %se, label %block_220, label %block_21E
+
+op_22A:
+  br label %block_22A
+
+op_22E:
+  %field43 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vc_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field43, i32 0, i32 12
+  %vc = load i8, i8* %vc_ptr1
+  %field44 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vd_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field44, i32 0, i32 13
+  %vd = load i8, i8* %vd_ptr1
+  %field45 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 558, i16* %field45
+  %sprite4 = getelementptr inbounds [246 x i8], [246 x i8]* @chip8_rom, i32 0, i32 234
+  %collision4 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %vc, i8 %vd, i8* %sprite4, i8 6)
+  %field46 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 560, i16* %field46
+  %field47 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field47, i32 0, i32 15
+  store i8 %collision4, i8* %vf_ptr4
+  %running23 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running23, label %op_230, label %exit
+
+op_230:
+  %running24 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field48 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 562, i16* %field48
+  %pressed = call i1 @chip8_rt_key_pressed(%chip8.state* %state, i8 1)
+  %released = xor i1 %pressed, true
+  %next2 = select i1 %released, i16 566, i16 564
+  %field49 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next2, i16* %field49
+  %field50 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field50, i32 0, i32 0
+  store i8 1, i8* %v0_ptr2
+  %running25 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running25, label %skip_232, label %exit
+
+skip_232:
+  br i1 %released, label %block_236, label %block_234
+
+op_236:
+  br label %block_236
+
+skip_238:
+  br i1 %released1, label %block_23C, label %block_23A
+
+op_23C:
+  br label %block_23C
+
+op_242:
+  %running33 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field67 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 580, i16* %field67
+  %pressed2 = call i1 @chip8_rt_key_pressed(%chip8.state* %state, i8 12)
+  %released2 = xor i1 %pressed2, true
+  %next4 = select i1 %released2, i16 584, i16 582
+  %field68 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next4, i16* %field68
+  %field69 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field69, i32 0, i32 0
+  store i8 12, i8* %v0_ptr5
+  %running34 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running34, label %skip_244, label %exit
+
+skip_244:
+  br i1 %released2, label %block_248, label %block_246
+
+op_248:
+  br label %block_248
+
+skip_24A:
+  br i1 %released3, label %block_24E, label %block_24C
+
+op_24E:
+  br label %block_24E
+
+op_254:
+  %running42 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field86 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field86, i32 0, i32 6
+  %v61 = load i8, i8* %v6_ptr2
+  %field87 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field87, i32 0, i32 7
+  %v7 = load i8, i8* %v7_ptr1
+  %field88 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 598, i16* %field88
+  %sprite7 = getelementptr inbounds [246 x i8], [246 x i8]* @chip8_rom, i32 0, i32 240
+  %collision7 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v61, i8 %v7, i8* %sprite7, i8 1)
+  %field89 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 600, i16* %field89
+  %field90 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field90, i32 0, i32 15
+  store i8 %collision7, i8* %vf_ptr7
+  %field91 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 752, i16* %field91
+  %running43 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running43, label %op_258, label %exit
+
+op_258:
+  %field92 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field92, i32 0, i32 8
+  %v8 = load i8, i8* %v8_ptr1
+  %add5 = add i8 %v61, %v8
+  %running44 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field93 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field93, i32 0, i32 9
+  %v9 = load i8, i8* %v9_ptr1
+  %add6 = add i8 %v7, %v9
+  %carry = icmp ult i8 %add6, %v7
+  %vf = zext i1 %carry to i8
+  %running45 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running46 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %and2 = and i8 %add5, 63
+  %running47 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running48 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %and3 = and i8 %add6, 31
+  %running49 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne = icmp ne i8 %and2, 2
+  %next6 = select i1 %sne, i16 616, i16 614
+  %field94 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next6, i16* %field94
+  %field95 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field95, i32 0, i32 0
+  store i8 63, i8* %v0_ptr8
+  %field96 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field96, i32 0, i32 1
+  store i8 31, i8* %v1_ptr
+  %field97 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field97, i32 0, i32 6
+  store i8 %and2, i8* %v6_ptr3
+  %field98 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field98, i32 0, i32 7
+  store i8 %and3, i8* %v7_ptr2
+  %field99 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field99, i32 0, i32 15
+  store i8 %vf, i8* %vf_ptr8
+  %running50 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running50, label %skip_264, label %exit
+
+skip_264:
+  br i1 %sne, label %block_268, label %block_266
+
+skip_268:
+  br i1 %sne1, label %block_26C, label %block_26A
+
+skip_26C:
+  br i1 %sne2, label %block_270, label %block_26E
+
+op_270:
+  br label %block_270
+
+skip_270:
+  br i1 %sne3, label %block_274, label %block_272
+
+op_274:
+  br label %block_274
+
+op_276:
+  %field117 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 554, i16* %field117
+  %running59 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running59, label %block_22A, label %exit
+
+op_28A:
+  br label %block_28A
+
+skip_28A:
+  br i1 %se1, label %block_28E, label %block_28C
+
+skip_292:
+  br i1 %se2, label %block_296, label %block_294
+
+skip_298:
+  br i1 %se3, label %block_29C, label %block_29A
+
+skip_29E:
+  br i1 %se4, label %block_2A2, label %block_2A0
+
+fault_2A6:
+  %field155 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 678, i16* %field155
+  %field156 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr17 = getelementptr inbounds [16 x i8], [16 x i8]* %field156, i32 0, i32 0
+  store i8 32, i8* %v0_ptr17
+  call void @chip8_rt_bad_stack(%chip8.state* %state)
+  br label %exit
+
+ok_2A6:
+  %field157 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  store i16 %sp3, i16* %field157
+  %field158 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 6
+  %slot1 = getelementptr inbounds [16 x i16], [16 x i16]* %field158, i32 0, i16 %sp3
+  store i16 678, i16* %slot1
+  %field159 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 724, i16* %field159
+  %field160 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr18 = getelementptr inbounds [16 x i8], [16 x i8]* %field160, i32 0, i32 0
+  store i8 32, i8* %v0_ptr18
+  %running83 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running83, label %call_2A6, label %exit
+
+call_2A6:
+  %returned1 = call i1 @chip8_sub_2D4(%chip8.state* %state)
+  br i1 %returned1, label %back_2A6, label %exit
+
+back_2A6:
+  %field161 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  %next14 = load i16, i16* %field161
+  %expected1 = icmp eq i16 %next14, 680
+  br i1 %expected1, label %block_2A8, label %dispatch
+
+fault_2AA:
+  %field165 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 682, i16* %field165
+  %field166 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %ve_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field166, i32 0, i32 14
+  store i8 %add7, i8* %ve_ptr3
+  %field167 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr16 = getelementptr inbounds [16 x i8], [16 x i8]* %field167, i32 0, i32 15
+  store i8 %vf7, i8* %vf_ptr16
+  call void @chip8_rt_bad_stack(%chip8.state* %state)
+  br label %exit
+
+ok_2AA:
+  %field168 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  store i16 %sp5, i16* %field168
+  %field169 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 6
+  %slot2 = getelementptr inbounds [16 x i16], [16 x i16]* %field169, i32 0, i16 %sp5
+  store i16 682, i16* %slot2
+  %field170 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 724, i16* %field170
+  %field171 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %ve_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field171, i32 0, i32 14
+  store i8 %add7, i8* %ve_ptr4
+  %field172 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr17 = getelementptr inbounds [16 x i8], [16 x i8]* %field172, i32 0, i32 15
+  store i8 %vf7, i8* %vf_ptr17
+  %running85 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running85, label %call_2AA, label %exit
+
+call_2AA:
+  %returned2 = call i1 @chip8_sub_2D4(%chip8.state* %state)
+  br i1 %returned2, label %back_2AA, label %exit
+
+back_2AA:
+  %field173 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  %next15 = load i16, i16* %field173
+  %expected2 = icmp eq i16 %next15, 684
+  br i1 %expected2, label %block_2AC, label %dispatch
+
+skip_2AE:
+  br i1 %se5, label %block_2B2, label %block_2B0
+
+op_2B2:
+  br label %block_2B2
+
+skip_2B4:
+  br i1 %se6, label %block_2B8, label %block_2B6
+
+op_2B8:
+  br label %block_2B8
+
+skip_2BC:
+  br i1 %sne4, label %block_2C0, label %block_2BE
+
+op_2C0:
+  br label %block_2C0
+
+skip_2C4:
+  br i1 %sne5, label %block_2C8, label %block_2C6
+
+op_2C8:
+  br label %block_2C8
+
+skip_2CE:
+  br i1 %sne6, label %block_2D2, label %block_2D0
+
+op_2D2:
+  br label %block_2D2
+
+op_2E2:
+  %add12 = add i8 20, 21
+  %running113 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %vx1 = zext i8 %mem2 to i16
+  %font1 = mul i16 %vx1, 5
+  %running114 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field221 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 742, i16* %field221
+  %i3 = zext i16 %font1 to i32
+  %field222 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 %font1, i16* %field222
+  %collision10 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %add12, i8 0, i8 5)
+  %field223 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 744, i16* %field223
+  %field224 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v4_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field224, i32 0, i32 4
+  store i8 %add12, i8* %v4_ptr1
+  %field225 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr19 = getelementptr inbounds [16 x i8], [16 x i8]* %field225, i32 0, i32 15
+  store i8 %collision10, i8* %vf_ptr19
+  %running115 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running115, label %op_2E8, label %exit
+
+op_2E8:
+  %field226 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  %sp6 = load i16, i16* %field226
+  %empty = icmp eq i16 %sp6, 0
+  br i1 %empty, label %fault_2E8, label %ok_2E8
+
+fault_2E8:
+  %field227 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 744, i16* %field227
+  call void @chip8_rt_bad_stack(%chip8.state* %state)
+  br label %exit
+
+ok_2E8:
+  %field228 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 6
+  %slot3 = getelementptr inbounds [16 x i16], [16 x i16]* %field228, i32 0, i16 %sp6
+  %ret_addr = load i16, i16* %slot3
+  %sp7 = sub i16 %sp6, 1
+  %field229 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  store i16 %sp7, i16* %field229
+  %next21 = add i16 %ret_addr, 2
+  %field230 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next21, i16* %field230
+  %running116 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running116, label %dispatch, label %exit
+}
+
+define internal i1 @chip8_sub_2D4(%chip8.state* %state) {
+entry:
+  br label %block_2D4
+
+leave:
+  ret i1 false
+
+unresolved:
+  call void @chip8_rt_unresolved(%chip8.state* %state)
+  br label %leave
+
+returned:
+  ret i1 true
+
+block_2D4:
+  %running = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %i = zext i16 754 to i32
+  %field = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %ve_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field, i32 0, i32 14
+  %ve = load i8, i8* %ve_ptr
+  %hundreds = udiv i8 %ve, 100
+  %tens = udiv i8 %ve, 10
+  %tens1 = urem i8 %tens, 10
+  %ones = urem i8 %ve, 10
+  %field1 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr = add i32 %i, 0
+  %mem_ptr = getelementptr inbounds [4096 x i8], [4096 x i8]* %field1, i32 0, i32 %addr
+  store i8 %hundreds, i8* %mem_ptr
+  %field2 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr1 = add i32 %i, 1
+  %mem_ptr1 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field2, i32 0, i32 %addr1
+  store i8 %tens1, i8* %mem_ptr1
+  %field3 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr2 = add i32 %i, 2
+  %mem_ptr2 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field3, i32 0, i32 %addr2
+  store i8 %ones, i8* %mem_ptr2
+  %running1 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %i1 = zext i16 754 to i32
+  %field4 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr3 = add i32 %i1, 0
+  %mem_ptr3 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field4, i32 0, i32 %addr3
+  %mem = load i8, i8* %mem_ptr3
+  %field5 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr4 = add i32 %i1, 1
+  %mem_ptr4 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field5, i32 0, i32 %addr4
+  %mem1 = load i8, i8* %mem_ptr4
+  %field6 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr5 = add i32 %i1, 2
+  %mem_ptr5 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field6, i32 0, i32 %addr5
+  %mem2 = load i8, i8* %mem_ptr5
+  %running2 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %vx = zext i8 %mem1 to i16
+  %font = mul i16 %vx, 5
+  %running3 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running4 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running5 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field7 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 736, i16* %field7
+  %i2 = zext i16 %font to i32
+  %field8 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 %font, i16* %field8
+  %collision = call i8 @chip8_rt_draw(%chip8.state* %state, i8 20, i8 0, i8 5)
+  %field9 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 738, i16* %field9
+  %field10 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field10, i32 0, i32 0
+  store i8 %mem, i8* %v0_ptr
+  %field11 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field11, i32 0, i32 1
+  store i8 %mem1, i8* %v1_ptr
+  %field12 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v2_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field12, i32 0, i32 2
+  store i8 %mem2, i8* %v2_ptr
+  %field13 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v4_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field13, i32 0, i32 4
+  store i8 20, i8* %v4_ptr
+  %field14 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v5_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field14, i32 0, i32 5
+  store i8 0, i8* %v5_ptr
+  %field15 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field15, i32 0, i32 15
+  store i8 %collision, i8* %vf_ptr
+  %running6 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running6, label %op_2E2, label %leave
+
+op_2E2:
+  %add = add i8 20, 21
+  %running7 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %vx1 = zext i8 %mem2 to i16
+  %font1 = mul i16 %vx1, 5
+  %running8 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field16 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 742, i16* %field16
+  %i3 = zext i16 %font1 to i32
+  %field17 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 %font1, i16* %field17
+  %collision1 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %add, i8 0, i8 5)
+  %field18 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 744, i16* %field18
+  %field19 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v4_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field19, i32 0, i32 4
+  store i8 %add, i8* %v4_ptr1
+  %field20 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field20, i32 0, i32 15
+  store i8 %collision1, i8* %vf_ptr1
+  %running9 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running9, label %op_2E8, label %leave
+
+op_2E8:
+  %field21 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  %sp = load i16, i16* %field21
+  %empty = icmp eq i16 %sp, 0
+  br i1 %empty, label %fault_2E8, label %ok_2E8
+
+fault_2E8:
+  %field22 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 744, i16* %field22
+  call void @chip8_rt_bad_stack(%chip8.state* %state)
+  br label %leave
+
+ok_2E8:
+  %field23 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 6
+  %slot = getelementptr inbounds [16 x i16], [16 x i16]* %field23, i32 0, i16 %sp
+  %ret_addr = load i16, i16* %slot
+  %sp1 = sub i16 %sp, 1
+  %field24 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  store i16 %sp1, i16* %field24
+  %next = add i16 %ret_addr, 2
+  %field25 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next, i16* %field25
+  %running10 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running10, label %returned, label %leave
+}
+
+define i32 @main() {
+entry:
+  %rom = bitcast [246 x i8]* @chip8_rom to i8*
+  %state = call %chip8.state* @chip8_rt_init(i8* %rom, i32 246)
+  %blocks = bitcast [98 x i16]* @chip8_blocks to i16*
+  call void @chip8_rt_run(%chip8.state* %state, void (%chip8.state*)* @chip8_run, i16* %blocks, i32 49)
+  %code = call i32 @chip8_rt_exit(%chip8.state* %state)
+  ret i32 %code
+}
+
+declare %chip8.state* @chip8_rt_init(i8*, i32)
+declare void @chip8_rt_run(%chip8.state*, void (%chip8.state*)*, i16*, i32)
+declare i32 @chip8_rt_exit(%chip8.state*)
+declare i1 @chip8_rt_tick(%chip8.state*, i1)
+declare void @chip8_rt_cls(%chip8.state*)
+declare i8 @chip8_rt_draw(%chip8.state*, i8, i8, i8)
+declare i8 @chip8_rt_draw_sprite(%chip8.state*, i8, i8, i8*, i8)
+declare i1 @chip8_rt_key_pressed(%chip8.state*, i8)
+declare i8 @chip8_rt_wait_key(%chip8.state*)
+declare i8 @chip8_rt_random(%chip8.state*)
+declare void @chip8_rt_bad_address(%chip8.state*, i16)
+declare void @chip8_rt_bad_stack(%chip8.state*)
+declare void @chip8_rt_unresolved(%chip8.state*)
+declare i1 @chip8_rt_code_written(%chip8.state*, i16, i16)
@@ -0,0 +1,1989 @@
+; ModuleID = 'test_opcode.ch8'
+source_filename = "test_opcode.ch8"
+
+%chip8.state = type { [16 x i8], i16, i16, i16, i8, i8, [16 x i16], [4096 x i8] }
+
+@chip8_rom = private constant [478 x i8] c"\12N\EA\AC\AA\EA\CE\AA\AA\AE\E0\A0\A0\E0\C0@@\E0\E0 \C0\E0\E0` \E0\A0\E0  `@ @\E0\80\E0\E0\E0   \E0\E0\A0\E0\E0\E0 \E0@\A0\E0\A0\E0\C0\80\E0\E0\80\C0\80\A0@\A0\A0\A2\02\DA\B4\00\EE\A2\02\DA\B4\13\DCh\01i\05j\0Ak\01e*f+\A2\16\D8\B4\A2>\D9\B4\A2\026+\A2\06\DA\B4k\06\A2\1A\D8\B4\A2>\D9\B4\A2\06E*\A2\02\DA\B4k\0B\A2\1E\D8\B4\A2>\D9\B4\A2\06U`\A2\02\DA\B4k\10\A2&\D8\B4\A2>\D9\B4\A2\06v\FFF*\A2\02\DA\B4k\15\A2.\D8\B4\A2>\D9\B4\A2\06\95`\A2\02\DA\B4k\1A\A22\D8\B4\A2>\D9\B4\22Bh\17i\1Bj k\01\A2\0A\D8\B4\A26\D9\B4\A2\02\DA\B4k\06\A2*\D8\B4\A2\0A\D9\B4\A2\06\87PG*\A2\02\DA\B4k\0B\A2*\D8\B4\A2\0E\D9\B4\A2\06g*\87\B1G+\A2\02\DA\B4k\10\A2*\D8\B4\A2\12\D9\B4\A2\06fxg\1F\87bG\18\A2\02\DA\B4k\15\A2*\D8\B4\A2\16\D9\B4\A2\06fxg\1F\87cGg\A2\02\DA\B4k\1A\A2*\D8\B4\A2\1A\D9\B4\A2\06f\8Cg\8C\87dG\18\A2\02\DA\B4h,i0j4k\01\A2*\D8\B4\A2\1E\D9\B4\A2\06f\8Cgx\87eG\EC\A2\02\DA\B4k\06\A2*\D8\B4\A2\22\D9\B4\A2\06f\E0\86nF\C0\A2\02\DA\B4k\0B\A2*\D8\B4\A26\D9\B4\A2\06f\0F\86fF\07\A2\02\DA\B4k\10\A2:\D8\B4\A2\1E\D9\B4\A3\E8`\00a0\F1U\A3\E9\F0e\A2\06@0\A2\02\DA\B4k\15\A2:\D8\B4\A2\16\D9\B4\A3\E8f\89\F63\F2e\A2\020\01\A2\061\03\A2\062\07\A2\06\DA\B4k\1A\A2\0E\D8\B4\A2>\D9\B4\12H\13\DC"
+@chip8_blocks = private constant [80 x i16] [i16 512, i16 514, i16 578, i16 584, i16 584, i16 590, i16 590, i16 614, i16 614, i16 616, i16 616, i16 632, i16 632, i16 634, i16 634, i16 650, i16 650, i16 652, i16 652, i16 670, i16 670, i16 672, i16 672, i16 688, i16 688, i16 690, i16 690, i16 704, i16 704, i16 740, i16 740, i16 742, i16 742, i16 762, i16 762, i16 764, i16 764, i16 786, i16 786, i16 788, i16 788, i16 810, i16 810, i16 812, i16 812, i16 834, i16 834, i16 836, i16 836, i16 864, i16 864, i16 866, i16 866, i16 886, i16 886, i16 888, i16 888, i16 908, i16 908, i16 910, i16 910, i16 938, i16 938, i16 940, i16 940, i16 964, i16 964, i16 966, i16 966, i16 968, i16 968, i16 970, i16 970, i16 972, i16 972, i16 974, i16 974, i16 988, i16 988, i16 990]
+
+define void @chip8_run(%chip8.state* %state) {
+entry:
+  br label %dispatch
+
+dispatch:
+  %field = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  %pc = load i16, i16* %field
+  switch i16 %pc, label %unresolved [
+    i16 512, label %block_200
+    i16 578, label %block_242
+    i16 584, label %block_248
+    i16 590, label %block_24E
+    i16 614, label %block_266
+    i16 616, label %block_268
+    i16 632, label %block_278
+    i16 634, label %block_27A
+    i16 650, label %block_28A
+    i16 652, label %block_28C
+    i16 670, label %block_29E
+    i16 672, label %block_2A0
+    i16 688, label %block_2B0
+    i16 690, label %block_2B2
+    i16 704, label %block_2C0
+    i16 740, label %block_2E4
+    i16 742, label %block_2E6
+    i16 762, label %block_2FA
+    i16 764, label %block_2FC
+    i16 786, label %block_312
+    i16 788, label %block_314
+    i16 810, label %block_32A
+    i16 812, label %block_32C
+    i16 834, label %block_342
+    i16 836, label %block_344
+    i16 864, label %block_360
+    i16 866, label %block_362
+    i16 886, label %block_376
+    i16 888, label %block_378
+    i16 908, label %block_38C
+    i16 910, label %block_38E
+    i16 938, label %block_3AA
+    i16 940, label %block_3AC
+    i16 964, label %block_3C4
+    i16 966, label %block_3C6
+    i16 968, label %block_3C8
+    i16 970, label %block_3CA
+    i16 972, label %block_3CC
+    i16 974, label %block_3CE
+    i16 988, label %block_3DC
+  ]
+
+unresolved:
+  call void @chip8_rt_unresolved(%chip8.state* %state)
+  br label %exit
+
+exit:
+  ret void
+
+block_200:
+  %field1 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 590, i16* %field1
+  %running = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running, label %block_24E, label %exit
+
+block_242:
+  %running1 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field2 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field2, i32 0, i32 10
+  %va = load i8, i8* %va_ptr
+  %field3 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field3, i32 0, i32 11
+  %vb = load i8, i8* %vb_ptr
+  %field4 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 580, i16* %field4
+  %sprite = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 2
+  %collision = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %va, i8 %vb, i8* %sprite, i8 4)
+  %field5 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 582, i16* %field5
+  %field6 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field6, i32 0, i32 15
+  store i8 %collision, i8* %vf_ptr
+  %field7 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field7
+  %running2 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running2, label %op_246, label %exit
+
+block_248:
+  %running4 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field13 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field13, i32 0, i32 10
+  %va1 = load i8, i8* %va_ptr1
+  %field14 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field14, i32 0, i32 11
+  %vb1 = load i8, i8* %vb_ptr1
+  %field15 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 586, i16* %field15
+  %sprite1 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 2
+  %collision1 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %va1, i8 %vb1, i8* %sprite1, i8 4)
+  %field16 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 588, i16* %field16
+  %field17 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field17, i32 0, i32 15
+  store i8 %collision1, i8* %vf_ptr1
+  %field18 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field18
+  %running5 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running5, label %op_24C, label %exit
+
+block_24E:
+  %running7 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running8 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running9 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running10 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running11 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running12 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running13 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field20 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 604, i16* %field20
+  %sprite2 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 22
+  %collision2 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 1, i8 1, i8* %sprite2, i8 4)
+  %field21 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 606, i16* %field21
+  %field22 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v5_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field22, i32 0, i32 5
+  store i8 42, i8* %v5_ptr
+  %field23 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field23, i32 0, i32 6
+  store i8 43, i8* %v6_ptr
+  %field24 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field24, i32 0, i32 8
+  store i8 1, i8* %v8_ptr
+  %field25 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field25, i32 0, i32 9
+  store i8 5, i8* %v9_ptr
+  %field26 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field26, i32 0, i32 10
+  store i8 10, i8* %va_ptr2
+  %field27 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field27, i32 0, i32 11
+  store i8 1, i8* %vb_ptr2
+  %field28 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field28, i32 0, i32 15
+  store i8 %collision2, i8* %vf_ptr2
+  %field29 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 534, i16* %field29
+  %running14 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running14, label %op_25E, label %exit
+
+block_266:
+  %field36 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 616, i16* %field36
+  %field37 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field37
+  %running19 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running19, label %op_268, label %exit
+
+block_268:
+  %field38 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field38, i32 0, i32 10
+  %va2 = load i8, i8* %va_ptr3
+  %field39 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field39, i32 0, i32 11
+  %vb2 = load i8, i8* %vb_ptr3
+  %field40 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 616, i16* %field40
+  %field41 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i = load i16, i16* %field41
+  %i1 = zext i16 %i to i32
+  %collision4 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va2, i8 %vb2, i8 4)
+  %field42 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 618, i16* %field42
+  %field43 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field43, i32 0, i32 15
+  store i8 %collision4, i8* %vf_ptr4
+  %running20 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running20, label %op_26A, label %exit
+
+block_278:
+  %field58 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 634, i16* %field58
+  %field59 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field59
+  %running28 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running28, label %op_27A, label %exit
+
+block_27A:
+  %field60 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field60, i32 0, i32 10
+  %va3 = load i8, i8* %va_ptr4
+  %field61 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field61, i32 0, i32 11
+  %vb3 = load i8, i8* %vb_ptr5
+  %field62 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 634, i16* %field62
+  %field63 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i2 = load i16, i16* %field63
+  %i3 = zext i16 %i2 to i32
+  %collision7 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va3, i8 %vb3, i8 4)
+  %field64 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 636, i16* %field64
+  %field65 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field65, i32 0, i32 15
+  store i8 %collision7, i8* %vf_ptr7
+  %running29 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running29, label %op_27C, label %exit
+
+block_28A:
+  %field81 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 652, i16* %field81
+  %field82 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field82
+  %running37 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running37, label %op_28C, label %exit
+
+block_28C:
+  %field83 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field83, i32 0, i32 10
+  %va4 = load i8, i8* %va_ptr5
+  %field84 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field84, i32 0, i32 11
+  %vb4 = load i8, i8* %vb_ptr7
+  %field85 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 652, i16* %field85
+  %field86 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i4 = load i16, i16* %field86
+  %i5 = zext i16 %i4 to i32
+  %collision10 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va4, i8 %vb4, i8 4)
+  %field87 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 654, i16* %field87
+  %field88 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr10 = getelementptr inbounds [16 x i8], [16 x i8]* %field88, i32 0, i32 15
+  store i8 %collision10, i8* %vf_ptr10
+  %running38 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running38, label %op_28E, label %exit
+
+block_29E:
+  %field104 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 672, i16* %field104
+  %field105 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field105
+  %running47 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running47, label %op_2A0, label %exit
+
+block_2A0:
+  %field106 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field106, i32 0, i32 10
+  %va5 = load i8, i8* %va_ptr6
+  %field107 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr9 = getelementptr inbounds [16 x i8], [16 x i8]* %field107, i32 0, i32 11
+  %vb5 = load i8, i8* %vb_ptr9
+  %field108 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 672, i16* %field108
+  %field109 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i6 = load i16, i16* %field109
+  %i7 = zext i16 %i6 to i32
+  %collision13 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va5, i8 %vb5, i8 4)
+  %field110 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 674, i16* %field110
+  %field111 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr13 = getelementptr inbounds [16 x i8], [16 x i8]* %field111, i32 0, i32 15
+  store i8 %collision13, i8* %vf_ptr13
+  %running48 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running48, label %op_2A2, label %exit
+
+block_2B0:
+  %field127 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 690, i16* %field127
+  %field128 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field128
+  %running56 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running56, label %op_2B2, label %exit
+
+block_2B2:
+  %field129 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field129, i32 0, i32 10
+  %va6 = load i8, i8* %va_ptr7
+  %field130 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr11 = getelementptr inbounds [16 x i8], [16 x i8]* %field130, i32 0, i32 11
+  %vb6 = load i8, i8* %vb_ptr11
+  %field131 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 690, i16* %field131
+  %field132 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i8 = load i16, i16* %field132
+  %i9 = zext i16 %i8 to i32
+  %collision16 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va6, i8 %vb6, i8 4)
+  %field133 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 692, i16* %field133
+  %field134 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr16 = getelementptr inbounds [16 x i8], [16 x i8]* %field134, i32 0, i32 15
+  store i8 %collision16, i8* %vf_ptr16
+  %running57 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running57, label %op_2B4, label %exit
+
+block_2C0:
+  %running64 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running65 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running66 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running67 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running68 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field152 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 714, i16* %field152
+  %sprite14 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 10
+  %collision19 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 23, i8 1, i8* %sprite14, i8 4)
+  %field153 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 716, i16* %field153
+  %field154 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field154, i32 0, i32 8
+  store i8 23, i8* %v8_ptr6
+  %field155 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field155, i32 0, i32 9
+  store i8 27, i8* %v9_ptr6
+  %field156 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field156, i32 0, i32 10
+  store i8 32, i8* %va_ptr8
+  %field157 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr13 = getelementptr inbounds [16 x i8], [16 x i8]* %field157, i32 0, i32 11
+  store i8 1, i8* %vb_ptr13
+  %field158 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr19 = getelementptr inbounds [16 x i8], [16 x i8]* %field158, i32 0, i32 15
+  store i8 %collision19, i8* %vf_ptr19
+  %field159 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 522, i16* %field159
+  %running69 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running69, label %op_2CC, label %exit
+
+block_2E4:
+  %field181 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 742, i16* %field181
+  %field182 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field182
+  %running82 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running82, label %op_2E6, label %exit
+
+block_2E6:
+  %field183 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr9 = getelementptr inbounds [16 x i8], [16 x i8]* %field183, i32 0, i32 10
+  %va7 = load i8, i8* %va_ptr9
+  %field184 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr15 = getelementptr inbounds [16 x i8], [16 x i8]* %field184, i32 0, i32 11
+  %vb7 = load i8, i8* %vb_ptr15
+  %field185 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 742, i16* %field185
+  %field186 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i10 = load i16, i16* %field186
+  %i11 = zext i16 %i10 to i32
+  %collision24 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va7, i8 %vb7, i8 4)
+  %field187 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 744, i16* %field187
+  %field188 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr24 = getelementptr inbounds [16 x i8], [16 x i8]* %field188, i32 0, i32 15
+  store i8 %collision24, i8* %vf_ptr24
+  %running83 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running83, label %op_2E8, label %exit
+
+block_2FA:
+  %field203 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 764, i16* %field203
+  %field204 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field204
+  %running93 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running93, label %op_2FC, label %exit
+
+block_2FC:
+  %field205 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr10 = getelementptr inbounds [16 x i8], [16 x i8]* %field205, i32 0, i32 10
+  %va8 = load i8, i8* %va_ptr10
+  %field206 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr17 = getelementptr inbounds [16 x i8], [16 x i8]* %field206, i32 0, i32 11
+  %vb8 = load i8, i8* %vb_ptr17
+  %field207 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 764, i16* %field207
+  %field208 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i12 = load i16, i16* %field208
+  %i13 = zext i16 %i12 to i32
+  %collision27 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va8, i8 %vb8, i8 4)
+  %field209 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 766, i16* %field209
+  %field210 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr27 = getelementptr inbounds [16 x i8], [16 x i8]* %field210, i32 0, i32 15
+  store i8 %collision27, i8* %vf_ptr27
+  %running94 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running94, label %op_2FE, label %exit
+
+block_312:
+  %field226 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 788, i16* %field226
+  %field227 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field227
+  %running105 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running105, label %op_314, label %exit
+
+block_314:
+  %field228 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr11 = getelementptr inbounds [16 x i8], [16 x i8]* %field228, i32 0, i32 10
+  %va9 = load i8, i8* %va_ptr11
+  %field229 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr19 = getelementptr inbounds [16 x i8], [16 x i8]* %field229, i32 0, i32 11
+  %vb9 = load i8, i8* %vb_ptr19
+  %field230 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 788, i16* %field230
+  %field231 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i14 = load i16, i16* %field231
+  %i15 = zext i16 %i14 to i32
+  %collision30 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va9, i8 %vb9, i8 4)
+  %field232 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 790, i16* %field232
+  %field233 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr30 = getelementptr inbounds [16 x i8], [16 x i8]* %field233, i32 0, i32 15
+  store i8 %collision30, i8* %vf_ptr30
+  %running106 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running106, label %op_316, label %exit
+
+block_32A:
+  %field249 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 812, i16* %field249
+  %field250 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field250
+  %running117 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running117, label %op_32C, label %exit
+
+block_32C:
+  %field251 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr12 = getelementptr inbounds [16 x i8], [16 x i8]* %field251, i32 0, i32 10
+  %va10 = load i8, i8* %va_ptr12
+  %field252 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr21 = getelementptr inbounds [16 x i8], [16 x i8]* %field252, i32 0, i32 11
+  %vb10 = load i8, i8* %vb_ptr21
+  %field253 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 812, i16* %field253
+  %field254 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i16 = load i16, i16* %field254
+  %i17 = zext i16 %i16 to i32
+  %collision33 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va10, i8 %vb10, i8 4)
+  %field255 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 814, i16* %field255
+  %field256 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr33 = getelementptr inbounds [16 x i8], [16 x i8]* %field256, i32 0, i32 15
+  store i8 %collision33, i8* %vf_ptr33
+  %running118 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running118, label %op_32E, label %exit
+
+block_342:
+  %field273 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 836, i16* %field273
+  %field274 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field274
+  %running129 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running129, label %op_344, label %exit
+
+block_344:
+  %field275 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr13 = getelementptr inbounds [16 x i8], [16 x i8]* %field275, i32 0, i32 10
+  %va11 = load i8, i8* %va_ptr13
+  %field276 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr23 = getelementptr inbounds [16 x i8], [16 x i8]* %field276, i32 0, i32 11
+  %vb11 = load i8, i8* %vb_ptr23
+  %field277 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 836, i16* %field277
+  %field278 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i18 = load i16, i16* %field278
+  %i19 = zext i16 %i18 to i32
+  %collision36 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va11, i8 %vb11, i8 4)
+  %field279 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 838, i16* %field279
+  %field280 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr37 = getelementptr inbounds [16 x i8], [16 x i8]* %field280, i32 0, i32 15
+  store i8 %collision36, i8* %vf_ptr37
+  %running130 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running130, label %op_346, label %exit
+
+block_360:
+  %field298 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 866, i16* %field298
+  %field299 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field299
+  %running144 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running144, label %op_362, label %exit
+
+block_362:
+  %field300 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr15 = getelementptr inbounds [16 x i8], [16 x i8]* %field300, i32 0, i32 10
+  %va12 = load i8, i8* %va_ptr15
+  %field301 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr25 = getelementptr inbounds [16 x i8], [16 x i8]* %field301, i32 0, i32 11
+  %vb12 = load i8, i8* %vb_ptr25
+  %field302 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 866, i16* %field302
+  %field303 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i20 = load i16, i16* %field303
+  %i21 = zext i16 %i20 to i32
+  %collision39 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va12, i8 %vb12, i8 4)
+  %field304 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 868, i16* %field304
+  %field305 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr41 = getelementptr inbounds [16 x i8], [16 x i8]* %field305, i32 0, i32 15
+  store i8 %collision39, i8* %vf_ptr41
+  %running145 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running145, label %op_364, label %exit
+
+block_376:
+  %field321 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 888, i16* %field321
+  %field322 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field322
+  %running155 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running155, label %op_378, label %exit
+
+block_378:
+  %field323 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr16 = getelementptr inbounds [16 x i8], [16 x i8]* %field323, i32 0, i32 10
+  %va13 = load i8, i8* %va_ptr16
+  %field324 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr27 = getelementptr inbounds [16 x i8], [16 x i8]* %field324, i32 0, i32 11
+  %vb13 = load i8, i8* %vb_ptr27
+  %field325 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 888, i16* %field325
+  %field326 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i22 = load i16, i16* %field326
+  %i23 = zext i16 %i22 to i32
+  %collision42 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va13, i8 %vb13, i8 4)
+  %field327 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 890, i16* %field327
+  %field328 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr45 = getelementptr inbounds [16 x i8], [16 x i8]* %field328, i32 0, i32 15
+  store i8 %collision42, i8* %vf_ptr45
+  %running156 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running156, label %op_37A, label %exit
+
+block_38C:
+  %field344 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 910, i16* %field344
+  %field345 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field345
+  %running166 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running166, label %op_38E, label %exit
+
+block_38E:
+  %field346 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr17 = getelementptr inbounds [16 x i8], [16 x i8]* %field346, i32 0, i32 10
+  %va14 = load i8, i8* %va_ptr17
+  %field347 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr29 = getelementptr inbounds [16 x i8], [16 x i8]* %field347, i32 0, i32 11
+  %vb14 = load i8, i8* %vb_ptr29
+  %field348 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 910, i16* %field348
+  %field349 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i24 = load i16, i16* %field349
+  %i25 = zext i16 %i24 to i32
+  %collision45 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va14, i8 %vb14, i8 4)
+  %field350 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 912, i16* %field350
+  %field351 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr49 = getelementptr inbounds [16 x i8], [16 x i8]* %field351, i32 0, i32 15
+  store i8 %collision45, i8* %vf_ptr49
+  %running167 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running167, label %op_390, label %exit
+
+block_3AA:
+  %field370 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 940, i16* %field370
+  %field371 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field371
+  %running181 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running181, label %op_3AC, label %exit
+
+block_3AC:
+  %field372 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr18 = getelementptr inbounds [16 x i8], [16 x i8]* %field372, i32 0, i32 10
+  %va15 = load i8, i8* %va_ptr18
+  %field373 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr31 = getelementptr inbounds [16 x i8], [16 x i8]* %field373, i32 0, i32 11
+  %vb15 = load i8, i8* %vb_ptr31
+  %field374 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 940, i16* %field374
+  %field375 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i28 = load i16, i16* %field375
+  %i29 = zext i16 %i28 to i32
+  %collision48 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va15, i8 %vb15, i8 4)
+  %field376 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 942, i16* %field376
+  %field377 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr52 = getelementptr inbounds [16 x i8], [16 x i8]* %field377, i32 0, i32 15
+  store i8 %collision48, i8* %vf_ptr52
+  %running182 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running182, label %op_3AE, label %exit
+
+block_3C4:
+  %field401 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 966, i16* %field401
+  %field402 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field402
+  %running194 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running194, label %op_3C6, label %exit
+
+block_3C6:
+  %field403 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field403, i32 0, i32 1
+  %v1 = load i8, i8* %v1_ptr2
+  %se3 = icmp eq i8 %v1, 3
+  %next17 = select i1 %se3, i16 970, i16 968
+  %field404 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next17, i16* %field404
+  %running195 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running195, label %skip_3C6, label %exit
+
+block_3C8:
+  %field405 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 970, i16* %field405
+  %field406 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field406
+  %running196 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running196, label %op_3CA, label %exit
+
+block_3CA:
+  %field407 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v2_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field407, i32 0, i32 2
+  %v2 = load i8, i8* %v2_ptr1
+  %se4 = icmp eq i8 %v2, 7
+  %next18 = select i1 %se4, i16 974, i16 972
+  %field408 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next18, i16* %field408
+  %running197 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running197, label %skip_3CA, label %exit
+
+block_3CC:
+  %field409 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 974, i16* %field409
+  %field410 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field410
+  %running198 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running198, label %op_3CE, label %exit
+
+block_3CE:
+  %field411 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr19 = getelementptr inbounds [16 x i8], [16 x i8]* %field411, i32 0, i32 10
+  %va16 = load i8, i8* %va_ptr19
+  %field412 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr33 = getelementptr inbounds [16 x i8], [16 x i8]* %field412, i32 0, i32 11
+  %vb16 = load i8, i8* %vb_ptr33
+  %field413 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 974, i16* %field413
+  %field414 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  %i32 = load i16, i16* %field414
+  %i33 = zext i16 %i32 to i32
+  %collision51 = call i8 @chip8_rt_draw(%chip8.state* %state, i8 %va16, i8 %vb16, i8 4)
+  %field415 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 976, i16* %field415
+  %field416 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr55 = getelementptr inbounds [16 x i8], [16 x i8]* %field416, i32 0, i32 15
+  store i8 %collision51, i8* %vf_ptr55
+  %running199 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running199, label %op_3D0, label %exit
+
+block_3DC:
+  %field429 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 988, i16* %field429
+  %running206 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running206, label %block_3DC, label %exit
+
+op_246:
+  %field8 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  %sp = load i16, i16* %field8
+  %empty = icmp eq i16 %sp, 0
+  br i1 %empty, label %fault_246, label %ok_246
+
+fault_246:
+  %field9 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 582, i16* %field9
+  call void @chip8_rt_bad_stack(%chip8.state* %state)
+  br label %exit
+
+ok_246:
+  %field10 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 6
+  %slot = getelementptr inbounds [16 x i16], [16 x i16]* %field10, i32 0, i16 %sp
+  %ret_addr = load i16, i16* %slot
+  %sp1 = sub i16 %sp, 1
+  %field11 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  store i16 %sp1, i16* %field11
+  %next = add i16 %ret_addr, 2
+  %field12 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next, i16* %field12
+  %running3 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running3, label %dispatch, label %exit
+
+op_24C:
+  %field19 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 988, i16* %field19
+  %running6 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running6, label %block_3DC, label %exit
+
+op_25E:
+  %running15 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field30 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 608, i16* %field30
+  %sprite3 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 62
+  %collision3 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 5, i8 1, i8* %sprite3, i8 4)
+  %field31 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 610, i16* %field31
+  %field32 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field32, i32 0, i32 15
+  store i8 %collision3, i8* %vf_ptr3
+  %field33 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 574, i16* %field33
+  %running16 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running16, label %op_262, label %exit
+
+op_262:
+  %running17 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %se = icmp eq i8 43, 43
+  %next1 = select i1 %se, i16 616, i16 614
+  %field34 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next1, i16* %field34
+  %field35 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field35
+  %running18 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running18, label %skip_264, label %exit
+
+skip_264:
+  br i1 %se, label %block_268, label %block_266
+
+op_268:
+  br label %block_268
+
+op_26A:
+  %running21 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running22 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field44 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field44, i32 0, i32 8
+  %v8 = load i8, i8* %v8_ptr1
+  %field45 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 622, i16* %field45
+  %sprite4 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 26
+  %collision5 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v8, i8 6, i8* %sprite4, i8 4)
+  %field46 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 624, i16* %field46
+  %field47 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field47, i32 0, i32 11
+  store i8 6, i8* %vb_ptr4
+  %field48 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field48, i32 0, i32 15
+  store i8 %collision5, i8* %vf_ptr5
+  %field49 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 538, i16* %field49
+  %running23 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running23, label %op_270, label %exit
+
+op_270:
+  %running24 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field50 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field50, i32 0, i32 9
+  %v9 = load i8, i8* %v9_ptr1
+  %field51 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 626, i16* %field51
+  %sprite5 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 62
+  %collision6 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v9, i8 6, i8* %sprite5, i8 4)
+  %field52 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 628, i16* %field52
+  %field53 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field53, i32 0, i32 15
+  store i8 %collision6, i8* %vf_ptr6
+  %field54 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 574, i16* %field54
+  %running25 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running25, label %op_274, label %exit
+
+op_274:
+  %running26 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field55 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v5_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field55, i32 0, i32 5
+  %v5 = load i8, i8* %v5_ptr1
+  %sne = icmp ne i8 %v5, 42
+  %next2 = select i1 %sne, i16 634, i16 632
+  %field56 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next2, i16* %field56
+  %field57 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field57
+  %running27 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running27, label %skip_276, label %exit
+
+skip_276:
+  br i1 %sne, label %block_27A, label %block_278
+
+op_27A:
+  br label %block_27A
+
+op_27C:
+  %running30 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running31 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field66 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field66, i32 0, i32 8
+  %v81 = load i8, i8* %v8_ptr2
+  %field67 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 640, i16* %field67
+  %sprite6 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 30
+  %collision8 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v81, i8 11, i8* %sprite6, i8 4)
+  %field68 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 642, i16* %field68
+  %field69 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field69, i32 0, i32 11
+  store i8 11, i8* %vb_ptr6
+  %field70 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field70, i32 0, i32 15
+  store i8 %collision8, i8* %vf_ptr8
+  %field71 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 542, i16* %field71
+  %running32 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running32, label %op_282, label %exit
+
+op_282:
+  %running33 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field72 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field72, i32 0, i32 9
+  %v91 = load i8, i8* %v9_ptr2
+  %field73 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 644, i16* %field73
+  %sprite7 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 62
+  %collision9 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v91, i8 11, i8* %sprite7, i8 4)
+  %field74 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 646, i16* %field74
+  %field75 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr9 = getelementptr inbounds [16 x i8], [16 x i8]* %field75, i32 0, i32 15
+  store i8 %collision9, i8* %vf_ptr9
+  %field76 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 574, i16* %field76
+  %running34 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running34, label %op_286, label %exit
+
+op_286:
+  %running35 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field77 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v5_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field77, i32 0, i32 5
+  %v51 = load i8, i8* %v5_ptr2
+  %field78 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field78, i32 0, i32 6
+  %v6 = load i8, i8* %v6_ptr1
+  %se1 = icmp eq i8 %v51, %v6
+  %next3 = select i1 %se1, i16 652, i16 650
+  %field79 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next3, i16* %field79
+  %field80 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field80
+  %running36 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running36, label %skip_288, label %exit
+
+skip_288:
+  br i1 %se1, label %block_28C, label %block_28A
+
+op_28C:
+  br label %block_28C
+
+op_28E:
+  %running39 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running40 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field89 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field89, i32 0, i32 8
+  %v82 = load i8, i8* %v8_ptr3
+  %field90 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 658, i16* %field90
+  %sprite8 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 38
+  %collision11 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v82, i8 16, i8* %sprite8, i8 4)
+  %field91 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 660, i16* %field91
+  %field92 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field92, i32 0, i32 11
+  store i8 16, i8* %vb_ptr8
+  %field93 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr11 = getelementptr inbounds [16 x i8], [16 x i8]* %field93, i32 0, i32 15
+  store i8 %collision11, i8* %vf_ptr11
+  %field94 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 550, i16* %field94
+  %running41 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running41, label %op_294, label %exit
+
+op_294:
+  %running42 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field95 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field95, i32 0, i32 9
+  %v92 = load i8, i8* %v9_ptr3
+  %field96 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 662, i16* %field96
+  %sprite9 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 62
+  %collision12 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v92, i8 16, i8* %sprite9, i8 4)
+  %field97 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 664, i16* %field97
+  %field98 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr12 = getelementptr inbounds [16 x i8], [16 x i8]* %field98, i32 0, i32 15
+  store i8 %collision12, i8* %vf_ptr12
+  %field99 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 574, i16* %field99
+  %running43 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running43, label %op_298, label %exit
+
+op_298:
+  %running44 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field100 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field100, i32 0, i32 6
+  %v61 = load i8, i8* %v6_ptr2
+  %add = add i8 %v61, -1
+  %running45 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne1 = icmp ne i8 %add, 42
+  %next4 = select i1 %sne1, i16 672, i16 670
+  %field101 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next4, i16* %field101
+  %field102 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field102, i32 0, i32 6
+  store i8 %add, i8* %v6_ptr3
+  %field103 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field103
+  %running46 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running46, label %skip_29C, label %exit
+
+skip_29C:
+  br i1 %sne1, label %block_2A0, label %block_29E
+
+op_2A0:
+  br label %block_2A0
+
+op_2A2:
+  %running49 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running50 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field112 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field112, i32 0, i32 8
+  %v83 = load i8, i8* %v8_ptr4
+  %field113 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 678, i16* %field113
+  %sprite10 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 46
+  %collision14 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v83, i8 21, i8* %sprite10, i8 4)
+  %field114 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 680, i16* %field114
+  %field115 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr10 = getelementptr inbounds [16 x i8], [16 x i8]* %field115, i32 0, i32 11
+  store i8 21, i8* %vb_ptr10
+  %field116 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr14 = getelementptr inbounds [16 x i8], [16 x i8]* %field116, i32 0, i32 15
+  store i8 %collision14, i8* %vf_ptr14
+  %field117 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 558, i16* %field117
+  %running51 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running51, label %op_2A8, label %exit
+
+op_2A8:
+  %running52 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field118 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field118, i32 0, i32 9
+  %v93 = load i8, i8* %v9_ptr4
+  %field119 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 682, i16* %field119
+  %sprite11 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 62
+  %collision15 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v93, i8 21, i8* %sprite11, i8 4)
+  %field120 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 684, i16* %field120
+  %field121 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr15 = getelementptr inbounds [16 x i8], [16 x i8]* %field121, i32 0, i32 15
+  store i8 %collision15, i8* %vf_ptr15
+  %field122 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 574, i16* %field122
+  %running53 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running53, label %op_2AC, label %exit
+
+op_2AC:
+  %running54 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field123 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v5_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field123, i32 0, i32 5
+  %v52 = load i8, i8* %v5_ptr3
+  %field124 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field124, i32 0, i32 6
+  %v62 = load i8, i8* %v6_ptr4
+  %sne2 = icmp ne i8 %v52, %v62
+  %next5 = select i1 %sne2, i16 690, i16 688
+  %field125 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next5, i16* %field125
+  %field126 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field126
+  %running55 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running55, label %skip_2AE, label %exit
+
+skip_2AE:
+  br i1 %sne2, label %block_2B2, label %block_2B0
+
+op_2B2:
+  br label %block_2B2
+
+op_2B4:
+  %running58 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running59 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field135 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field135, i32 0, i32 8
+  %v84 = load i8, i8* %v8_ptr5
+  %field136 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 696, i16* %field136
+  %sprite12 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 50
+  %collision17 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v84, i8 26, i8* %sprite12, i8 4)
+  %field137 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 698, i16* %field137
+  %field138 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr12 = getelementptr inbounds [16 x i8], [16 x i8]* %field138, i32 0, i32 11
+  store i8 26, i8* %vb_ptr12
+  %field139 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr17 = getelementptr inbounds [16 x i8], [16 x i8]* %field139, i32 0, i32 15
+  store i8 %collision17, i8* %vf_ptr17
+  %field140 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 562, i16* %field140
+  %running60 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running60, label %op_2BA, label %exit
+
+op_2BA:
+  %running61 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field141 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field141, i32 0, i32 9
+  %v94 = load i8, i8* %v9_ptr5
+  %field142 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 700, i16* %field142
+  %sprite13 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 62
+  %collision18 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v94, i8 26, i8* %sprite13, i8 4)
+  %field143 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 702, i16* %field143
+  %field144 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr18 = getelementptr inbounds [16 x i8], [16 x i8]* %field144, i32 0, i32 15
+  store i8 %collision18, i8* %vf_ptr18
+  %field145 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 574, i16* %field145
+  %running62 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running62, label %op_2BE, label %exit
+
+op_2BE:
+  %field146 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  %sp2 = load i16, i16* %field146
+  %sp3 = add i16 %sp2, 1
+  %full = icmp uge i16 %sp3, 16
+  br i1 %full, label %fault_2BE, label %ok_2BE
+
+fault_2BE:
+  %field147 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 702, i16* %field147
+  call void @chip8_rt_bad_stack(%chip8.state* %state)
+  br label %exit
+
+ok_2BE:
+  %field148 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  store i16 %sp3, i16* %field148
+  %field149 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 6
+  %slot1 = getelementptr inbounds [16 x i16], [16 x i16]* %field149, i32 0, i16 %sp3
+  store i16 702, i16* %slot1
+  %field150 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 578, i16* %field150
+  %running63 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running63, label %call_2BE, label %exit
+
+call_2BE:
+  %returned = call i1 @chip8_sub_242(%chip8.state* %state)
+  br i1 %returned, label %back_2BE, label %exit
+
+back_2BE:
+  %field151 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  %next6 = load i16, i16* %field151
+  %expected = icmp eq i16 %next6, 704
+  br i1 %expected, label %block_2C0, label %dispatch
+
+op_2CC:
+  %running70 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field160 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 718, i16* %field160
+  %sprite15 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 54
+  %collision20 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 27, i8 1, i8* %sprite15, i8 4)
+  %field161 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 720, i16* %field161
+  %field162 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr20 = getelementptr inbounds [16 x i8], [16 x i8]* %field162, i32 0, i32 15
+  store i8 %collision20, i8* %vf_ptr20
+  %field163 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 566, i16* %field163
+  %running71 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running71, label %op_2D0, label %exit
+
+op_2D0:
+  %running72 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field164 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 722, i16* %field164
+  %sprite16 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 2
+  %collision21 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 32, i8 1, i8* %sprite16, i8 4)
+  %field165 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 724, i16* %field165
+  %field166 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr21 = getelementptr inbounds [16 x i8], [16 x i8]* %field166, i32 0, i32 15
+  store i8 %collision21, i8* %vf_ptr21
+  %field167 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field167
+  %running73 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running73, label %op_2D4, label %exit
+
+op_2D4:
+  %running74 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running75 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field168 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 728, i16* %field168
+  %sprite17 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 42
+  %collision22 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 23, i8 6, i8* %sprite17, i8 4)
+  %field169 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 730, i16* %field169
+  %field170 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr14 = getelementptr inbounds [16 x i8], [16 x i8]* %field170, i32 0, i32 11
+  store i8 6, i8* %vb_ptr14
+  %field171 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr22 = getelementptr inbounds [16 x i8], [16 x i8]* %field171, i32 0, i32 15
+  store i8 %collision22, i8* %vf_ptr22
+  %field172 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 554, i16* %field172
+  %running76 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running76, label %op_2DA, label %exit
+
+op_2DA:
+  %running77 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field173 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 732, i16* %field173
+  %sprite18 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 10
+  %collision23 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 27, i8 6, i8* %sprite18, i8 4)
+  %field174 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 734, i16* %field174
+  %field175 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr23 = getelementptr inbounds [16 x i8], [16 x i8]* %field175, i32 0, i32 15
+  store i8 %collision23, i8* %vf_ptr23
+  %field176 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 522, i16* %field176
+  %running78 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running78, label %op_2DE, label %exit
+
+op_2DE:
+  %running79 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field177 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v5_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field177, i32 0, i32 5
+  %v53 = load i8, i8* %v5_ptr4
+  %running80 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne3 = icmp ne i8 %v53, 42
+  %next7 = select i1 %sne3, i16 742, i16 740
+  %field178 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next7, i16* %field178
+  %field179 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field179, i32 0, i32 7
+  store i8 %v53, i8* %v7_ptr
+  %field180 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field180
+  %running81 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running81, label %skip_2E2, label %exit
+
+skip_2E2:
+  br i1 %sne3, label %block_2E6, label %block_2E4
+
+op_2E6:
+  br label %block_2E6
+
+op_2E8:
+  %running84 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running85 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field189 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field189, i32 0, i32 8
+  %v85 = load i8, i8* %v8_ptr7
+  %field190 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 748, i16* %field190
+  %sprite19 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 42
+  %collision25 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v85, i8 11, i8* %sprite19, i8 4)
+  %field191 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 750, i16* %field191
+  %field192 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr16 = getelementptr inbounds [16 x i8], [16 x i8]* %field192, i32 0, i32 11
+  store i8 11, i8* %vb_ptr16
+  %field193 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr25 = getelementptr inbounds [16 x i8], [16 x i8]* %field193, i32 0, i32 15
+  store i8 %collision25, i8* %vf_ptr25
+  %field194 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 554, i16* %field194
+  %running86 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running86, label %op_2EE, label %exit
+
+op_2EE:
+  %running87 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field195 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field195, i32 0, i32 9
+  %v95 = load i8, i8* %v9_ptr7
+  %field196 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 752, i16* %field196
+  %sprite20 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 14
+  %collision26 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v95, i8 11, i8* %sprite20, i8 4)
+  %field197 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 754, i16* %field197
+  %field198 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr26 = getelementptr inbounds [16 x i8], [16 x i8]* %field198, i32 0, i32 15
+  store i8 %collision26, i8* %vf_ptr26
+  %field199 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 526, i16* %field199
+  %running88 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running88, label %op_2F2, label %exit
+
+op_2F2:
+  %running89 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running90 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %or = or i8 42, 11
+  %running91 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne4 = icmp ne i8 %or, 43
+  %next8 = select i1 %sne4, i16 764, i16 762
+  %field200 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next8, i16* %field200
+  %field201 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field201, i32 0, i32 7
+  store i8 %or, i8* %v7_ptr1
+  %field202 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field202
+  %running92 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running92, label %skip_2F8, label %exit
+
+skip_2F8:
+  br i1 %sne4, label %block_2FC, label %block_2FA
+
+op_2FC:
+  br label %block_2FC
+
+op_2FE:
+  %running95 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running96 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field211 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field211, i32 0, i32 8
+  %v86 = load i8, i8* %v8_ptr8
+  %field212 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 770, i16* %field212
+  %sprite21 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 42
+  %collision28 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v86, i8 16, i8* %sprite21, i8 4)
+  %field213 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 772, i16* %field213
+  %field214 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr18 = getelementptr inbounds [16 x i8], [16 x i8]* %field214, i32 0, i32 11
+  store i8 16, i8* %vb_ptr18
+  %field215 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr28 = getelementptr inbounds [16 x i8], [16 x i8]* %field215, i32 0, i32 15
+  store i8 %collision28, i8* %vf_ptr28
+  %field216 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 554, i16* %field216
+  %running97 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running97, label %op_304, label %exit
+
+op_304:
+  %running98 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field217 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field217, i32 0, i32 9
+  %v96 = load i8, i8* %v9_ptr8
+  %field218 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 774, i16* %field218
+  %sprite22 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 18
+  %collision29 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v96, i8 16, i8* %sprite22, i8 4)
+  %field219 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 776, i16* %field219
+  %field220 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr29 = getelementptr inbounds [16 x i8], [16 x i8]* %field220, i32 0, i32 15
+  store i8 %collision29, i8* %vf_ptr29
+  %field221 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 530, i16* %field221
+  %running99 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running99, label %op_308, label %exit
+
+op_308:
+  %running100 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running101 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running102 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %and = and i8 31, 120
+  %running103 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne5 = icmp ne i8 %and, 24
+  %next9 = select i1 %sne5, i16 788, i16 786
+  %field222 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next9, i16* %field222
+  %field223 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field223, i32 0, i32 6
+  store i8 120, i8* %v6_ptr5
+  %field224 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr2 = getelementptr inbounds [16 x i8], [16 x i8]* %field224, i32 0, i32 7
+  store i8 %and, i8* %v7_ptr2
+  %field225 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field225
+  %running104 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running104, label %skip_310, label %exit
+
+skip_310:
+  br i1 %sne5, label %block_314, label %block_312
+
+op_314:
+  br label %block_314
+
+op_316:
+  %running107 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running108 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field234 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr9 = getelementptr inbounds [16 x i8], [16 x i8]* %field234, i32 0, i32 8
+  %v87 = load i8, i8* %v8_ptr9
+  %field235 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 794, i16* %field235
+  %sprite23 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 42
+  %collision31 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v87, i8 21, i8* %sprite23, i8 4)
+  %field236 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 796, i16* %field236
+  %field237 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr20 = getelementptr inbounds [16 x i8], [16 x i8]* %field237, i32 0, i32 11
+  store i8 21, i8* %vb_ptr20
+  %field238 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr31 = getelementptr inbounds [16 x i8], [16 x i8]* %field238, i32 0, i32 15
+  store i8 %collision31, i8* %vf_ptr31
+  %field239 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 554, i16* %field239
+  %running109 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running109, label %op_31C, label %exit
+
+op_31C:
+  %running110 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field240 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr9 = getelementptr inbounds [16 x i8], [16 x i8]* %field240, i32 0, i32 9
+  %v97 = load i8, i8* %v9_ptr9
+  %field241 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 798, i16* %field241
+  %sprite24 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 22
+  %collision32 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v97, i8 21, i8* %sprite24, i8 4)
+  %field242 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 800, i16* %field242
+  %field243 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr32 = getelementptr inbounds [16 x i8], [16 x i8]* %field243, i32 0, i32 15
+  store i8 %collision32, i8* %vf_ptr32
+  %field244 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 534, i16* %field244
+  %running111 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running111, label %op_320, label %exit
+
+op_320:
+  %running112 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running113 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running114 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %xor = xor i8 31, 120
+  %running115 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne6 = icmp ne i8 %xor, 103
+  %next10 = select i1 %sne6, i16 812, i16 810
+  %field245 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next10, i16* %field245
+  %field246 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr6 = getelementptr inbounds [16 x i8], [16 x i8]* %field246, i32 0, i32 6
+  store i8 120, i8* %v6_ptr6
+  %field247 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr3 = getelementptr inbounds [16 x i8], [16 x i8]* %field247, i32 0, i32 7
+  store i8 %xor, i8* %v7_ptr3
+  %field248 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field248
+  %running116 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running116, label %skip_328, label %exit
+
+skip_328:
+  br i1 %sne6, label %block_32C, label %block_32A
+
+op_32C:
+  br label %block_32C
+
+op_32E:
+  %running119 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running120 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field257 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr10 = getelementptr inbounds [16 x i8], [16 x i8]* %field257, i32 0, i32 8
+  %v88 = load i8, i8* %v8_ptr10
+  %field258 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 818, i16* %field258
+  %sprite25 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 42
+  %collision34 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v88, i8 26, i8* %sprite25, i8 4)
+  %field259 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 820, i16* %field259
+  %field260 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr22 = getelementptr inbounds [16 x i8], [16 x i8]* %field260, i32 0, i32 11
+  store i8 26, i8* %vb_ptr22
+  %field261 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr34 = getelementptr inbounds [16 x i8], [16 x i8]* %field261, i32 0, i32 15
+  store i8 %collision34, i8* %vf_ptr34
+  %field262 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 554, i16* %field262
+  %running121 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running121, label %op_334, label %exit
+
+op_334:
+  %running122 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field263 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr10 = getelementptr inbounds [16 x i8], [16 x i8]* %field263, i32 0, i32 9
+  %v98 = load i8, i8* %v9_ptr10
+  %field264 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 822, i16* %field264
+  %sprite26 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 26
+  %collision35 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v98, i8 26, i8* %sprite26, i8 4)
+  %field265 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 824, i16* %field265
+  %field266 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr35 = getelementptr inbounds [16 x i8], [16 x i8]* %field266, i32 0, i32 15
+  store i8 %collision35, i8* %vf_ptr35
+  %field267 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 538, i16* %field267
+  %running123 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running123, label %op_338, label %exit
+
+op_338:
+  %running124 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running125 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running126 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %add1 = add i8 -116, -116
+  %carry = icmp ult i8 %add1, -116
+  %vf = zext i1 %carry to i8
+  %running127 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne7 = icmp ne i8 %add1, 24
+  %next11 = select i1 %sne7, i16 836, i16 834
+  %field268 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next11, i16* %field268
+  %field269 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr7 = getelementptr inbounds [16 x i8], [16 x i8]* %field269, i32 0, i32 6
+  store i8 -116, i8* %v6_ptr7
+  %field270 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr4 = getelementptr inbounds [16 x i8], [16 x i8]* %field270, i32 0, i32 7
+  store i8 %add1, i8* %v7_ptr4
+  %field271 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr36 = getelementptr inbounds [16 x i8], [16 x i8]* %field271, i32 0, i32 15
+  store i8 %vf, i8* %vf_ptr36
+  %field272 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field272
+  %running128 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running128, label %skip_340, label %exit
+
+skip_340:
+  br i1 %sne7, label %block_344, label %block_342
+
+op_344:
+  br label %block_344
+
+op_346:
+  %running131 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running132 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running133 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running134 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running135 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field281 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 848, i16* %field281
+  %sprite27 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 42
+  %collision37 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 44, i8 1, i8* %sprite27, i8 4)
+  %field282 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 850, i16* %field282
+  %field283 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr11 = getelementptr inbounds [16 x i8], [16 x i8]* %field283, i32 0, i32 8
+  store i8 44, i8* %v8_ptr11
+  %field284 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr11 = getelementptr inbounds [16 x i8], [16 x i8]* %field284, i32 0, i32 9
+  store i8 48, i8* %v9_ptr11
+  %field285 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr14 = getelementptr inbounds [16 x i8], [16 x i8]* %field285, i32 0, i32 10
+  store i8 52, i8* %va_ptr14
+  %field286 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr24 = getelementptr inbounds [16 x i8], [16 x i8]* %field286, i32 0, i32 11
+  store i8 1, i8* %vb_ptr24
+  %field287 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr38 = getelementptr inbounds [16 x i8], [16 x i8]* %field287, i32 0, i32 15
+  store i8 %collision37, i8* %vf_ptr38
+  %field288 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 554, i16* %field288
+  %running136 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running136, label %op_352, label %exit
+
+op_352:
+  %running137 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field289 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 852, i16* %field289
+  %sprite28 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 30
+  %collision38 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 48, i8 1, i8* %sprite28, i8 4)
+  %field290 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 854, i16* %field290
+  %field291 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr39 = getelementptr inbounds [16 x i8], [16 x i8]* %field291, i32 0, i32 15
+  store i8 %collision38, i8* %vf_ptr39
+  %field292 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 542, i16* %field292
+  %running138 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running138, label %op_356, label %exit
+
+op_356:
+  %running139 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running140 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running141 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %no_borrow = icmp ugt i8 120, -116
+  %vf1 = zext i1 %no_borrow to i8
+  %sub = sub i8 120, -116
+  %running142 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne8 = icmp ne i8 %sub, -20
+  %next12 = select i1 %sne8, i16 866, i16 864
+  %field293 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next12, i16* %field293
+  %field294 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr8 = getelementptr inbounds [16 x i8], [16 x i8]* %field294, i32 0, i32 6
+  store i8 -116, i8* %v6_ptr8
+  %field295 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v7_ptr5 = getelementptr inbounds [16 x i8], [16 x i8]* %field295, i32 0, i32 7
+  store i8 %sub, i8* %v7_ptr5
+  %field296 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr40 = getelementptr inbounds [16 x i8], [16 x i8]* %field296, i32 0, i32 15
+  store i8 %vf1, i8* %vf_ptr40
+  %field297 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field297
+  %running143 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running143, label %skip_35E, label %exit
+
+skip_35E:
+  br i1 %sne8, label %block_362, label %block_360
+
+op_362:
+  br label %block_362
+
+op_364:
+  %running146 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running147 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field306 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr12 = getelementptr inbounds [16 x i8], [16 x i8]* %field306, i32 0, i32 8
+  %v89 = load i8, i8* %v8_ptr12
+  %field307 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 872, i16* %field307
+  %sprite29 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 42
+  %collision40 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v89, i8 6, i8* %sprite29, i8 4)
+  %field308 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 874, i16* %field308
+  %field309 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr26 = getelementptr inbounds [16 x i8], [16 x i8]* %field309, i32 0, i32 11
+  store i8 6, i8* %vb_ptr26
+  %field310 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr42 = getelementptr inbounds [16 x i8], [16 x i8]* %field310, i32 0, i32 15
+  store i8 %collision40, i8* %vf_ptr42
+  %field311 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 554, i16* %field311
+  %running148 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running148, label %op_36A, label %exit
+
+op_36A:
+  %running149 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field312 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr12 = getelementptr inbounds [16 x i8], [16 x i8]* %field312, i32 0, i32 9
+  %v99 = load i8, i8* %v9_ptr12
+  %field313 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 876, i16* %field313
+  %sprite30 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 34
+  %collision41 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v99, i8 6, i8* %sprite30, i8 4)
+  %field314 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 878, i16* %field314
+  %field315 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr43 = getelementptr inbounds [16 x i8], [16 x i8]* %field315, i32 0, i32 15
+  store i8 %collision41, i8* %vf_ptr43
+  %field316 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 546, i16* %field316
+  %running150 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running150, label %op_36E, label %exit
+
+op_36E:
+  %running151 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running152 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %msb = lshr i8 -32, 7
+  %shl = shl i8 -32, 1
+  %running153 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne9 = icmp ne i8 %shl, -64
+  %next13 = select i1 %sne9, i16 888, i16 886
+  %field317 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next13, i16* %field317
+  %field318 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr9 = getelementptr inbounds [16 x i8], [16 x i8]* %field318, i32 0, i32 6
+  store i8 %shl, i8* %v6_ptr9
+  %field319 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr44 = getelementptr inbounds [16 x i8], [16 x i8]* %field319, i32 0, i32 15
+  store i8 %msb, i8* %vf_ptr44
+  %field320 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field320
+  %running154 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running154, label %skip_374, label %exit
+
+skip_374:
+  br i1 %sne9, label %block_378, label %block_376
+
+op_378:
+  br label %block_378
+
+op_37A:
+  %running157 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running158 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field329 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr13 = getelementptr inbounds [16 x i8], [16 x i8]* %field329, i32 0, i32 8
+  %v810 = load i8, i8* %v8_ptr13
+  %field330 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 894, i16* %field330
+  %sprite31 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 42
+  %collision43 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v810, i8 11, i8* %sprite31, i8 4)
+  %field331 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 896, i16* %field331
+  %field332 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr28 = getelementptr inbounds [16 x i8], [16 x i8]* %field332, i32 0, i32 11
+  store i8 11, i8* %vb_ptr28
+  %field333 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr46 = getelementptr inbounds [16 x i8], [16 x i8]* %field333, i32 0, i32 15
+  store i8 %collision43, i8* %vf_ptr46
+  %field334 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 554, i16* %field334
+  %running159 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running159, label %op_380, label %exit
+
+op_380:
+  %running160 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field335 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr13 = getelementptr inbounds [16 x i8], [16 x i8]* %field335, i32 0, i32 9
+  %v910 = load i8, i8* %v9_ptr13
+  %field336 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 898, i16* %field336
+  %sprite32 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 54
+  %collision44 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v910, i8 11, i8* %sprite32, i8 4)
+  %field337 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 900, i16* %field337
+  %field338 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr47 = getelementptr inbounds [16 x i8], [16 x i8]* %field338, i32 0, i32 15
+  store i8 %collision44, i8* %vf_ptr47
+  %field339 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 566, i16* %field339
+  %running161 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running161, label %op_384, label %exit
+
+op_384:
+  %running162 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running163 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %lsb = and i8 15, 1
+  %shr = lshr i8 15, 1
+  %running164 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne10 = icmp ne i8 %shr, 7
+  %next14 = select i1 %sne10, i16 910, i16 908
+  %field340 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next14, i16* %field340
+  %field341 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr10 = getelementptr inbounds [16 x i8], [16 x i8]* %field341, i32 0, i32 6
+  store i8 %shr, i8* %v6_ptr10
+  %field342 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr48 = getelementptr inbounds [16 x i8], [16 x i8]* %field342, i32 0, i32 15
+  store i8 %lsb, i8* %vf_ptr48
+  %field343 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field343
+  %running165 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running165, label %skip_38A, label %exit
+
+skip_38A:
+  br i1 %sne10, label %block_38E, label %block_38C
+
+op_38E:
+  br label %block_38E
+
+op_390:
+  %running168 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running169 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field352 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr14 = getelementptr inbounds [16 x i8], [16 x i8]* %field352, i32 0, i32 8
+  %v811 = load i8, i8* %v8_ptr14
+  %field353 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 916, i16* %field353
+  %sprite33 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 58
+  %collision46 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v811, i8 16, i8* %sprite33, i8 4)
+  %field354 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 918, i16* %field354
+  %field355 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr30 = getelementptr inbounds [16 x i8], [16 x i8]* %field355, i32 0, i32 11
+  store i8 16, i8* %vb_ptr30
+  %field356 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr50 = getelementptr inbounds [16 x i8], [16 x i8]* %field356, i32 0, i32 15
+  store i8 %collision46, i8* %vf_ptr50
+  %field357 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 570, i16* %field357
+  %running170 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running170, label %op_396, label %exit
+
+op_396:
+  %running171 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field358 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr14 = getelementptr inbounds [16 x i8], [16 x i8]* %field358, i32 0, i32 9
+  %v911 = load i8, i8* %v9_ptr14
+  %field359 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 920, i16* %field359
+  %sprite34 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 30
+  %collision47 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v911, i8 16, i8* %sprite34, i8 4)
+  %field360 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 922, i16* %field360
+  %field361 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr51 = getelementptr inbounds [16 x i8], [16 x i8]* %field361, i32 0, i32 15
+  store i8 %collision47, i8* %vf_ptr51
+  %field362 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 542, i16* %field362
+  %running172 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running172, label %op_39A, label %exit
+
+op_39A:
+  %running173 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running174 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running175 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %i26 = zext i16 1000 to i32
+  %field363 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr = add i32 %i26, 0
+  %mem_ptr = getelementptr inbounds [4096 x i8], [4096 x i8]* %field363, i32 0, i32 %addr
+  store i8 0, i8* %mem_ptr
+  %field364 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr1 = add i32 %i26, 1
+  %mem_ptr1 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field364, i32 0, i32 %addr1
+  store i8 48, i8* %mem_ptr1
+  %running176 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running177 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %i27 = zext i16 1001 to i32
+  %field365 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr2 = add i32 %i27, 0
+  %mem_ptr2 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field365, i32 0, i32 %addr2
+  %mem = load i8, i8* %mem_ptr2
+  %running178 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running179 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %sne11 = icmp ne i8 %mem, 48
+  %next15 = select i1 %sne11, i16 940, i16 938
+  %field366 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next15, i16* %field366
+  %field367 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field367, i32 0, i32 0
+  store i8 %mem, i8* %v0_ptr
+  %field368 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field368, i32 0, i32 1
+  store i8 48, i8* %v1_ptr
+  %field369 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 518, i16* %field369
+  %running180 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running180, label %skip_3A8, label %exit
+
+skip_3A8:
+  br i1 %sne11, label %block_3AC, label %block_3AA
+
+op_3AC:
+  br label %block_3AC
+
+op_3AE:
+  %running183 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running184 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field378 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr15 = getelementptr inbounds [16 x i8], [16 x i8]* %field378, i32 0, i32 8
+  %v812 = load i8, i8* %v8_ptr15
+  %field379 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 946, i16* %field379
+  %sprite35 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 58
+  %collision49 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v812, i8 21, i8* %sprite35, i8 4)
+  %field380 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 948, i16* %field380
+  %field381 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr32 = getelementptr inbounds [16 x i8], [16 x i8]* %field381, i32 0, i32 11
+  store i8 21, i8* %vb_ptr32
+  %field382 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr53 = getelementptr inbounds [16 x i8], [16 x i8]* %field382, i32 0, i32 15
+  store i8 %collision49, i8* %vf_ptr53
+  %field383 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 570, i16* %field383
+  %running185 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running185, label %op_3B4, label %exit
+
+op_3B4:
+  %running186 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field384 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr15 = getelementptr inbounds [16 x i8], [16 x i8]* %field384, i32 0, i32 9
+  %v912 = load i8, i8* %v9_ptr15
+  %field385 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 950, i16* %field385
+  %sprite36 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 22
+  %collision50 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v912, i8 21, i8* %sprite36, i8 4)
+  %field386 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 952, i16* %field386
+  %field387 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr54 = getelementptr inbounds [16 x i8], [16 x i8]* %field387, i32 0, i32 15
+  store i8 %collision50, i8* %vf_ptr54
+  %field388 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 534, i16* %field388
+  %running187 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running187, label %op_3B8, label %exit
+
+op_3B8:
+  %running188 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running189 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %i30 = zext i16 1000 to i32
+  %hundreds = udiv i8 -119, 100
+  %tens = udiv i8 -119, 10
+  %tens1 = urem i8 %tens, 10
+  %ones = urem i8 -119, 10
+  %field389 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr3 = add i32 %i30, 0
+  %mem_ptr3 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field389, i32 0, i32 %addr3
+  store i8 %hundreds, i8* %mem_ptr3
+  %field390 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr4 = add i32 %i30, 1
+  %mem_ptr4 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field390, i32 0, i32 %addr4
+  store i8 %tens1, i8* %mem_ptr4
+  %field391 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr5 = add i32 %i30, 2
+  %mem_ptr5 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field391, i32 0, i32 %addr5
+  store i8 %ones, i8* %mem_ptr5
+  %running190 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %i31 = zext i16 1000 to i32
+  %field392 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr6 = add i32 %i31, 0
+  %mem_ptr6 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field392, i32 0, i32 %addr6
+  %mem1 = load i8, i8* %mem_ptr6
+  %field393 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr7 = add i32 %i31, 1
+  %mem_ptr7 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field393, i32 0, i32 %addr7
+  %mem2 = load i8, i8* %mem_ptr7
+  %field394 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 7
+  %addr8 = add i32 %i31, 2
+  %mem_ptr8 = getelementptr inbounds [4096 x i8], [4096 x i8]* %field394, i32 0, i32 %addr8
+  %mem3 = load i8, i8* %mem_ptr8
+  %running191 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running192 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %se2 = icmp eq i8 %mem1, 1
+  %next16 = select i1 %se2, i16 966, i16 964
+  %field395 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next16, i16* %field395
+  %field396 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v0_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field396, i32 0, i32 0
+  store i8 %mem1, i8* %v0_ptr1
+  %field397 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v1_ptr1 = getelementptr inbounds [16 x i8], [16 x i8]* %field397, i32 0, i32 1
+  store i8 %mem2, i8* %v1_ptr1
+  %field398 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v2_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field398, i32 0, i32 2
+  store i8 %mem3, i8* %v2_ptr
+  %field399 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v6_ptr11 = getelementptr inbounds [16 x i8], [16 x i8]* %field399, i32 0, i32 6
+  store i8 -119, i8* %v6_ptr11
+  %field400 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field400
+  %running193 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running193, label %skip_3C2, label %exit
+
+skip_3C2:
+  br i1 %se2, label %block_3C6, label %block_3C4
+
+op_3C6:
+  br label %block_3C6
+
+skip_3C6:
+  br i1 %se3, label %block_3CA, label %block_3C8
+
+op_3CA:
+  br label %block_3CA
+
+skip_3CA:
+  br i1 %se4, label %block_3CE, label %block_3CC
+
+op_3CE:
+  br label %block_3CE
+
+op_3D0:
+  %running200 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %running201 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field417 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v8_ptr16 = getelementptr inbounds [16 x i8], [16 x i8]* %field417, i32 0, i32 8
+  %v813 = load i8, i8* %v8_ptr16
+  %field418 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 980, i16* %field418
+  %sprite37 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 14
+  %collision52 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v813, i8 26, i8* %sprite37, i8 4)
+  %field419 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 982, i16* %field419
+  %field420 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr34 = getelementptr inbounds [16 x i8], [16 x i8]* %field420, i32 0, i32 11
+  store i8 26, i8* %vb_ptr34
+  %field421 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr56 = getelementptr inbounds [16 x i8], [16 x i8]* %field421, i32 0, i32 15
+  store i8 %collision52, i8* %vf_ptr56
+  %field422 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 526, i16* %field422
+  %running202 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running202, label %op_3D6, label %exit
+
+op_3D6:
+  %running203 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field423 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %v9_ptr16 = getelementptr inbounds [16 x i8], [16 x i8]* %field423, i32 0, i32 9
+  %v913 = load i8, i8* %v9_ptr16
+  %field424 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 984, i16* %field424
+  %sprite38 = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 62
+  %collision53 = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %v913, i8 26, i8* %sprite38, i8 4)
+  %field425 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 986, i16* %field425
+  %field426 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr57 = getelementptr inbounds [16 x i8], [16 x i8]* %field426, i32 0, i32 15
+  store i8 %collision53, i8* %vf_ptr57
+  %field427 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 574, i16* %field427
+  %running204 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running204, label %op_3DA, label %exit
+
+op_3DA:
+  %field428 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 584, i16* %field428
+  %running205 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running205, label %block_248, label %exit
+}
+
+define internal i1 @chip8_sub_242(%chip8.state* %state) {
+entry:
+  br label %block_242
+
+leave:
+  ret i1 false
+
+unresolved:
+  call void @chip8_rt_unresolved(%chip8.state* %state)
+  br label %leave
+
+returned:
+  ret i1 true
+
+block_242:
+  %running = call i1 @chip8_rt_tick(%chip8.state* %state, i1 false)
+  %field = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %va_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field, i32 0, i32 10
+  %va = load i8, i8* %va_ptr
+  %field1 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vb_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field1, i32 0, i32 11
+  %vb = load i8, i8* %vb_ptr
+  %field2 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 580, i16* %field2
+  %sprite = getelementptr inbounds [478 x i8], [478 x i8]* @chip8_rom, i32 0, i32 2
+  %collision = call i8 @chip8_rt_draw_sprite(%chip8.state* %state, i8 %va, i8 %vb, i8* %sprite, i8 4)
+  %field3 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 582, i16* %field3
+  %field4 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 0
+  %vf_ptr = getelementptr inbounds [16 x i8], [16 x i8]* %field4, i32 0, i32 15
+  store i8 %collision, i8* %vf_ptr
+  %field5 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 1
+  store i16 514, i16* %field5
+  %running1 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running1, label %op_246, label %leave
+
+op_246:
+  %field6 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  %sp = load i16, i16* %field6
+  %empty = icmp eq i16 %sp, 0
+  br i1 %empty, label %fault_246, label %ok_246
+
+fault_246:
+  %field7 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 582, i16* %field7
+  call void @chip8_rt_bad_stack(%chip8.state* %state)
+  br label %leave
+
+ok_246:
+  %field8 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 6
+  %slot = getelementptr inbounds [16 x i16], [16 x i16]* %field8, i32 0, i16 %sp
+  %ret_addr = load i16, i16* %slot
+  %sp1 = sub i16 %sp, 1
+  %field9 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 3
+  store i16 %sp1, i16* %field9
+  %next = add i16 %ret_addr, 2
+  %field10 = getelementptr inbounds %chip8.state, %chip8.state* %state, i32 0, i32 2
+  store i16 %next, i16* %field10
+  %running2 = call i1 @chip8_rt_tick(%chip8.state* %state, i1 true)
+  br i1 %running2, label %returned, label %leave
+}
+
+define i32 @main() {
+entry:
+  %rom = bitcast [478 x i8]* @chip8_rom to i8*
+  %state = call %chip8.state* @chip8_rt_init(i8* %rom, i32 478)
+  %blocks = bitcast [80 x i16]* @chip8_blocks to i16*
+  call void @chip8_rt_run(%chip8.state* %state, void (%chip8.state*)* @chip8_run, i16* %blocks, i32 40)
+  %code = call i32 @chip8_rt_exit(%chip8.state* %state)
+  ret i32 %code
+}
+
+declare %chip8.state* @chip8_rt_init(i8*, i32)
+declare void @chip8_rt_run(%chip8.state*, void (%chip8.state*)*, i16*, i32)
+declare i32 @chip8_rt_exit(%chip8.state*)
+declare i1 @chip8_rt_tick(%chip8.state*, i1)
+declare void @chip8_rt_cls(%chip8.state*)
+declare i8 @chip8_rt_draw(%chip8.state*, i8, i8, i8)
+declare i8 @chip8_rt_draw_sprite(%chip8.state*, i8, i8, i8*, i8)
+declare i1 @chip8_rt_key_pressed(%chip8.state*, i8)
+declare i8 @chip8_rt_wait_key(%chip8.state*)
+declare i8 @chip8_rt_random(%chip8.state*)
+declare void @chip8_rt_bad_address(%chip8.state*, i16)
+declare void @chip8_rt_bad_stack(%chip8.state*)
+declare void @chip8_rt_unresolved(%chip8.state*)
+declare i1 @chip8_rt_code_written(%chip8.state*, i16, i16)